@@ -0,0 +1,24 @@
+import json
+
+def split_paired(item):
+    """Older results held both arms on one line; split them like the Rust reader does."""
+    base = {k: v for k, v in item.items() if not k.startswith(('with_tools', 'without_tools'))}
+    for arm, prefix in [('tools', 'with_tools'), ('no-tools', 'without_tools')]:
+        yield {**base, 'arm': arm, 'answer': item[prefix], 'duration_ms': item[f'{prefix}_duration_ms'],
+               'length': item[f'{prefix}_length'], 'correct': item.get(f'{prefix}_correct')}
+
+def load_results(file):
+    results = []
+    for line in open(file):
+        if not line.strip():
+            continue
+        item = json.loads(line)
+        if 'answer' not in item and 'with_tools' in item:
+            results.extend(split_paired(item))
+        else:
+            results.append(item)
+    return results
+
+def arms(data):
+    """Arm ids in the order they first appear."""
+    return list(dict.fromkeys(item['arm'] for item in data))
@@ -1,94 +1,77 @@
 import json, sys, matplotlib.pyplot as plt, numpy as np
 from pathlib import Path
+from results import load_results, arms
 
 def load_data(file):
-    return [json.loads(line) for line in open(file) if line.strip()]
+    return load_results(file)
 
 def has_correctness(data):
-    return any('with_tools_correct' in item and item['with_tools_correct'] is not None for item in data)
+    return any(item.get('correct') is not None for item in data)
+
+def by_arm(data, arm):
+    return [item for item in data if item['arm'] == arm]
 
 def create_charts(data, output_dir):
     Path(output_dir).mkdir(exist_ok=True)
+    names = arms(data)
+    times = [[item['duration_ms'] for item in by_arm(data, arm)] for arm in names]
+    lengths = [[item['length'] for item in by_arm(data, arm)] for arm in names]
     
-    with_times = [item['with_tools_duration_ms'] for item in data]
-    without_times = [item['without_tools_duration_ms'] for item in data]
-    with_lengths = [item['with_tools_length'] for item in data]
-    without_lengths = [item['without_tools_length'] for item in data]
-    
-    colors = ['#3498db', '#e74c3c'] ## blue, orange
+    colors = plt.cm.tab10.colors[:len(names)]
     
-    plt.figure(figsize=(8, 5))
-    plt.bar(['With Tools', 'Without Tools'], [np.mean(with_times), np.mean(without_times)], color=colors, alpha=0.8)
+    plt.figure(figsize=(max(8, 2 * len(names)), 5))
+    plt.bar(names, [np.mean(t) for t in times], color=colors, alpha=0.8)
     plt.title('Average Response Time'), plt.ylabel('Time (ms)')
     plt.tight_layout(), plt.savefig(f'{output_dir}/time.png', dpi=300), plt.close()
     
-    plt.figure(figsize=(8, 5))
-    plt.bar(['With Tools', 'Without Tools'], [np.mean(with_lengths), np.mean(without_lengths)], color=colors, alpha=0.8)
+    plt.figure(figsize=(max(8, 2 * len(names)), 5))
+    plt.bar(names, [np.mean(l) for l in lengths], color=colors, alpha=0.8)
     plt.title('Average Response Length'), plt.ylabel('Characters')
     plt.tight_layout(), plt.savefig(f'{output_dir}/length.png', dpi=300), plt.close()
     
     plt.figure(figsize=(12, 5))
-    x = range(1, len(data) + 1)
-    plt.plot(x, with_times, color=colors[0], label='With Tools', linewidth=2)
-    plt.plot(x, without_times, color=colors[1], label='Without Tools', linewidth=2)
+    for arm, t, color in zip(names, times, colors):
+        plt.plot(range(1, len(t) + 1), t, color=color, label=arm, linewidth=2)
     plt.title('Time per Prompt'), plt.xlabel('Prompt'), plt.ylabel('Time (ms)'), plt.legend(), plt.grid(alpha=0.3)
     plt.tight_layout(), plt.savefig(f'{output_dir}/time_per_prompt.png', dpi=300), plt.close()
     
     plt.figure(figsize=(12, 5))
-    plt.plot(x, with_lengths, color=colors[0], label='With Tools', linewidth=2)
-    plt.plot(x, without_lengths, color=colors[1], label='Without Tools', linewidth=2)
+    for arm, l, color in zip(names, lengths, colors):
+        plt.plot(range(1, len(l) + 1), l, color=color, label=arm, linewidth=2)
     plt.title('Length per Prompt'), plt.xlabel('Prompt'), plt.ylabel('Characters'), plt.legend(), plt.grid(alpha=0.3)
     plt.tight_layout(), plt.savefig(f'{output_dir}/length_per_prompt.png', dpi=300), plt.close()
     
     if has_correctness(data):
-        with_correct = sum(1 for item in data if item.get('with_tools_correct'))
-        without_correct = sum(1 for item in data if item.get('without_tools_correct'))
-        plt.figure(figsize=(8, 5))
-        plt.bar(['With Tools', 'Without Tools'], [with_correct/len(data)*100, without_correct/len(data)*100], color=colors, alpha=0.8)
+        accuracy = [sum(1 for item in by_arm(data, arm) if item.get('correct')) / len(by_arm(data, arm)) * 100 for arm in names]
+        plt.figure(figsize=(max(8, 2 * len(names)), 5))
+        plt.bar(names, accuracy, color=colors, alpha=0.8)
         plt.title('Accuracy'), plt.ylabel('Percentage'), plt.ylim(0, 100)
         plt.tight_layout(), plt.savefig(f'{output_dir}/accuracy.png', dpi=300), plt.close()
         
-        groups = [
-            [item['with_tools_length'] for item in data if item.get('with_tools_correct') == True],
-            [item['with_tools_length'] for item in data if item.get('with_tools_correct') == False],
-            [item['without_tools_length'] for item in data if item.get('without_tools_correct') == True],
-            [item['without_tools_length'] for item in data if item.get('without_tools_correct') == False]
-        ]
-        avgs = [np.mean(g) if g else 0 for g in groups]
-        plt.figure(figsize=(10, 5))
-        plt.bar(['With\n(Correct)', 'With\n(Wrong)', 'Without\n(Correct)', 'Without\n(Wrong)'], 
-                avgs, color=['#27ae60', '#3498db', '#f39c12', '#e74c3c'], alpha=0.8)
+        labels, avgs = [], []
+        for arm in names:
+            for label, want in [('Correct', True), ('Wrong', False)]:
+                group = [item['length'] for item in by_arm(data, arm) if item.get('correct') == want]
+                labels.append(f'{arm}\n({label})')
+                avgs.append(np.mean(group) if group else 0)
+        plt.figure(figsize=(max(10, 2.5 * len(names)), 5))
+        plt.bar(labels, avgs, color=[c for c in colors for _ in range(2)], alpha=0.8)
         plt.title('Length by Correctness'), plt.ylabel('Characters')
         plt.tight_layout(), plt.savefig(f'{output_dir}/length_by_correctness.png', dpi=300), plt.close()
 
 def print_stats(data):
-    with_times = [item['with_tools_duration_ms'] for item in data]
-    without_times = [item['without_tools_duration_ms'] for item in data]
-    with_lengths = [item['with_tools_length'] for item in data]
-    without_lengths = [item['without_tools_length'] for item in data]
-    
-    print(f"Analyzed {len(data)} test cases")
-    print(f"Time:   {np.mean(with_times):.0f}ms vs {np.mean(without_times):.0f}ms")
-    print(f"Length: {np.mean(with_lengths):.0f} vs {np.mean(without_lengths):.0f} chars")
-    
-    time_diff = (np.mean(without_times) - np.mean(with_times))/np.mean(without_times)*100
-    length_diff = (np.mean(without_lengths) - np.mean(with_lengths))/np.mean(without_lengths)*100
-    print(f"Tools: {time_diff:+.0f}% time, {length_diff:+.0f}% length")
-    
-    if has_correctness(data):
-        with_correct = sum(1 for item in data if item.get('with_tools_correct'))
-        without_correct = sum(1 for item in data if item.get('without_tools_correct'))
-        print(f"Accuracy: {with_correct/len(data)*100:.1f}% vs {without_correct/len(data)*100:.1f}%")
-        
-        with_correct_items = [item for item in data if item.get('with_tools_correct') == True]
-        with_wrong_items = [item for item in data if item.get('with_tools_correct') == False]
-        without_correct_items = [item for item in data if item.get('without_tools_correct') == True]
-        without_wrong_items = [item for item in data if item.get('without_tools_correct') == False]
-        
-        if with_correct_items and with_wrong_items:
-            print(f"With Tools - Correct: {np.mean([item['with_tools_length'] for item in with_correct_items]):.0f} chars, Wrong: {np.mean([item['with_tools_length'] for item in with_wrong_items]):.0f} chars")
-        if without_correct_items and without_wrong_items:
-            print(f"Without Tools - Correct: {np.mean([item['without_tools_length'] for item in without_correct_items]):.0f} chars, Wrong: {np.mean([item['without_tools_length'] for item in without_wrong_items]):.0f} chars")
+    names = arms(data)
+    print(f"Analyzed {len({item['prompt'] for item in data})} test cases across {len(names)} arms")
+    for arm in names:
+        items = by_arm(data, arm)
+        line = f"{arm}: {np.mean([i['duration_ms'] for i in items]):.0f}ms, {np.mean([i['length'] for i in items]):.0f} chars"
+        if has_correctness(data):
+            correct = [i for i in items if i.get('correct') == True]
+            wrong = [i for i in items if i.get('correct') == False]
+            line += f", accuracy {len(correct)/len(items)*100:.1f}%"
+            if correct and wrong:
+                line += f" (correct {np.mean([i['length'] for i in correct]):.0f} chars, wrong {np.mean([i['length'] for i in wrong]):.0f} chars)"
+        print(line)
 
 def main():
     if len(sys.argv) < 2:
@@ -101,14 +84,6 @@ def main():
     create_charts(data, output_dir)
     print_stats(data)
     
-    if not has_correctness(data):
-        scored_file = Path(sys.argv[1]).parent / f"{Path(sys.argv[1]).stem}_with_correctness{Path(sys.argv[1]).suffix}"
-        with open(scored_file, 'w') as f:
-            for item in data:
-                item['with_tools_correct'] = None
-                item['without_tools_correct'] = None
-                f.write(json.dumps(item) + '\n')
-    
     print(f"Charts: {output_dir}/")
 
 if __name__ == "__main__":
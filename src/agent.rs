@@ -1,13 +1,35 @@
+use crate::clock::{system_clock, Clock};
 use crate::error::AgentError;
+use crate::rate_limit::RateLimiter;
+use crate::policy::{Approver, PolicyCheck, PolicyDecision, TerminalApprover, ToolPolicy};
 use crate::traits::Model;
+use crate::trace::{RunTrace, ToolCallRecord};
 use crate::types::{Message, ModelResponse, ToolCall};
-use crate::tools::ToolRegistry;
+use crate::tools::{RunContext, ToolRegistry};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 pub struct Agent {
     model: Box<dyn Model>,
     tool_registry: ToolRegistry,
     max_loops: usize,
+    policy: ToolPolicy,
+    approver: Arc<dyn Approver>,
+    clock: Arc<dyn Clock>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+/// Final answer of a run together with its trace, which is kept even when the run fails.
+pub struct AgentRun {
+    pub output: Result<String, AgentError>,
+    pub trace: RunTrace,
+}
+
+impl AgentRun {
+    /// A run that failed before the agent could start.
+    pub fn failed(error: AgentError) -> Self {
+        Self { output: Err(error), trace: RunTrace::new() }
+    }
 }
 
 impl Agent {
@@ -16,6 +38,10 @@ impl Agent {
             model,
             tool_registry,
             max_loops: 5,
+            policy: ToolPolicy::allow_all(),
+            approver: Arc::new(TerminalApprover),
+            clock: system_clock(),
+            rate_limiter: None,
         }
     }
 
@@ -24,11 +50,55 @@ impl Agent {
         self
     }
 
+    pub fn with_policy(mut self, policy: ToolPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_approver(mut self, approver: Arc<dyn Approver>) -> Self {
+        self.approver = approver;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
     pub async fn run_conversation(
+        &self,
+        messages: Vec<Message>,
+        use_tools: bool,
+        verbose: bool,
+    ) -> Result<String, AgentError> {
+        self.run_traced(messages, use_tools, verbose).await.output
+    }
+
+    pub async fn run_traced(
+        &self,
+        messages: Vec<Message>,
+        use_tools: bool,
+        verbose: bool,
+    ) -> AgentRun {
+        let mut trace = RunTrace::new();
+        // tool state such as calculator variables starts fresh with each run
+        let context = RunContext::default();
+        let output = self.run_loop(messages, use_tools, verbose, &mut trace, &context).await;
+        AgentRun { output, trace }
+    }
+
+    async fn run_loop(
         &self,
         mut messages: Vec<Message>,
         use_tools: bool,
         verbose: bool,
+        trace: &mut RunTrace,
+        context: &RunContext,
     ) -> Result<String, AgentError> {
         let tool_specs = if use_tools && !self.tool_registry.is_empty() {
             Some(self.tool_registry.to_tool_specs())
@@ -56,6 +126,10 @@ impl Agent {
                 }
             }
             
+            if let Some(limiter) = &self.rate_limiter {
+                trace.rate_limit_wait += limiter.acquire().await;
+            }
+            trace.round_trips += 1;
             let result = self.model.generate(messages.clone(), tool_specs.clone()).await?;
             
             match result {
@@ -78,7 +152,7 @@ impl Agent {
                         }
                     }
                     
-                    let tool_result_messages = self.execute_tool_calls(tool_calls.clone(), verbose).await?;
+                    let tool_result_messages = self.execute_tool_calls(tool_calls.clone(), verbose, trace, context).await?;
                     
                     messages.push(Message::assistant_with_tool_calls(tool_calls));
                     messages.extend(tool_result_messages);
@@ -95,20 +169,62 @@ impl Agent {
         &self,
         tool_calls: Vec<ToolCall>,
         verbose: bool,
+        trace: &mut RunTrace,
+        context: &RunContext,
     ) -> Result<Vec<Message>, AgentError> {
         let mut result_messages = Vec::new();
         
         for tool_call in tool_calls {
+            let mut record = ToolCallRecord {
+                tool_name: tool_call.function.name.clone(),
+                arguments: tool_call.function.arguments.clone(),
+                result: None,
+                error: None,
+            };
+
             // Parse the JSON arguments string
             let args: HashMap<String, serde_json::Value> = 
-                serde_json::from_str(&tool_call.function.arguments)
-                    .map_err(|e| AgentError::InvalidInputError(
-                        format!("Failed to parse tool arguments: {}", e)
-                    ))?;
+                match serde_json::from_str(&tool_call.function.arguments) {
+                    Ok(args) => args,
+                    Err(e) => {
+                        let error = AgentError::InvalidInputError(format!("Failed to parse tool arguments: {}", e));
+                        record.error = Some(error.to_string());
+                        trace.record_tool_call(self.clock.now(), record);
+                        return Err(error);
+                    }
+                };
+
+            let decision = self.authorize(&tool_call, &args).await;
+            if verbose {
+                eprintln!("[DEBUG]   policy: {} {} ({})",
+                    if decision.allowed { "allowed" } else { "denied" }, decision.tool_name, decision.reason);
+            }
+            let allowed = decision.allowed;
+            let reason = decision.reason.clone();
+            trace.record_decision(self.clock.now(), decision);
+
+            if !allowed {
+                // Let the model see the refusal and carry on without the tool
+                let denied = AgentError::PermissionDenied {
+                    tool_name: tool_call.function.name.clone(),
+                    reason,
+                };
+                record.error = Some(denied.to_string());
+                trace.record_tool_call(self.clock.now(), record);
+                result_messages.push(Message::tool_result(&denied.to_string(), &tool_call.id));
+                continue;
+            }
             
-            let result = self.tool_registry
-                .execute_tool(&tool_call.function.name, args)
-                .await?;
+            let result = match self.tool_registry.execute_tool_in(&tool_call.function.name, args, context).await {
+                Ok(result) => result,
+                Err(e) => {
+                    record.error = Some(e.to_string());
+                    trace.record_tool_call(self.clock.now(), record);
+                    return Err(e);
+                }
+            };
+            record.result = Some(result.clone());
+            trace.record_tool_call(self.clock.now(), record);
             
             if verbose {
                 let result_preview = if result.len() > 40 { 
@@ -124,6 +240,29 @@ impl Agent {
         
         Ok(result_messages)
     }
+
+    async fn authorize(&self, tool_call: &ToolCall, args: &HashMap<String, serde_json::Value>) -> PolicyDecision {
+        let tool_name = tool_call.function.name.clone();
+        let mode = self.policy.mode_for(&tool_name);
+
+        let (allowed, reason) = match self.policy.check(&tool_name, args) {
+            PolicyCheck::Allow => (true, "allowed by policy".to_string()),
+            PolicyCheck::Deny(reason) => (false, reason),
+            PolicyCheck::Ask => match self.approver.approve(&tool_name, args).await {
+                Ok(true) => (true, "approved by user".to_string()),
+                Ok(false) => (false, "rejected by user".to_string()),
+                Err(reason) => (false, reason),
+            },
+        };
+
+        PolicyDecision {
+            tool_name,
+            arguments: tool_call.function.arguments.clone(),
+            mode,
+            allowed,
+            reason,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +270,47 @@ mod tests {
     use super::*;
     use crate::tools::create_default_registry;
     use crate::openai::OpenAIModel;
+    use crate::policy::{PermissionMode, StaticApprover};
+    use crate::types::{ToolFunction, ToolSpec};
+    use std::sync::Mutex;
+
+    // Replays canned responses and remembers what it was sent
+    struct ScriptedModel {
+        responses: Mutex<Vec<ModelResponse>>,
+        seen: Arc<Mutex<Vec<Vec<Message>>>>,
+    }
+
+    impl ScriptedModel {
+        fn new(mut responses: Vec<ModelResponse>) -> (Self, Arc<Mutex<Vec<Vec<Message>>>>) {
+            responses.reverse();
+            let seen = Arc::new(Mutex::new(Vec::new()));
+            (Self { responses: Mutex::new(responses), seen: seen.clone() }, seen)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Model for ScriptedModel {
+        async fn generate(
+            &self,
+            messages: Vec<Message>,
+            _tools: Option<Vec<ToolSpec>>,
+        ) -> Result<ModelResponse, AgentError> {
+            self.seen.lock().unwrap().push(messages);
+            self.responses.lock().unwrap().pop()
+                .ok_or_else(|| AgentError::ModelError("script exhausted".to_string()))
+        }
+    }
+
+    fn calculator_call(expression: &str) -> ModelResponse {
+        ModelResponse::ToolCalls(vec![ToolCall {
+            id: "call_1".to_string(),
+            call_type: "function".to_string(),
+            function: ToolFunction {
+                name: "calculator".to_string(),
+                arguments: format!("{{\"expression\": \"{}\"}}", expression),
+            },
+        }])
+    }
 
     #[tokio::test]
     async fn test_agent_creation() {
@@ -149,4 +329,108 @@ mod tests {
         let agent = Agent::new(model, registry).with_max_loops(10);
         assert_eq!(agent.max_loops, 10);
     }
+
+    #[tokio::test]
+    async fn test_denied_tool_is_reported_to_model_and_traced() {
+        let (model, seen) = ScriptedModel::new(vec![
+            calculator_call("2 + 2"),
+            ModelResponse::Text("done".to_string()),
+        ]);
+        let policy = ToolPolicy::allow_all().with_rule("calculator", PermissionMode::Deny, vec![]);
+        let agent = Agent::new(Box::new(model), create_default_registry()).with_policy(policy);
+
+        let run = agent.run_traced(vec![Message::user("hi")], true, false).await;
+        assert_eq!(run.output.unwrap(), "done");
+
+        let decisions: Vec<_> = run.trace.decisions().collect();
+        assert_eq!(decisions.len(), 1);
+        assert!(!decisions[0].allowed);
+
+        let seen = seen.lock().unwrap();
+        let tool_reply = seen[1].last().unwrap().content.clone().unwrap();
+        assert!(tool_reply.contains("Permission denied"));
+    }
+
+    #[tokio::test]
+    async fn test_ask_mode_uses_approver() {
+        let (model, seen) = ScriptedModel::new(vec![
+            calculator_call("6 * 7"),
+            ModelResponse::Text("42".to_string()),
+        ]);
+        let policy = ToolPolicy::allow_all().with_rule("calculator", PermissionMode::Ask, vec![]);
+        let agent = Agent::new(Box::new(model), create_default_registry())
+            .with_policy(policy)
+            .with_approver(Arc::new(StaticApprover(true)));
+
+        let run = agent.run_traced(vec![Message::user("hi")], true, false).await;
+        assert!(run.output.is_ok());
+
+        let decision = run.trace.decisions().next().unwrap();
+        assert!(decision.allowed);
+        assert_eq!(decision.mode, PermissionMode::Ask);
+        assert_eq!(seen.lock().unwrap()[1].last().unwrap().content.as_deref(), Some("42"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_limit_wait_is_traced() {
+        let (model, _) = ScriptedModel::new(vec![
+            calculator_call("1 + 1"),
+            ModelResponse::Text("2".to_string()),
+        ]);
+        let agent = Agent::new(Box::new(model), create_default_registry())
+            .with_rate_limiter(Arc::new(RateLimiter::per_minute(60)));
+
+        let run = agent.run_traced(vec![Message::user("hi")], true, false).await;
+        assert_eq!(run.output.unwrap(), "2");
+        assert_eq!(run.trace.rate_limit_wait, std::time::Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_and_errors_are_traced() {
+        let (model, _) = ScriptedModel::new(vec![
+            calculator_call("6 * 7"),
+            calculator_call("2 +* 2"),
+        ]);
+        let agent = Agent::new(Box::new(model), create_default_registry());
+
+        let run = agent.run_traced(vec![Message::user("hi")], true, false).await;
+        assert!(run.output.is_err());
+        assert_eq!(run.trace.round_trips, 2);
+
+        let calls: Vec<_> = run.trace.tool_calls().collect();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].result.as_deref(), Some("42"));
+        assert!(calls[0].error.is_none());
+        assert!(calls[1].error.is_some() && calls[1].result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_calculator_variables_last_for_one_run() {
+        let (model, _) = ScriptedModel::new(vec![
+            calculator_call("total = 6 * 7"),
+            calculator_call("total + 1"),
+            ModelResponse::Text("43".to_string()),
+            calculator_call("total"),
+        ]);
+        let agent = Agent::new(Box::new(model), create_default_registry());
+
+        let run = agent.run_traced(vec![Message::user("hi")], true, false).await;
+        assert_eq!(run.output.unwrap(), "43");
+        assert_eq!(run.trace.tool_calls().nth(1).unwrap().result.as_deref(), Some("43"));
+
+        // same agent and registry, new run
+        let run = agent.run_traced(vec![Message::user("again")], true, false).await;
+        let error = run.output.unwrap_err().to_string();
+        assert!(error.contains("Unknown name 'total'"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_max_loops_round_trips() {
+        let (model, _) = ScriptedModel::new(vec![calculator_call("1 + 1"), calculator_call("1 + 1")]);
+        let agent = Agent::new(Box::new(model), create_default_registry()).with_max_loops(2);
+
+        let run = agent.run_traced(vec![Message::user("hi")], true, false).await;
+        assert!(matches!(run.output, Err(AgentError::MaxLoopsExceeded { max_loops: 2 })));
+        assert_eq!((run.trace.round_trips, run.trace.tool_calls().count()), (2, 2));
+    }
 }
@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::benchmark::resolvers;
+use crate::benchmark::scoring::{AnswerType, Tolerance};
+use crate::benchmark::stats::SplitMix64;
+use crate::error::AgentError;
+use crate::tools::create_default_registry;
+use crate::tools::holidays::HolidayCalendar;
+
+pub const DEFAULT_CASES_PATH: &str = "test_cases.yaml";
+
+// Section headers such as `# Calculator math (1-25)`
+static SECTION_HEADER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^\s*#\s*([^#(\n]+?)\s*\((\d+)-(\d+)\)\s*$").unwrap()
+});
+
+/// A case file: `tests` rows are either mappings (`id`, `prompt`, `expected`,
+/// ...) or lists whose columns are named by `schema`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TestCaseFile {
+    #[serde(default)]
+    pub schema: Option<Vec<String>>,
+    pub tests: Vec<serde_yaml::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestCase {
+    /// Stable identifier; `<file stem>-<row>` when the file doesn't give one
+    #[serde(default)]
+    pub id: String,
+    pub prompt: String,
+    pub expected_output: String,
+    /// Original placeholder (e.g. `days_to_christmas (126)`) when `expected_output` was computed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_placeholder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub answer_type: AnswerType,
+    /// Overrides the run's tolerance for this case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<Tolerance>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Tools a good answer may call; others count as unnecessary. `None` when undeclared
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_tools: Option<Vec<String>>,
+}
+
+/// One row as written, before validation.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CaseRecord {
+    id: Option<String>,
+    prompt: String,
+    #[serde(alias = "expected_output")]
+    expected: serde_yaml::Value,
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    answer_type: AnswerType,
+    tolerance: Option<CaseTolerance>,
+    notes: Option<String>,
+    expected_tools: Option<Vec<String>>,
+}
+
+/// `tolerance: 0.5` is absolute; the mapping form sets either bound.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum CaseTolerance {
+    Absolute(f64),
+    Bounds {
+        #[serde(default)]
+        absolute: f64,
+        #[serde(default)]
+        relative: f64,
+    },
+}
+
+impl From<CaseTolerance> for Tolerance {
+    fn from(tolerance: CaseTolerance) -> Self {
+        match tolerance {
+            CaseTolerance::Absolute(absolute) => Tolerance { absolute, relative: 0.0 },
+            CaseTolerance::Bounds { absolute, relative } => Tolerance { absolute, relative },
+        }
+    }
+}
+
+/// Which cases to run, applied after loading: filter, category, shuffle, then limit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CaseSelection {
+    /// Files or directories of `*.yaml`/`*.yml`; empty means `test_cases.yaml`
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// Matched against the prompt
+    #[serde(default, with = "regex_pattern")]
+    pub filter: Option<Regex>,
+    /// Case-insensitive substring of the case's category
+    pub category: Option<String>,
+    pub limit: Option<usize>,
+    /// Shuffle with this seed; `None` keeps file order
+    pub shuffle_seed: Option<u64>,
+}
+
+impl CaseSelection {
+    pub fn apply(&self, cases: Vec<TestCase>) -> Vec<TestCase> {
+        let mut selected: Vec<TestCase> = cases.into_iter()
+            .filter(|case| self.filter.as_ref().is_none_or(|re| re.is_match(&case.prompt)))
+            .filter(|case| match &self.category {
+                None => true,
+                Some(wanted) => case.category.as_ref()
+                    .is_some_and(|c| c.to_lowercase().contains(&wanted.to_lowercase())),
+            })
+            .collect();
+
+        if let Some(seed) = self.shuffle_seed {
+            shuffle(&mut selected, seed);
+        }
+        if let Some(limit) = self.limit {
+            selected.truncate(limit);
+        }
+        selected
+    }
+}
+
+mod regex_pattern {
+    use regex::Regex;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(regex: &Option<Regex>, serializer: S) -> Result<S::Ok, S::Error> {
+        match regex {
+            Some(regex) => serializer.serialize_some(regex.as_str()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Regex>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|pattern| Regex::new(&pattern).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Loads every case file in `selection.paths` (in order) and applies the selection.
+pub async fn load_selected_cases(selection: &CaseSelection, now: DateTime<Utc>, holidays: &HolidayCalendar) -> Result<Vec<TestCase>, AgentError> {
+    let paths = if selection.paths.is_empty() {
+        vec![PathBuf::from(DEFAULT_CASES_PATH)]
+    } else {
+        selection.paths.clone()
+    };
+
+    let mut cases: Vec<TestCase> = Vec::new();
+    let mut seen: HashMap<String, PathBuf> = HashMap::new();
+    for path in expand_case_paths(&paths).await? {
+        for case in load_test_cases(&path, now, holidays).await? {
+            if let Some(first) = seen.insert(case.id.clone(), path.clone()) {
+                return Err(AgentError::InvalidInputError(format!(
+                    "Duplicate test case id '{}' in {} and {}", case.id, first.display(), path.display()
+                )));
+            }
+            cases.push(case);
+        }
+    }
+    Ok(selection.apply(cases))
+}
+
+async fn expand_case_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>, AgentError> {
+    let mut files = Vec::new();
+    for path in paths {
+        if tokio::fs::metadata(path).await.map(|m| m.is_dir()).unwrap_or(false) {
+            let mut entries = tokio::fs::read_dir(path).await?;
+            let mut found = Vec::new();
+            while let Some(entry) = entries.next_entry().await? {
+                let entry_path = entry.path();
+                if matches!(entry_path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")) {
+                    found.push(entry_path);
+                }
+            }
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Loads one case file, resolving placeholder expectations as of `now` with
+/// named days from `holidays`.
+pub async fn load_test_cases(path: &Path, now: DateTime<Utc>, holidays: &HolidayCalendar) -> Result<Vec<TestCase>, AgentError> {
+    let yaml_content = tokio::fs::read_to_string(path).await
+        .map_err(|e| AgentError::InvalidInputError(format!("Failed to read {}: {}", path.display(), e)))?;
+    parse_test_cases(&yaml_content, path, now, holidays)
+}
+
+/// Parses and validates a case file. Every bad row is reported, as
+/// `file:line: problem`, rather than stopping at (or skipping) the first.
+pub fn parse_test_cases(yaml: &str, path: &Path, now: DateTime<Utc>, holidays: &HolidayCalendar) -> Result<Vec<TestCase>, AgentError> {
+    let test_file: TestCaseFile = serde_yaml::from_str(yaml)
+        .map_err(|e| AgentError::InvalidInputError(format!("Failed to parse {}: {}", path.display(), e)))?;
+
+    let schema = test_file.schema.unwrap_or_else(|| vec!["prompt".to_string(), "expected_output".to_string()]);
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_else(|| "case".to_string());
+    let sections = section_categories(yaml);
+    let lines = row_lines(yaml);
+
+    let mut test_cases = Vec::new();
+    let mut errors = Vec::new();
+    let mut ids: HashMap<String, String> = HashMap::new();
+
+    for (index, row) in test_file.tests.into_iter().enumerate() {
+        let location = match lines.get(index) {
+            Some(line) => format!("{}:{}", path.display(), line),
+            None => format!("{}: row {}", path.display(), index + 1),
+        };
+
+        let record = match to_record(row, &schema) {
+            Ok(record) => record,
+            Err(message) => {
+                errors.push(format!("{}: {}", location, message));
+                continue;
+            },
+        };
+        let case = match validate(record, index, &stem, &sections, now, holidays) {
+            Ok(case) => case,
+            Err(message) => {
+                errors.push(format!("{}: {}", location, message));
+                continue;
+            },
+        };
+        if let Some(first) = ids.insert(case.id.clone(), location.clone()) {
+            errors.push(format!("{}: duplicate id '{}' (first used at {})", location, case.id, first));
+            continue;
+        }
+        test_cases.push(case);
+    }
+
+    if !errors.is_empty() {
+        return Err(AgentError::InvalidInputError(format!(
+            "{} invalid test case(s):\n{}", errors.len(), errors.join("\n")
+        )));
+    }
+    Ok(test_cases)
+}
+
+fn to_record(row: serde_yaml::Value, schema: &[String]) -> Result<CaseRecord, String> {
+    let row = match row {
+        serde_yaml::Value::Sequence(columns) => {
+            if columns.len() != schema.len() {
+                return Err(format!(
+                    "expected {} columns ({}), found {}", schema.len(), schema.join(", "), columns.len()
+                ));
+            }
+            let named = schema.iter().cloned().map(serde_yaml::Value::String).zip(columns).collect();
+            serde_yaml::Value::Mapping(named)
+        },
+        mapping @ serde_yaml::Value::Mapping(_) => mapping,
+        _ => return Err("expected a list or a mapping".to_string()),
+    };
+    serde_yaml::from_value(row).map_err(|e| e.to_string())
+}
+
+fn validate(
+    record: CaseRecord,
+    index: usize,
+    stem: &str,
+    sections: &[(usize, usize, String)],
+    now: DateTime<Utc>,
+    holidays: &HolidayCalendar,
+) -> Result<TestCase, String> {
+    if record.prompt.trim().is_empty() {
+        return Err("prompt is empty".to_string());
+    }
+    let expected = match record.expected {
+        serde_yaml::Value::String(s) => s,
+        serde_yaml::Value::Number(n) => n.to_string(),
+        _ => return Err("expected must be a string or a number".to_string()),
+    };
+    if expected.trim().is_empty() {
+        return Err("expected is empty".to_string());
+    }
+    let id = match record.id {
+        Some(id) if id.trim().is_empty() => return Err("id is empty".to_string()),
+        Some(id) => id,
+        None => format!("{}-{:03}", stem, index + 1),
+    };
+    let tolerance = record.tolerance.map(Tolerance::from);
+    if tolerance.is_some_and(|t| t.absolute < 0.0 || t.relative < 0.0) {
+        return Err("tolerance can't be negative".to_string());
+    }
+    if let Some(tools) = &record.expected_tools {
+        let known = create_default_registry().tool_names();
+        if let Some(unknown) = tools.iter().find(|t| !known.contains(t)) {
+            return Err(format!("expected_tools names unknown tool '{}' (available: {})", unknown, known.join(", ")));
+        }
+    }
+
+    let (expected_output, expected_placeholder) = match resolvers::resolve_expected(&expected, now, holidays) {
+        Some(resolved) => (resolved, Some(expected)),
+        None => (expected, None),
+    };
+
+    // section ranges in the comments are 1-based
+    let category = record.category.or_else(|| {
+        sections.iter()
+            .find(|(first, last, _)| (*first..=*last).contains(&(index + 1)))
+            .map(|(_, _, name)| name.clone())
+    });
+
+    Ok(TestCase {
+        id,
+        prompt: record.prompt,
+        expected_output,
+        expected_placeholder,
+        category,
+        tags: record.tags,
+        answer_type: record.answer_type,
+        tolerance,
+        notes: record.notes,
+        expected_tools: record.expected_tools,
+    })
+}
+
+// serde_yaml doesn't expose positions for values, so find where each `tests`
+// item starts: the `-` lines at the indent of the first one.
+fn row_lines(yaml: &str) -> Vec<usize> {
+    let mut rows = Vec::new();
+    let mut in_tests = false;
+    let mut item_indent = None;
+
+    for (number, line) in yaml.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if !in_tests {
+            in_tests = indent == 0 && trimmed.starts_with("tests:");
+            continue;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if indent == 0 && !trimmed.starts_with('-') {
+            break;
+        }
+        if trimmed == "-" || trimmed.starts_with("- ") {
+            match item_indent {
+                None => {
+                    item_indent = Some(indent);
+                    rows.push(number + 1);
+                },
+                Some(expected) if expected == indent => rows.push(number + 1),
+                _ => {},
+            }
+        }
+    }
+    rows
+}
+
+/// Categories from `# Name (first-last)` comment headers, since the list
+/// format has nowhere else to put them.
+fn section_categories(yaml: &str) -> Vec<(usize, usize, String)> {
+    SECTION_HEADER.captures_iter(yaml)
+        .filter_map(|c| {
+            let first = c.get(2)?.as_str().parse().ok()?;
+            let last = c.get(3)?.as_str().parse().ok()?;
+            Some((first, last, c.get(1)?.as_str().trim().to_string()))
+        })
+        .collect()
+}
+
+// Fisher-Yates driven by splitmix64, so a seed reproduces the same order everywhere
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut rng = SplitMix64::new(seed);
+    for i in (1..items.len()).rev() {
+        let j = rng.below(i + 1);
+        items.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn written_at() -> DateTime<Utc> {
+        crate::clock::parse_instant("2025-08-21T12:50:00Z").unwrap()
+    }
+
+    fn case(prompt: &str, category: &str) -> TestCase {
+        TestCase {
+            prompt: prompt.to_string(),
+            expected_output: "1".to_string(),
+            category: Some(category.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_load_test_cases() {
+        if std::path::Path::new(DEFAULT_CASES_PATH).exists() {
+            let result = load_test_cases(Path::new(DEFAULT_CASES_PATH), written_at(), &HolidayCalendar::builtin()).await;
+            assert!(result.is_ok());
+            let test_cases = result.unwrap();
+            assert!(!test_cases.is_empty());
+
+            let christmas = test_cases.iter().find(|c| c.prompt == "How many days until Christmas?").unwrap();
+            assert_eq!(christmas.expected_output, "126");
+            assert_eq!(christmas.expected_placeholder.as_deref(), Some("days_to_christmas (126)"));
+            assert_eq!(christmas.category.as_deref(), Some("Date calculations"));
+            assert_eq!(test_cases[0].category.as_deref(), Some("Calculator math"));
+            assert_eq!(test_cases[0].id, "calc-01");
+
+            let pace = test_cases.iter().find(|c| c.prompt.contains("marathon")).unwrap();
+            assert_eq!(pace.answer_type, AnswerType::Duration);
+        }
+    }
+
+    #[test]
+    fn test_list_rows_still_load() {
+        let yaml = "schema: [prompt, expected_output]\ntests:\n  # Calculator math (1-1)\n  - [\"What is 2 + 2?\", \"4\"]\n";
+        let cases = parse_test_cases(yaml, Path::new("legacy.yaml"), written_at(), &HolidayCalendar::builtin()).unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].id, "legacy-001");
+        assert_eq!(cases[0].expected_output, "4");
+        assert_eq!(cases[0].category.as_deref(), Some("Calculator math"));
+        assert_eq!(cases[0].answer_type, AnswerType::Number);
+    }
+
+    #[test]
+    fn test_structured_rows() {
+        let yaml = r#"
+tests:
+  - id: tip
+    prompt: How much tip on a $127.83 bill for 18% service?
+    expected: 23.01
+    category: Money
+    tags: [percent]
+    tolerance: 0.01
+  - id: pace
+    prompt: What mile pace is a 2:52 marathon?
+    expected: "6:34"
+    answer_type: duration
+    tolerance: {relative: 0.02}
+    notes: minutes:seconds per mile
+    expected_tools: [calculator]
+"#;
+        let cases = parse_test_cases(yaml, Path::new("cases.yaml"), written_at(), &HolidayCalendar::builtin()).unwrap();
+        assert_eq!(cases[0].expected_output, "23.01");
+        assert_eq!(cases[0].tags, vec!["percent"]);
+        assert_eq!(cases[0].tolerance, Some(Tolerance { absolute: 0.01, relative: 0.0 }));
+        assert_eq!(cases[1].answer_type, AnswerType::Duration);
+        assert_eq!(cases[1].tolerance, Some(Tolerance { absolute: 0.0, relative: 0.02 }));
+        assert_eq!(cases[1].notes.as_deref(), Some("minutes:seconds per mile"));
+        assert_eq!(cases[0].expected_tools, None);
+        assert_eq!(cases[1].expected_tools, Some(vec!["calculator".to_string()]));
+    }
+
+    #[test]
+    fn test_malformed_rows_report_lines() {
+        let yaml = r#"schema: [prompt, expected_output]
+tests:
+  - ["What is 2 + 2?", "4"]
+  - ["What is 3 + 3?"]
+  - id: dup
+    prompt: What is 4 + 4?
+    expected: "8"
+  - id: dup
+    prompt: What is 5 + 5?
+    expected: "10"
+  - prompt: ""
+    expected: "0"
+  - prompt: What is 6 + 6?
+    expected: "12"
+    answer_type: money
+  - prompt: What is 7 + 7?
+    expected: "14"
+    expected_tools: [abacus]
+"#;
+        let err = parse_test_cases(yaml, Path::new("bad.yaml"), written_at(), &HolidayCalendar::builtin()).unwrap_err().to_string();
+        assert!(err.contains("5 invalid test case(s)"), "{}", err);
+        assert!(err.contains("bad.yaml:16: expected_tools names unknown tool 'abacus'"), "{}", err);
+        assert!(err.contains("bad.yaml:4: expected 2 columns (prompt, expected_output), found 1"), "{}", err);
+        assert!(err.contains("bad.yaml:8: duplicate id 'dup' (first used at bad.yaml:5)"), "{}", err);
+        assert!(err.contains("bad.yaml:11: prompt is empty"), "{}", err);
+        assert!(err.contains("bad.yaml:13: unknown variant `money`"), "{}", err);
+    }
+
+    #[test]
+    fn test_selection_filters_and_limits() {
+        let cases = vec![
+            case("How many days until Christmas?", "Date calculations"),
+            case("What is 2 + 2?", "Calculator math"),
+            case("How many days until Halloween?", "Date calculations"),
+        ];
+
+        let selection = CaseSelection {
+            filter: Some(Regex::new("(?i)days until").unwrap()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let selected = selection.apply(cases.clone());
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].prompt, "How many days until Christmas?");
+
+        let selection = CaseSelection { category: Some("calculator".to_string()), ..Default::default() };
+        assert_eq!(selection.apply(cases).len(), 1);
+    }
+
+    #[test]
+    fn test_shuffle_is_seeded() {
+        let mut a: Vec<u32> = (0..20).collect();
+        let mut b: Vec<u32> = (0..20).collect();
+        shuffle(&mut a, 7);
+        shuffle(&mut b, 7);
+        assert_eq!(a, b);
+        assert_ne!(a, (0..20).collect::<Vec<u32>>());
+
+        let mut c: Vec<u32> = (0..20).collect();
+        shuffle(&mut c, 8);
+        assert_ne!(a, c);
+    }
+}
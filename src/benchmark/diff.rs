@@ -0,0 +1,370 @@
+//! `cvc bench diff`: what moved between two runs, per arm, and whether
+//! accuracy dropped enough to fail a pipeline.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use crate::benchmark::{arm_order, read_results_file, TestResult};
+use crate::error::AgentError;
+
+const COLUMN_WIDTH: usize = 58;
+
+/// A case whose verdict changed between the runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flip {
+    pub id: String,
+    pub repeat: usize,
+    pub prompt: String,
+    pub expected_output: String,
+    pub old_answer: String,
+    pub new_answer: String,
+    /// `true` when the new run got it right and the old didn't
+    pub fixed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArmDiff {
+    pub arm: String,
+    /// Answers present in both runs
+    pub matched: usize,
+    /// Matched answers decided in both runs
+    pub decided: usize,
+    pub old_correct: usize,
+    pub new_correct: usize,
+    pub old_latency_ms: f64,
+    pub new_latency_ms: f64,
+    pub old_length: f64,
+    pub new_length: f64,
+    pub flips: Vec<Flip>,
+}
+
+impl ArmDiff {
+    /// New minus old accuracy over answers decided in both runs, in points
+    pub fn accuracy_delta(&self) -> Option<f64> {
+        (self.decided > 0).then(|| 100.0 * (self.new_correct as f64 - self.old_correct as f64) / self.decided as f64)
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &Flip> {
+        self.flips.iter().filter(|f| !f.fixed)
+    }
+
+    pub fn fixes(&self) -> impl Iterator<Item = &Flip> {
+        self.flips.iter().filter(|f| f.fixed)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunDiff {
+    pub arms: Vec<ArmDiff>,
+    pub only_old_arms: Vec<String>,
+    pub only_new_arms: Vec<String>,
+    /// Answers in arms both runs have, but with no partner in the other run
+    pub unmatched_old: usize,
+    pub unmatched_new: usize,
+}
+
+impl RunDiff {
+    /// Pairs answers by arm and repeat, then by case id, falling back to the
+    /// prompt when an id is missing or was renamed.
+    pub fn between(old: &[TestResult], new: &[TestResult]) -> Self {
+        let old_arms = arm_order(old);
+        let new_arms = arm_order(new);
+        let mut diff = RunDiff {
+            only_old_arms: old_arms.iter().filter(|a| !new_arms.contains(a)).cloned().collect(),
+            only_new_arms: new_arms.iter().filter(|a| !old_arms.contains(a)).cloned().collect(),
+            ..Default::default()
+        };
+
+        for arm in new_arms.iter().filter(|a| old_arms.contains(a)) {
+            let mut unmatched: Vec<&TestResult> = old.iter().filter(|r| &r.arm == arm).collect();
+            let mut pairs = Vec::new();
+            for n in new.iter().filter(|r| &r.arm == arm) {
+                let by_id = unmatched.iter().position(|o| !n.id.is_empty() && o.id == n.id && o.repeat == n.repeat);
+                let found = by_id.or_else(|| unmatched.iter().position(|o| o.prompt == n.prompt && o.repeat == n.repeat));
+                match found {
+                    Some(index) => pairs.push((unmatched.remove(index), n)),
+                    None => diff.unmatched_new += 1,
+                }
+            }
+            diff.unmatched_old += unmatched.len();
+            diff.arms.push(arm_diff(arm, &pairs));
+        }
+        diff
+    }
+
+    /// Arms whose accuracy fell by more than `max_drop` points.
+    pub fn failing_arms(&self, max_drop: f64) -> Vec<&ArmDiff> {
+        self.arms.iter().filter(|a| a.accuracy_delta().is_some_and(|d| -d > max_drop)).collect()
+    }
+
+    /// Why a pipeline gate with `max_drop` should fail; empty when it passes.
+    /// Besides accuracy drops, an arm missing from the new run, an arm with
+    /// nothing decided in both runs, and old answers with no partner in the
+    /// new run all fail, since each could hide a regression.
+    pub fn gate_failures(&self, max_drop: f64) -> Vec<String> {
+        let mut failures: Vec<String> = self.failing_arms(max_drop).iter()
+            .map(|arm| format!("{} accuracy dropped {:.1} pts (allowed {:.1})", arm.arm, -arm.accuracy_delta().unwrap_or(0.0), max_drop))
+            .collect();
+        failures.extend(self.only_old_arms.iter().map(|arm| format!("{} is missing from the new run", arm)));
+        failures.extend(self.arms.iter()
+            .filter(|arm| arm.matched > 0 && arm.accuracy_delta().is_none())
+            .map(|arm| format!("{} has no answers decided in both runs, so its accuracy can't be compared", arm.arm)));
+        if self.unmatched_old > 0 {
+            failures.push(format!("{} answer(s) from the old run have no match in the new run", self.unmatched_old));
+        }
+        failures
+    }
+
+    pub fn render(&self, old_label: &str, new_label: &str) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "=== Benchmark diff ===\nOld: {}\nNew: {}\n", old_label, new_label);
+
+        for arm in &self.arms {
+            let accuracy = match arm.accuracy_delta() {
+                Some(delta) => format!(
+                    "accuracy {:.1}% -> {:.1}% ({:+.1} pts)",
+                    percent(arm.old_correct, arm.decided), percent(arm.new_correct, arm.decided), delta,
+                ),
+                None => "accuracy n/a".to_string(),
+            };
+            let _ = writeln!(
+                out, "[{}] {} matched: {}; latency {:.0} -> {:.0} ms ({}); length {:.0} -> {:.0} chars ({})",
+                arm.arm, arm.matched, accuracy,
+                arm.old_latency_ms, arm.new_latency_ms, change(arm.old_latency_ms, arm.new_latency_ms),
+                arm.old_length, arm.new_length, change(arm.old_length, arm.new_length),
+            );
+            for (title, flips) in [("Regressed", arm.regressions().collect::<Vec<_>>()), ("Fixed", arm.fixes().collect())] {
+                if flips.is_empty() {
+                    continue;
+                }
+                let _ = writeln!(out, "  {} ({}):", title, flips.len());
+                for flip in flips {
+                    let run = if flip.repeat > 0 { format!(" run {}", flip.repeat + 1) } else { String::new() };
+                    let _ = writeln!(out, "    {}{}: {} (expected {})", flip.id, run, flip.prompt, flip.expected_output);
+                    out.push_str(&side_by_side(&flip.old_answer, &flip.new_answer));
+                }
+            }
+            out.push('\n');
+        }
+
+        if !self.only_old_arms.is_empty() {
+            let _ = writeln!(out, "Arms only in old run: {}", self.only_old_arms.join(", "));
+        }
+        if !self.only_new_arms.is_empty() {
+            let _ = writeln!(out, "Arms only in new run: {}", self.only_new_arms.join(", "));
+        }
+        if self.unmatched_old + self.unmatched_new > 0 {
+            let _ = writeln!(out, "Unmatched answers: {} only in old, {} only in new", self.unmatched_old, self.unmatched_new);
+        }
+        out.trim_end().to_string()
+    }
+}
+
+/// Diffs two results files. The flag is set when the `max_drop` gate fails
+/// (see `RunDiff::gate_failures`).
+pub async fn diff_files(old: &Path, new: &Path, max_drop: Option<f64>) -> Result<(String, bool), AgentError> {
+    let diff = RunDiff::between(&read_results_file(old).await?, &read_results_file(new).await?);
+    if diff.arms.is_empty() {
+        return Err(AgentError::InvalidInputError(format!(
+            "No arms in common between {} and {}", old.display(), new.display()
+        )));
+    }
+
+    let mut output = diff.render(&old.display().to_string(), &new.display().to_string());
+    let mut failed = false;
+    if let Some(max_drop) = max_drop {
+        let failures = diff.gate_failures(max_drop);
+        failed = !failures.is_empty();
+        for failure in failures {
+            let _ = write!(output, "\nFAIL: {}", failure);
+        }
+        if !failed {
+            let _ = write!(output, "\nOK: no arm's accuracy dropped more than {:.1} pts", max_drop);
+        }
+    }
+    Ok((output, failed))
+}
+
+fn arm_diff(arm: &str, pairs: &[(&TestResult, &TestResult)]) -> ArmDiff {
+    let decided: Vec<(bool, bool, &TestResult, &TestResult)> = pairs.iter()
+        .filter_map(|(o, n)| Some((o.verdict()?, n.verdict()?, *o, *n)))
+        .collect();
+    let mean = |f: &dyn Fn(&(&TestResult, &TestResult)) -> f64| {
+        if pairs.is_empty() { 0.0 } else { pairs.iter().map(f).sum::<f64>() / pairs.len() as f64 }
+    };
+
+    ArmDiff {
+        arm: arm.to_string(),
+        matched: pairs.len(),
+        decided: decided.len(),
+        old_correct: decided.iter().filter(|d| d.0).count(),
+        new_correct: decided.iter().filter(|d| d.1).count(),
+        old_latency_ms: mean(&|(o, _)| o.duration_ms as f64),
+        new_latency_ms: mean(&|(_, n)| n.duration_ms as f64),
+        old_length: mean(&|(o, _)| o.length as f64),
+        new_length: mean(&|(_, n)| n.length as f64),
+        flips: decided.iter()
+            .filter(|(was, is, _, _)| was != is)
+            .map(|(_, is, o, n)| Flip {
+                id: if n.id.is_empty() { o.id.clone() } else { n.id.clone() },
+                repeat: n.repeat,
+                prompt: n.prompt.clone(),
+                expected_output: n.expected_output.clone(),
+                old_answer: o.answer.clone(),
+                new_answer: n.answer.clone(),
+                fixed: *is,
+            })
+            .collect(),
+    }
+}
+
+fn percent(count: usize, total: usize) -> f64 {
+    100.0 * count as f64 / total.max(1) as f64
+}
+
+/// `+150, +12.5%`
+fn change(old: f64, new: f64) -> String {
+    let delta = new - old;
+    if old == 0.0 {
+        return format!("{:+.0}", delta);
+    }
+    format!("{:+.0}, {:+.1}%", delta, 100.0 * delta / old)
+}
+
+/// Both answers wrapped into two columns under `old` and `new` headings.
+fn side_by_side(old: &str, new: &str) -> String {
+    let (left, right) = (wrap(old, COLUMN_WIDTH), wrap(new, COLUMN_WIDTH));
+    let mut out = format!("      {:<width$} | {}\n", "old", "new", width = COLUMN_WIDTH);
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map_or("", String::as_str);
+        let r = right.get(i).map_or("", String::as_str);
+        let _ = writeln!(out, "      {:<width$} | {}", l, r, width = COLUMN_WIDTH);
+    }
+    out
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.to_string();
+        // split words longer than a column
+        while word.chars().count() > width {
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            let head: String = word.chars().take(width).collect();
+            word = word.chars().skip(width).collect();
+            lines.push(head);
+        }
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, prompt: &str, arm: &str, correct: Option<bool>, duration_ms: u64) -> TestResult {
+        TestResult {
+            id: id.to_string(),
+            prompt: prompt.to_string(),
+            arm: arm.to_string(),
+            answer: format!("answer to {}", prompt),
+            correct,
+            duration_ms,
+            length: 100,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matches_by_id_then_prompt() {
+        let old = vec![
+            result("a", "What is 2 + 2?", "tools", Some(true), 100),
+            result("", "What is 3 + 3?", "tools", Some(true), 100),
+            result("c", "What is 4 + 4?", "tools", Some(false), 100),
+            result("gone", "Removed case", "tools", Some(true), 100),
+        ];
+        let new = vec![
+            result("a", "What is 2 + 2? (reworded)", "tools", Some(true), 300),
+            result("b", "What is 3 + 3?", "tools", Some(false), 300),
+            result("c", "What is 4 + 4?", "tools", Some(true), 300),
+            result("new", "Added case", "tools", Some(true), 300),
+        ];
+        let diff = RunDiff::between(&old, &new);
+        let arm = &diff.arms[0];
+        assert_eq!((arm.matched, arm.decided, arm.old_correct, arm.new_correct), (3, 3, 2, 2));
+        assert_eq!((diff.unmatched_old, diff.unmatched_new), (1, 1));
+        assert_eq!(arm.regressions().map(|f| f.id.as_str()).collect::<Vec<_>>(), vec!["b"]);
+        assert_eq!(arm.fixes().map(|f| f.id.as_str()).collect::<Vec<_>>(), vec!["c"]);
+        assert_eq!((arm.old_latency_ms, arm.new_latency_ms), (100.0, 300.0));
+    }
+
+    #[test]
+    fn test_failing_arms_and_render() {
+        let old = vec![
+            result("a", "p1", "tools", Some(true), 100), result("b", "p2", "tools", Some(true), 100),
+            result("a", "p1", "no-tools", Some(false), 100), result("x", "px", "legacy", Some(true), 1),
+        ];
+        let new = vec![
+            result("a", "p1", "tools", Some(true), 150), result("b", "p2", "tools", Some(false), 150),
+            result("a", "p1", "no-tools", None, 100),
+        ];
+        let diff = RunDiff::between(&old, &new);
+        assert_eq!(diff.arms[0].accuracy_delta(), Some(-50.0));
+        assert_eq!(diff.arms[1].accuracy_delta(), None);
+        assert_eq!(diff.failing_arms(10.0).len(), 1);
+        assert!(diff.failing_arms(50.0).is_empty());
+
+        let text = diff.render("old.jsonl", "new.jsonl");
+        assert!(text.contains("[tools] 2 matched: accuracy 100.0% -> 50.0% (-50.0 pts); latency 100 -> 150 ms (+50, +50.0%)"), "{}", text);
+        assert!(text.contains("  Regressed (1):\n    b: p2 (expected )"), "{}", text);
+        assert!(text.contains("answer to p2"), "{}", text);
+        assert!(text.contains("Arms only in old run: legacy"), "{}", text);
+    }
+
+    #[test]
+    fn test_gate_fails_on_missing_arm() {
+        let old = vec![result("a", "p1", "tools", Some(true), 100), result("a", "p1", "no-tools", Some(true), 100)];
+        let new = vec![result("a", "p1", "tools", Some(true), 100)];
+        let diff = RunDiff::between(&old, &new);
+        assert!(diff.failing_arms(0.0).is_empty());
+        assert_eq!(diff.gate_failures(0.0), vec!["no-tools is missing from the new run"]);
+    }
+
+    #[test]
+    fn test_gate_fails_on_undecidable_arm() {
+        let old = vec![result("a", "p1", "tools", Some(true), 100)];
+        let new = vec![result("a", "p1", "tools", None, 100)];
+        let diff = RunDiff::between(&old, &new);
+        assert_eq!(diff.arms[0].accuracy_delta(), None);
+        assert_eq!(diff.gate_failures(5.0), vec!["tools has no answers decided in both runs, so its accuracy can't be compared"]);
+    }
+
+    #[test]
+    fn test_gate_fails_on_lost_cases() {
+        let old = vec![result("a", "p1", "tools", Some(true), 100), result("b", "p2", "tools", Some(false), 100)];
+        let new = vec![result("a", "p1", "tools", Some(true), 100)];
+        let diff = RunDiff::between(&old, &new);
+        assert_eq!(diff.arms[0].accuracy_delta(), Some(0.0));
+        assert_eq!(diff.gate_failures(5.0), vec!["1 answer(s) from the old run have no match in the new run"]);
+        assert!(RunDiff::between(&old, &old).gate_failures(5.0).is_empty());
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("a bb ccc", 4), vec!["a bb", "ccc"]);
+        assert_eq!(wrap("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+        assert!(wrap("  ", 4).is_empty());
+    }
+}
@@ -0,0 +1,408 @@
+//! LLM-as-judge scoring for answers the numeric scorer can't settle, such as
+//! riddles where "47 minutes" and "141 minutes" are both defensible.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use crate::benchmark::{parse_results, TestResult};
+use crate::error::AgentError;
+use crate::openai::OpenAIModel;
+use crate::traits::Model;
+use crate::types::{Message, ModelResponse};
+
+pub const DEFAULT_RUBRIC: &str = "You are grading answers to counting, math and date questions.
+
+An answer is CORRECT if its final answer matches the expected answer, allowing small rounding differences (within 1 for whole numbers, or about 1% for decimals). Extra explanation, formatting or units do not matter. If the expected answer is a description rather than a value, decide whether the answer satisfies it.
+An answer is INCORRECT if its final answer differs, if it gives several conflicting final answers, or if it refuses or errors.
+
+Reply with JSON only, no prose: {\"verdict\": \"correct\" | \"incorrect\", \"rationale\": \"<one sentence>\", \"confidence\": <0.0 to 1.0>}";
+
+pub const JUDGE_CACHE_PATH: &str = "results/judge_cache.jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JudgeDecision {
+    Correct,
+    Incorrect,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgeVerdict {
+    pub verdict: JudgeDecision,
+    pub rationale: String,
+    pub confidence: f64,
+}
+
+impl JudgeVerdict {
+    pub fn correct(&self) -> bool {
+        self.verdict == JudgeDecision::Correct
+    }
+}
+
+/// Which benchmark answers get sent to the judge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JudgeMode {
+    #[default]
+    Off,
+    /// Only answers the auto-scorer flagged for review
+    Review,
+    All,
+}
+
+impl std::str::FromStr for JudgeMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(JudgeMode::Off),
+            "review" => Ok(JudgeMode::Review),
+            "all" => Ok(JudgeMode::All),
+            other => Err(format!("Invalid judge mode '{}'. Use: off, review, or all", other)),
+        }
+    }
+}
+
+/// Verdicts keyed by judge model, rubric and graded text, persisted as JSONL.
+pub struct JudgeCache {
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<String, JudgeVerdict>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheLine {
+    key: String,
+    verdict: JudgeVerdict,
+}
+
+impl JudgeCache {
+    pub fn in_memory() -> Self {
+        Self { path: None, entries: Mutex::new(HashMap::new()) }
+    }
+
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self, AgentError> {
+        let path = path.into();
+        let mut entries = HashMap::new();
+        if let Ok(content) = tokio::fs::read_to_string(&path).await {
+            for line in content.lines().filter(|l| !l.trim().is_empty()) {
+                // a torn last line from an interrupted run is just a cache miss
+                if let Ok(entry) = serde_json::from_str::<CacheLine>(line) {
+                    entries.insert(entry.key, entry.verdict);
+                }
+            }
+        }
+        Ok(Self { path: Some(path), entries: Mutex::new(entries) })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, key: &str) -> Option<JudgeVerdict> {
+        self.entries.lock().ok()?.get(key).cloned()
+    }
+
+    async fn insert(&self, key: String, verdict: JudgeVerdict) -> Result<(), AgentError> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let line = serde_json::to_string(&CacheLine { key: key.clone(), verdict: verdict.clone() })?;
+            let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(path).await?;
+            file.write_all(format!("{}\n", line).as_bytes()).await?;
+        }
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, verdict);
+        }
+        Ok(())
+    }
+}
+
+pub struct Judge {
+    model: Box<dyn Model>,
+    /// Identifies the judge model in cache keys
+    model_id: String,
+    rubric: String,
+    cache: JudgeCache,
+}
+
+impl Judge {
+    pub fn new(model: Box<dyn Model>, model_id: impl Into<String>) -> Self {
+        Self {
+            model,
+            model_id: model_id.into(),
+            rubric: DEFAULT_RUBRIC.to_string(),
+            cache: JudgeCache::in_memory(),
+        }
+    }
+
+    pub fn with_rubric(mut self, rubric: impl Into<String>) -> Self {
+        self.rubric = rubric.into();
+        self
+    }
+
+    pub fn with_cache(mut self, cache: JudgeCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub async fn judge(&self, prompt: &str, expected: &str, answer: &str) -> Result<JudgeVerdict, AgentError> {
+        let key = cache_key(&[&self.model_id, &self.rubric, prompt, expected, answer]);
+        if let Some(cached) = self.cache.get(&key) {
+            return Ok(cached);
+        }
+
+        let messages = vec![
+            Message::system(&self.rubric),
+            Message::user(&format!(
+                "Question: {}\nExpected answer: {}\n\nAnswer to grade:\n{}",
+                prompt, expected, answer
+            )),
+        ];
+
+        let response = match self.model.generate(messages, None).await? {
+            ModelResponse::Text(text) => text,
+            ModelResponse::ToolCalls(_) => {
+                return Err(AgentError::ModelError("Judge model tried to call tools".to_string()));
+            },
+        };
+
+        let verdict = parse_verdict(&response)?;
+        self.cache.insert(key, verdict.clone()).await?;
+        Ok(verdict)
+    }
+
+    /// Judges a result's answer if `mode` calls for it, filling in `judge`.
+    pub async fn judge_result(&self, result: &mut TestResult, mode: JudgeMode) -> Result<(), AgentError> {
+        let wanted = match mode {
+            JudgeMode::Off => false,
+            JudgeMode::Review => result.correct.is_none(),
+            JudgeMode::All => true,
+        };
+
+        if wanted {
+            result.judge = Some(self.judge(&result.prompt, &result.expected_output, &result.answer).await?);
+        }
+        Ok(())
+    }
+}
+
+/// OpenAI-backed judge. The model is `model_name`, else `JUDGE_MODEL_NAME`,
+/// else gpt-4.1-mini, kept separate from the model under test.
+pub async fn openai_judge(model_name: Option<String>) -> Result<Judge, AgentError> {
+    let api_key = std::env::var("OPENAI_API_KEY")
+        .map_err(|_| AgentError::ConfigurationError(
+            "OPENAI_API_KEY not set".to_string()
+        ))?;
+
+    let model_name = model_name
+        .or_else(|| std::env::var("JUDGE_MODEL_NAME").ok())
+        .unwrap_or_else(|| "gpt-4.1-mini".to_string());
+
+    let model = Box::new(OpenAIModel::new(api_key, model_name.clone()));
+    let cache = JudgeCache::open(JUDGE_CACHE_PATH).await?;
+    Ok(Judge::new(model, format!("openai:{}", model_name)).with_cache(cache))
+}
+
+fn parse_verdict(response: &str) -> Result<JudgeVerdict, AgentError> {
+    // tolerate ```json fences and chatter around the object
+    let start = response.find('{');
+    let end = response.rfind('}');
+    let json = match (start, end) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => return Err(AgentError::ModelError(format!("Judge did not return JSON: {}", response))),
+    };
+
+    let mut verdict: JudgeVerdict = serde_json::from_str(json)
+        .map_err(|e| AgentError::ModelError(format!("Judge returned malformed verdict ({}): {}", e, json)))?;
+    verdict.confidence = verdict.confidence.clamp(0.0, 1.0);
+    Ok(verdict)
+}
+
+// FNV-1a, so keys stay stable across builds and Rust versions
+fn cache_key(parts: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for byte in part.bytes().chain(std::iter::once(0)) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+/// How the judge compares with human labels in an already-scored file.
+#[derive(Debug, Default, Serialize)]
+pub struct AgreementReport {
+    /// Rows scored by a person in `bench score`
+    pub compared: usize,
+    /// Rows skipped because only the auto-scorer (or nobody) scored them
+    pub unreviewed: usize,
+    pub agreed: usize,
+    /// Judge said correct, human said incorrect
+    pub judge_lenient: usize,
+    /// Judge said incorrect, human said correct
+    pub judge_strict: usize,
+    pub cohens_kappa: f64,
+}
+
+impl AgreementReport {
+    pub fn agreement_rate(&self) -> f64 {
+        if self.compared == 0 { 0.0 } else { self.agreed as f64 / self.compared as f64 }
+    }
+
+    fn from_pairs(pairs: &[(bool, bool)]) -> Self {
+        let compared = pairs.len();
+        let agreed = pairs.iter().filter(|(h, j)| h == j).count();
+        let judge_lenient = pairs.iter().filter(|(h, j)| !h && *j).count();
+        let judge_strict = pairs.iter().filter(|(h, j)| *h && !j).count();
+
+        let cohens_kappa = if compared == 0 {
+            0.0
+        } else {
+            let n = compared as f64;
+            let observed = agreed as f64 / n;
+            let human_yes = pairs.iter().filter(|(h, _)| *h).count() as f64 / n;
+            let judge_yes = pairs.iter().filter(|(_, j)| *j).count() as f64 / n;
+            let expected = human_yes * judge_yes + (1.0 - human_yes) * (1.0 - judge_yes);
+            if expected >= 1.0 { 1.0 } else { (observed - expected) / (1.0 - expected) }
+        };
+
+        Self { compared, agreed, judge_lenient, judge_strict, cohens_kappa, unreviewed: 0 }
+    }
+
+    pub fn summary(&self) -> String {
+        [
+            "=== Judge vs Human Agreement ===".to_string(),
+            format!("Compared:      {} human-reviewed answers ({} not reviewed, skipped)", self.compared, self.unreviewed),
+            format!("Agreement:     {}/{} ({:.1}%)", self.agreed, self.compared, 100.0 * self.agreement_rate()),
+            format!("Cohen's kappa: {:.3}", self.cohens_kappa),
+            format!("Judge more lenient than human: {}", self.judge_lenient),
+            format!("Judge stricter than human:     {}", self.judge_strict),
+        ].join("\n")
+    }
+}
+
+/// Judges every answer a person scored in `bench score` and reports agreement.
+/// Auto-scored verdicts are skipped: they aren't human labels.
+pub async fn judge_agreement(path: &str, judge: &Judge) -> Result<AgreementReport, AgentError> {
+    let content = tokio::fs::read_to_string(path).await
+        .map_err(|e| AgentError::InvalidInputError(format!("Failed to read {}: {}", path, e)))?;
+
+    let mut pairs = Vec::new();
+    let mut unreviewed = 0;
+    for result in parse_results(&content)? {
+        let Some(human) = result.correct.filter(|_| result.reviewed) else {
+            unreviewed += 1;
+            continue;
+        };
+        let verdict = judge.judge(&result.prompt, &result.expected_output, &result.answer).await?;
+        pairs.push((human, verdict.correct()));
+    }
+
+    Ok(AgreementReport { unreviewed, ..AgreementReport::from_pairs(&pairs) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ToolSpec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct CountingJudgeModel {
+        reply: String,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Model for CountingJudgeModel {
+        async fn generate(&self, _messages: Vec<Message>, _tools: Option<Vec<ToolSpec>>) -> Result<ModelResponse, AgentError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ModelResponse::Text(self.reply.clone()))
+        }
+    }
+
+    fn judge_replying(reply: &str) -> (Judge, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let model = CountingJudgeModel { reply: reply.to_string(), calls: calls.clone() };
+        (Judge::new(Box::new(model), "test-judge"), calls)
+    }
+
+    #[test]
+    fn test_parse_verdict_with_fences() {
+        let verdict = parse_verdict("```json\n{\"verdict\": \"incorrect\", \"rationale\": \"off by 94\", \"confidence\": 1.4}\n```").unwrap();
+        assert_eq!(verdict.verdict, JudgeDecision::Incorrect);
+        assert_eq!(verdict.confidence, 1.0);
+        assert!(parse_verdict("I think it's right").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_judge_calls_are_cached() {
+        let (judge, calls) = judge_replying(r#"{"verdict": "correct", "rationale": "matches", "confidence": 0.9}"#);
+
+        let first = judge.judge("How many minutes?", "141", "141 minutes").await.unwrap();
+        let second = judge.judge("How many minutes?", "141", "141 minutes").await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        judge.judge("How many minutes?", "141", "47 minutes").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_agreement_report() {
+        let report = AgreementReport::from_pairs(&[(true, true), (false, false), (true, false), (false, true)]);
+        assert_eq!(report.compared, 4);
+        assert_eq!(report.agreed, 2);
+        assert_eq!(report.judge_lenient, 1);
+        assert_eq!(report.judge_strict, 1);
+        assert!(report.cohens_kappa.abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_agreement_only_counts_human_reviewed_rows() {
+        let (judge, calls) = judge_replying(r#"{"verdict": "correct", "rationale": "matches", "confidence": 0.9}"#);
+        let row = |correct: &str, reviewed: bool| format!(
+            r#"{{"prompt": "p", "expected_output": "1", "answer": "1", "timestamp": "", "duration_ms": 0, "length": 1, "correct": {}, "reviewed": {}}}"#,
+            correct, reviewed
+        );
+        let content = [row("true", true), row("false", true), row("true", false), row("null", false)].join("\n");
+        let path = std::env::temp_dir().join(format!("cvc-agreement-{}.jsonl", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+
+        let report = judge_agreement(path.to_str().unwrap(), &judge).await.unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(report.compared, 2);
+        assert_eq!(report.unreviewed, 2);
+        assert_eq!(report.agreed, 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_agreement_counts_legacy_scored_rows() {
+        let (judge, _) = judge_replying(r#"{"verdict": "correct", "rationale": "matches", "confidence": 0.9}"#);
+        // the with/without-tools layout, labelled by hand before auto-scoring
+        let content = r#"{"prompt": "p", "expected_output": "4", "with_tools": "4", "without_tools": "5", "timestamp": "", "with_tools_correct": true, "without_tools_correct": null}"#;
+        let path = std::env::temp_dir().join(format!("cvc-agreement-legacy-{}.jsonl", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+
+        let report = judge_agreement(path.to_str().unwrap(), &judge).await.unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(report.compared, 1);
+        assert_eq!(report.unreviewed, 1);
+        assert_eq!(report.agreed, 1);
+    }
+
+    #[test]
+    fn test_cache_key_is_stable() {
+        assert_eq!(cache_key(&["a", "b"]), cache_key(&["a", "b"]));
+        assert_ne!(cache_key(&["ab", ""]), cache_key(&["a", "b"]));
+    }
+}
@@ -0,0 +1,187 @@
+//! The run manifest: everything needed to reproduce or resume a benchmark
+//! run, written next to its results file before the first case starts.
+
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::benchmark::judge::JudgeMode;
+use crate::benchmark::matrix::ArmMatrix;
+use crate::benchmark::scoring::Tolerance;
+use crate::benchmark::{BenchmarkOptions, CaseSelection};
+use crate::cli::RunOptions;
+use crate::error::AgentError;
+use crate::policy::ToolPolicy;
+use crate::tools::holidays::HolidayCalendar;
+use crate::tools::units::UnitTable;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunManifest {
+    /// Wall-clock start of the first attempt
+    pub started_at: DateTime<Utc>,
+    /// The run clock at start; each case resolves its placeholders when it starts
+    pub now: DateTime<Utc>,
+    /// `--now` was given, so every tool saw exactly `now`
+    pub clock_pinned: bool,
+    /// `git rev-parse HEAD`, with `-dirty` for uncommitted changes
+    pub git_commit: Option<String>,
+    pub version: String,
+    pub cases: CaseSelection,
+    /// Arms after `--arms` filtering
+    pub matrix: ArmMatrix,
+    pub tolerance: Tolerance,
+    pub judge_mode: JudgeMode,
+    pub judge_model: Option<String>,
+    pub policy: ToolPolicy,
+    /// Holidays added with `--holidays`; missing in manifests from before the holidays tool
+    #[serde(default)]
+    pub holidays: HolidayCalendar,
+    /// Units added with `--units`; missing in manifests from before the units tool
+    #[serde(default)]
+    pub units: UnitTable,
+    pub concurrency: usize,
+    pub requests_per_minute: Option<u32>,
+    /// Runs per case and arm; missing in manifests from before `--repeat`
+    #[serde(default)]
+    pub repeat: usize,
+    #[serde(default)]
+    pub resumed_at: Vec<DateTime<Utc>>,
+}
+
+impl RunManifest {
+    pub fn new(options: &BenchmarkOptions, matrix: &ArmMatrix, now: DateTime<Utc>) -> Self {
+        Self {
+            started_at: Utc::now(),
+            now,
+            clock_pinned: options.run.now.is_some(),
+            git_commit: git_commit(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            cases: options.cases.clone(),
+            matrix: matrix.clone(),
+            tolerance: options.tolerance,
+            judge_mode: options.judge_mode,
+            judge_model: options.judge_model.clone(),
+            policy: options.run.policy.clone(),
+            holidays: options.run.holidays.clone(),
+            units: options.run.units.clone(),
+            concurrency: options.concurrency,
+            requests_per_minute: options.requests_per_minute,
+            repeat: options.repeat.max(1),
+            resumed_at: Vec::new(),
+        }
+    }
+
+    /// Options that continue this run. An unpinned run's clock picks up at
+    /// the original start time, so time-dependent answers and expectations
+    /// agree with the results already written.
+    pub fn to_options(&self, output: PathBuf) -> BenchmarkOptions {
+        let run = if self.clock_pinned {
+            RunOptions { now: Some(self.now), ..Default::default() }
+        } else {
+            RunOptions { clock_offset: Some(self.now - Utc::now()), ..Default::default() }
+        };
+        let run = RunOptions { policy: self.policy.clone(), holidays: self.holidays.clone(), units: self.units.clone(), ..run };
+
+        BenchmarkOptions {
+            cases: self.cases.clone(),
+            matrix: Some(self.matrix.clone()),
+            arms: Vec::new(),
+            output: Some(output),
+            run,
+            tolerance: self.tolerance,
+            judge_mode: self.judge_mode,
+            judge_model: self.judge_model.clone(),
+            concurrency: self.concurrency,
+            requests_per_minute: self.requests_per_minute,
+            repeat: self.repeat,
+        }
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, AgentError> {
+        let content = tokio::fs::read_to_string(path).await
+            .map_err(|e| AgentError::InvalidInputError(format!("Failed to read manifest {}: {}", path.display(), e)))?;
+        serde_json::from_str(&content)
+            .map_err(|e| AgentError::InvalidInputError(format!("Failed to parse manifest {}: {}", path.display(), e)))
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<(), AgentError> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, serde_json::to_string_pretty(self)?).await
+            .map_err(|e| AgentError::InvalidInputError(format!("Could not save manifest to {}: {}", path.display(), e)))
+    }
+}
+
+/// `results/benchmark_x.jsonl` -> `results/benchmark_x.manifest.json`
+pub fn manifest_path(results_file: &Path) -> PathBuf {
+    results_file.with_extension("manifest.json")
+}
+
+fn git_commit() -> Option<String> {
+    let git = |args: &[&str]| {
+        std::process::Command::new("git").args(args).output().ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+    };
+    let commit = git(&["rev-parse", "HEAD"])?;
+    let dirty = git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty());
+    Some(if dirty { format!("{}-dirty", commit) } else { commit })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::holidays::{Holiday, HolidayRule};
+    use crate::tools::units::CustomUnit;
+    use regex::Regex;
+
+    #[test]
+    fn test_manifest_path() {
+        assert_eq!(manifest_path(Path::new("results/benchmark_1.jsonl")), PathBuf::from("results/benchmark_1.manifest.json"));
+    }
+
+    fn founders_day() -> HolidayCalendar {
+        let founders_day = Holiday {
+            name: "Founders Day".to_string(),
+            aliases: Vec::new(),
+            regions: vec!["US".to_string()],
+            rule: HolidayRule::Fixed { month: 3, day: 9 },
+        };
+        HolidayCalendar { holidays: vec![founders_day] }
+    }
+
+    fn furlongs() -> UnitTable {
+        UnitTable { units: vec![CustomUnit { names: vec!["furlong".to_string()], equals: "220 yd".to_string() }] }
+    }
+
+    #[test]
+    fn test_manifest_round_trip_restores_options() {
+        let now = crate::clock::parse_instant("2025-08-21T12:50:00Z").unwrap();
+        let options = BenchmarkOptions {
+            cases: CaseSelection {
+                filter: Some(Regex::new("(?i)days until").unwrap()),
+                shuffle_seed: Some(42),
+                ..Default::default()
+            },
+            run: RunOptions { now: Some(now), holidays: founders_day(), units: furlongs(), ..Default::default() },
+            judge_mode: JudgeMode::Review,
+            concurrency: 4,
+            repeat: 3,
+            ..Default::default()
+        };
+        let manifest = RunManifest::new(&options, &ArmMatrix::standard("gpt-4.1-nano"), now);
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        let restored: RunManifest = serde_json::from_str(&json).unwrap();
+        let resumed = restored.to_options(PathBuf::from("out.jsonl"));
+
+        assert_eq!(resumed.cases.filter.unwrap().as_str(), "(?i)days until");
+        assert_eq!(resumed.cases.shuffle_seed, Some(42));
+        assert_eq!(resumed.run.clock().now(), now);
+        assert_eq!(resumed.judge_mode, JudgeMode::Review);
+        assert_eq!(resumed.repeat, 3);
+        assert_eq!(resumed.run.holidays, founders_day());
+        assert_eq!(resumed.run.units, furlongs());
+        assert_eq!(resumed.matrix.unwrap().arms.len(), 2);
+    }
+}
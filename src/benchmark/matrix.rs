@@ -0,0 +1,438 @@
+//! The benchmark matrix: every combination of model, tool set, system prompt
+//! and sampling settings is one arm. Arms that agree on everything except the
+//! compared dimension are paired, so a difference in their scores can only
+//! come from that dimension.
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::agent::{Agent, AgentRun};
+use crate::cli::{system_prompt, RunOptions, DEFAULT_PERSONA};
+use crate::error::AgentError;
+use crate::openai::{OpenAIModel, OPENAI_BASE_URL};
+use crate::tools::{create_default_registry, create_registry};
+use crate::types::{Message, Sampling};
+
+/// An OpenAI-compatible endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    /// Environment variable holding the API key
+    #[serde(default = "default_api_key_env")]
+    pub api_key_env: String,
+}
+
+fn default_base_url() -> String {
+    OPENAI_BASE_URL.to_string()
+}
+
+fn default_api_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+
+impl Default for ProviderConfig {
+    fn default() -> Self {
+        Self { base_url: default_base_url(), api_key_env: default_api_key_env() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolSet {
+    pub name: String,
+    /// Registered tool names; empty runs the model without tools
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemPrompt {
+    pub name: String,
+    /// Persona and instructions; the arm's tool list and tool guidance are appended
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplingConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub sampling: Sampling,
+}
+
+/// A dimension of the matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    Model,
+    #[default]
+    ToolSet,
+    SystemPrompt,
+    Sampling,
+}
+
+/// Matrix file as written. Missing sections fall back to the standard
+/// with/without tools comparison.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MatrixConfig {
+    /// Extra endpoints by name; `openai` is always available
+    #[serde(default)]
+    pub providers: HashMap<String, ProviderConfig>,
+    /// `model` or `provider:model`; a prefix that isn't a provider name is
+    /// part of the model (e.g. `ft:gpt-4o-mini:...`)
+    #[serde(default)]
+    pub models: Vec<String>,
+    #[serde(default)]
+    pub tool_sets: Vec<ToolSet>,
+    #[serde(default)]
+    pub system_prompts: Vec<SystemPrompt>,
+    #[serde(default)]
+    pub sampling: Vec<SamplingConfig>,
+    /// Which dimension paired arms differ in
+    #[serde(default)]
+    pub compare: Dimension,
+}
+
+impl MatrixConfig {
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> Result<Self, AgentError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AgentError::InvalidInputError(format!("Failed to read matrix {}: {}", path.display(), e)))?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| AgentError::InvalidInputError(format!("Failed to parse matrix {}: {}", path.display(), e)))
+    }
+}
+
+/// One cell of the matrix, fully resolved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArmSpec {
+    pub id: String,
+    pub provider: String,
+    pub provider_config: ProviderConfig,
+    pub model: String,
+    pub tool_set: ToolSet,
+    pub system_prompt: SystemPrompt,
+    pub sampling: SamplingConfig,
+}
+
+impl ArmSpec {
+    /// What this arm sends for `dimension`, ignoring labels, so two arms
+    /// with differently named but identical settings still compare equal.
+    fn setting(&self, dimension: Dimension) -> String {
+        match dimension {
+            Dimension::Model => format!("{}|{}", self.provider_config.base_url, self.model),
+            Dimension::ToolSet => {
+                let mut tools = self.tool_set.tools.clone();
+                tools.sort();
+                tools.join(",")
+            },
+            Dimension::SystemPrompt => self.system_prompt.text.clone(),
+            Dimension::Sampling => serde_json::to_string(&self.sampling.sampling).unwrap_or_default(),
+        }
+    }
+
+    /// Label for `dimension`, as used in arm ids.
+    pub fn label(&self, dimension: Dimension) -> String {
+        match dimension {
+            Dimension::Model if self.provider == "openai" => self.model.clone(),
+            Dimension::Model => format!("{}:{}", self.provider, self.model),
+            Dimension::ToolSet => self.tool_set.name.clone(),
+            Dimension::SystemPrompt => self.system_prompt.name.clone(),
+            Dimension::Sampling => self.sampling.name.clone(),
+        }
+    }
+
+    pub fn uses_tools(&self) -> bool {
+        !self.tool_set.tools.is_empty()
+    }
+
+    /// Runs `prompt` on this arm. Policy, clock and rate limiter come from
+    /// `options` and are the same for every arm.
+    pub async fn run(&self, prompt: &str, options: &RunOptions) -> AgentRun {
+        match self.build_agent(prompt, options) {
+            Ok((agent, messages)) => agent.run_traced(messages, self.uses_tools(), options.verbose).await,
+            Err(e) => AgentRun::failed(e),
+        }
+    }
+
+    fn build_agent(&self, prompt: &str, options: &RunOptions) -> Result<(Agent, Vec<Message>), AgentError> {
+        if prompt.trim().is_empty() {
+            return Err(AgentError::InvalidInputError(
+                "Please provide a non-empty prompt".to_string()
+            ));
+        }
+
+        let api_key = options.api_key(&self.provider_config.api_key_env)?;
+        let model = OpenAIModel::new(api_key, self.model.clone())
+            .with_base_url(&self.provider_config.base_url)
+            .with_sampling(self.sampling.sampling.clone());
+
+        let clock = options.clock();
+        let tool_registry = create_registry(clock.clone(), options.holidays.clone(), options.units.clone()).subset(&self.tool_set.tools)?;
+        let system_message = Message::system(&system_prompt(&self.system_prompt.text, &tool_registry));
+
+        let mut agent = Agent::new(Box::new(model), tool_registry)
+            .with_policy(options.policy.clone())
+            .with_clock(clock);
+        if let Some(limiter) = &options.rate_limiter {
+            agent = agent.with_rate_limiter(limiter.clone());
+        }
+
+        Ok((agent, vec![system_message, Message::user(prompt)]))
+    }
+}
+
+/// The arms to run and how they pair up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArmMatrix {
+    pub arms: Vec<ArmSpec>,
+    pub compare: Dimension,
+}
+
+impl ArmMatrix {
+    /// With and without every default tool, on one model.
+    pub fn standard(model: &str) -> Self {
+        Self::from_config(MatrixConfig { models: vec![model.to_string()], ..Default::default() })
+            .expect("standard matrix is valid")
+    }
+
+    /// Expands the cross-product, checking every name it refers to.
+    pub fn from_config(config: MatrixConfig) -> Result<Self, AgentError> {
+        let invalid = |message: String| AgentError::InvalidInputError(format!("Invalid matrix: {}", message));
+
+        if config.models.is_empty() {
+            return Err(invalid("no models".to_string()));
+        }
+        let tool_sets = if config.tool_sets.is_empty() { standard_tool_sets() } else { config.tool_sets };
+        let system_prompts = if config.system_prompts.is_empty() {
+            vec![SystemPrompt { name: "count".to_string(), text: DEFAULT_PERSONA.to_string() }]
+        } else {
+            config.system_prompts
+        };
+        let sampling = if config.sampling.is_empty() {
+            vec![SamplingConfig { name: "t0".to_string(), sampling: Sampling::default() }]
+        } else {
+            config.sampling
+        };
+
+        let known_tools = create_default_registry().tool_names();
+        for set in &tool_sets {
+            if let Some(unknown) = set.tools.iter().find(|t| !known_tools.contains(t)) {
+                return Err(invalid(format!("tool set '{}' names unknown tool '{}'", set.name, unknown)));
+            }
+        }
+
+        let mut providers = config.providers;
+        providers.entry("openai".to_string()).or_default();
+
+        let mut arms = Vec::new();
+        for model in &config.models {
+            let (provider, model_name) = match model.split_once(':') {
+                Some((provider, name)) if providers.contains_key(provider) => (provider, name),
+                _ => ("openai", model.as_str()),
+            };
+            let provider_config = &providers[provider];
+
+            for tool_set in &tool_sets {
+                for system_prompt in &system_prompts {
+                    for sampling in &sampling {
+                        arms.push(ArmSpec {
+                            id: String::new(),
+                            provider: provider.to_string(),
+                            provider_config: provider_config.clone(),
+                            model: model_name.to_string(),
+                            tool_set: tool_set.clone(),
+                            system_prompt: system_prompt.clone(),
+                            sampling: sampling.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // model and tool set always appear in ids; the others only when they vary
+        let varies = |dimension: Dimension| {
+            arms.iter().any(|a| a.label(dimension) != arms[0].label(dimension))
+        };
+        let mut shown = vec![Dimension::Model, Dimension::ToolSet];
+        shown.extend([Dimension::SystemPrompt, Dimension::Sampling].into_iter().filter(|d| varies(*d)));
+        let ids: Vec<String> = arms.iter()
+            .map(|arm| shown.iter().map(|d| arm.label(*d)).collect::<Vec<_>>().join("/"))
+            .collect();
+        for (arm, id) in arms.iter_mut().zip(ids) {
+            arm.id = id;
+        }
+
+        let matrix = Self { arms, compare: config.compare };
+        matrix.verify()?;
+        Ok(matrix)
+    }
+
+    /// Keeps the arms matching any of `selectors`: a full arm id such as
+    /// `gpt-4.1-nano/tools`, or one label (`tools`, `gpt-4.1-nano`) that keeps
+    /// every arm carrying it. Empty keeps every arm.
+    pub fn select(mut self, selectors: &[String]) -> Result<Self, AgentError> {
+        if selectors.is_empty() {
+            return Ok(self);
+        }
+        let matches = |arm: &ArmSpec, selector: &str| {
+            arm.id == selector
+                || [Dimension::Model, Dimension::ToolSet, Dimension::SystemPrompt, Dimension::Sampling]
+                    .iter().any(|d| arm.label(*d) == selector)
+        };
+        if let Some(unknown) = selectors.iter().find(|s| !self.arms.iter().any(|a| matches(a, s))) {
+            let known: Vec<&str> = self.arms.iter().map(|a| a.id.as_str()).collect();
+            return Err(AgentError::InvalidInputError(format!(
+                "Unknown arm '{}'. Arms in this matrix: {} (or one of their labels)", unknown, known.join(", ")
+            )));
+        }
+        self.arms.retain(|a| selectors.iter().any(|s| matches(a, s)));
+        Ok(self)
+    }
+
+    /// Arms grouped by everything except the compared dimension, in matrix
+    /// order. Arms in one group differ only in that dimension.
+    pub fn pairs(&self) -> Vec<Vec<&ArmSpec>> {
+        let mut groups: Vec<(Vec<String>, Vec<&ArmSpec>)> = Vec::new();
+        for arm in &self.arms {
+            let key = self.held_fixed(arm);
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(arm),
+                None => groups.push((key, vec![arm])),
+            }
+        }
+        groups.into_iter().map(|(_, group)| group).collect()
+    }
+
+    /// Every two arms within a group of `pairs()`, as (first, second) ids.
+    pub fn pair_ids(&self) -> Vec<(String, String)> {
+        let mut ids = Vec::new();
+        for group in self.pairs() {
+            for (i, a) in group.iter().enumerate() {
+                for b in &group[i + 1..] {
+                    ids.push((a.id.clone(), b.id.clone()));
+                }
+            }
+        }
+        ids
+    }
+
+    fn held_fixed(&self, arm: &ArmSpec) -> Vec<String> {
+        [Dimension::Model, Dimension::ToolSet, Dimension::SystemPrompt, Dimension::Sampling]
+            .into_iter()
+            .filter(|d| *d != self.compare)
+            .map(|d| arm.setting(d))
+            .collect()
+    }
+
+    /// Arm ids must be unique, and paired arms must actually differ in the
+    /// compared dimension (two names for the same settings would make a
+    /// comparison against itself).
+    pub fn verify(&self) -> Result<(), AgentError> {
+        let invalid = |message: String| AgentError::InvalidInputError(format!("Invalid matrix: {}", message));
+
+        for (i, arm) in self.arms.iter().enumerate() {
+            if self.arms[..i].iter().any(|a| a.id == arm.id) {
+                return Err(invalid(format!("duplicate arm '{}'", arm.id)));
+            }
+        }
+        for group in self.pairs() {
+            for (i, a) in group.iter().enumerate() {
+                for b in &group[i + 1..] {
+                    if a.setting(self.compare) == b.setting(self.compare) {
+                        return Err(invalid(format!(
+                            "arms '{}' and '{}' are identical; paired arms must differ in {:?}", a.id, b.id, self.compare
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn standard_tool_sets() -> Vec<ToolSet> {
+    let mut tools = create_default_registry().tool_names();
+    tools.sort();
+    vec![
+        ToolSet { name: "tools".to_string(), tools },
+        ToolSet { name: "no-tools".to_string(), tools: Vec::new() },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_matrix_pairs_differ_only_in_tools() {
+        let matrix = ArmMatrix::standard("gpt-4.1-nano");
+        let ids: Vec<&str> = matrix.arms.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, vec!["gpt-4.1-nano/tools", "gpt-4.1-nano/no-tools"]);
+
+        let pairs = matrix.pairs();
+        assert_eq!(pairs.len(), 1);
+        let (with, without) = (pairs[0][0], pairs[0][1]);
+        assert_eq!(with.model, without.model);
+        assert_eq!(with.system_prompt, without.system_prompt);
+        assert_eq!(with.sampling, without.sampling);
+        assert_ne!(with.tool_set.tools, without.tool_set.tools);
+    }
+
+    #[test]
+    fn test_cross_product_and_pairing() {
+        let yaml = r#"
+providers:
+  local: {base_url: "http://localhost:11434/v1", api_key_env: LOCAL_KEY}
+models: [gpt-4.1-nano, "local:llama3"]
+sampling:
+  - {name: t0, temperature: 0.0}
+  - {name: t7, temperature: 0.7, seed: 1}
+"#;
+        let matrix = ArmMatrix::from_config(serde_yaml::from_str(yaml).unwrap()).unwrap();
+        assert_eq!(matrix.arms.len(), 8);
+        assert!(matrix.arms.iter().any(|a| a.id == "local:llama3/no-tools/t7"));
+
+        let pairs = matrix.pairs();
+        assert_eq!(pairs.len(), 4);
+        for group in pairs {
+            assert_eq!(group.len(), 2);
+            assert_eq!(group[0].model, group[1].model);
+            assert_eq!(group[0].sampling, group[1].sampling);
+        }
+    }
+
+    #[test]
+    fn test_identical_pairs_are_rejected() {
+        let config = MatrixConfig {
+            models: vec!["gpt-4.1-nano".to_string()],
+            tool_sets: vec![
+                ToolSet { name: "calc".to_string(), tools: vec!["calculator".to_string()] },
+                ToolSet { name: "also-calc".to_string(), tools: vec!["calculator".to_string()] },
+            ],
+            ..Default::default()
+        };
+        let err = ArmMatrix::from_config(config).unwrap_err().to_string();
+        assert!(err.contains("identical"), "{}", err);
+
+        let config = MatrixConfig {
+            models: vec!["gpt-4.1-nano".to_string()],
+            tool_sets: vec![ToolSet { name: "abacus".to_string(), tools: vec!["abacus".to_string()] }],
+            ..Default::default()
+        };
+        assert!(ArmMatrix::from_config(config).is_err());
+    }
+
+    #[test]
+    fn test_select_arms() {
+        let matrix = ArmMatrix::standard("gpt-4.1-nano");
+        let selected = matrix.clone().select(&["gpt-4.1-nano/tools".to_string()]).unwrap();
+        assert_eq!(selected.arms.len(), 1);
+        // a label keeps every arm carrying it, as before arm ids
+        let tools = matrix.clone().select(&["tools".to_string()]).unwrap();
+        assert_eq!(tools.arms.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), ["gpt-4.1-nano/tools"]);
+        assert_eq!(matrix.clone().select(&["tools".to_string(), "no-tools".to_string()]).unwrap().arms.len(), 2);
+        assert_eq!(matrix.clone().select(&["gpt-4.1-nano".to_string()]).unwrap().arms.len(), 2);
+        assert!(matrix.select(&["calc".to_string()]).is_err());
+    }
+}
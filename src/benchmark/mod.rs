@@ -0,0 +1,705 @@
+use crate::cli::{default_model_name, RunOptions};
+use crate::error::AgentError;
+use crate::rate_limit::RateLimiter;
+use crate::trace::ToolCallRecord;
+use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+pub mod cases;
+pub mod diff;
+pub mod judge;
+pub mod manifest;
+pub mod matrix;
+pub mod report;
+pub mod resolvers;
+pub mod score_ui;
+pub mod scoring;
+pub mod stats;
+
+pub use cases::{CaseSelection, TestCase, TestCaseFile};
+pub use matrix::{ArmMatrix, ArmSpec};
+
+use judge::{Judge, JudgeMode, JudgeVerdict};
+use manifest::{manifest_path, RunManifest};
+use scoring::{score_typed, AnswerType, Tolerance, Verdict};
+use stats::Comparison;
+
+#[derive(Debug, Clone, Default)]
+pub struct BenchmarkOptions {
+    pub cases: CaseSelection,
+    /// Arms to run; `None` is the standard with/without tools pair on the default model
+    pub matrix: Option<ArmMatrix>,
+    /// Arm ids to keep from the matrix; empty keeps all
+    pub arms: Vec<String>,
+    /// Results file; defaults to `results/benchmark_<timestamp>.jsonl`
+    pub output: Option<PathBuf>,
+    /// Shared by every arm; its clock also resolves placeholders
+    pub run: RunOptions,
+    pub tolerance: Tolerance,
+    pub judge_mode: JudgeMode,
+    /// Judge model, independent of the model under test
+    pub judge_model: Option<String>,
+    /// Arms allowed in flight at once; 0 and 1 both run one at a time
+    pub concurrency: usize,
+    /// Client-side request budget shared by every arm, if any
+    pub requests_per_minute: Option<u32>,
+    /// Times each case runs on each arm; 0 and 1 both run once
+    pub repeat: usize,
+}
+
+/// One arm's answer to one case.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestResult {
+    #[serde(default)]
+    pub id: String,
+    /// Matrix arm id, e.g. `gpt-4.1-nano/tools`
+    #[serde(default)]
+    pub arm: String,
+    #[serde(default)]
+    pub model: String,
+    /// Which of the `--repeat` runs this is, from 0
+    #[serde(default)]
+    pub repeat: usize,
+    pub prompt: String,
+    pub expected_output: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_placeholder: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub answer_type: AnswerType,
+    /// The case's own tolerance, when it overrides the run's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tolerance: Option<Tolerance>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_tools: Option<Vec<String>>,
+    pub answer: String,
+    pub timestamp: String,
+    pub duration_ms: u64,
+    pub length: usize,
+    /// `None` until scored; the auto-scorer leaves undecidable answers unset
+    #[serde(default)]
+    pub correct: Option<bool>,
+    #[serde(default)]
+    pub needs_review: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub review_notes: Vec<String>,
+    /// `correct` was set by a person in `bench score`
+    #[serde(default)]
+    pub reviewed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewer_note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub judge: Option<JudgeVerdict>,
+    /// Tool calls in the order made, including denied and failed ones
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCallRecord>,
+    /// Requests sent to the model
+    #[serde(default)]
+    pub round_trips: usize,
+    #[serde(default)]
+    pub max_loops_exceeded: bool,
+}
+
+impl TestResult {
+    /// The recorded verdict, falling back to the judge's when unscored.
+    pub fn verdict(&self) -> Option<bool> {
+        self.correct.or_else(|| self.judge.as_ref().map(|j| j.correct()))
+    }
+
+    /// The request itself failed (transport or API error), as opposed to an
+    /// answer, or a run that hit the loop limit.
+    pub fn failed_to_run(&self) -> bool {
+        self.answer.starts_with("Error:") && !self.max_loops_exceeded
+    }
+
+    pub fn tool_errors(&self) -> usize {
+        self.tool_calls.iter().filter(|c| c.error.is_some()).count()
+    }
+
+    /// Calls to tools the case didn't declare in `expected_tools`; `None` when
+    /// the case declares none.
+    pub fn unnecessary_tool_calls(&self) -> Option<usize> {
+        let expected = self.expected_tools.as_ref()?;
+        Some(self.tool_calls.iter().filter(|c| !expected.contains(&c.tool_name)).count())
+    }
+}
+
+/// Results files from before the matrix held both arms on one line.
+#[derive(Deserialize)]
+struct PairedResult {
+    prompt: String,
+    expected_output: String,
+    #[serde(default)]
+    expected_placeholder: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    with_tools: String,
+    without_tools: String,
+    timestamp: String,
+    #[serde(default)]
+    with_tools_duration_ms: u64,
+    #[serde(default)]
+    without_tools_duration_ms: u64,
+    #[serde(default)]
+    with_tools_correct: Option<bool>,
+    #[serde(default)]
+    without_tools_correct: Option<bool>,
+    #[serde(default)]
+    review_notes: Vec<String>,
+    #[serde(default)]
+    with_tools_judge: Option<JudgeVerdict>,
+    #[serde(default)]
+    without_tools_judge: Option<JudgeVerdict>,
+    /// Absent in files from before auto-scoring, whose labels came from a person
+    #[serde(default)]
+    needs_review: Option<bool>,
+    #[serde(default)]
+    reviewed: Option<bool>,
+}
+
+impl PairedResult {
+    fn split(self) -> [TestResult; 2] {
+        let legacy = self.needs_review.is_none() && self.reviewed.is_none();
+        let arm = |arm: &str, note_prefix: &str, answer: String, duration_ms, correct: Option<bool>, judge| TestResult {
+            arm: arm.to_string(),
+            prompt: self.prompt.clone(),
+            expected_output: self.expected_output.clone(),
+            expected_placeholder: self.expected_placeholder.clone(),
+            category: self.category.clone(),
+            length: answer.len(),
+            answer,
+            timestamp: self.timestamp.clone(),
+            duration_ms,
+            correct,
+            needs_review: correct.is_none(),
+            reviewed: self.reviewed.unwrap_or(legacy && correct.is_some()),
+            review_notes: self.review_notes.iter()
+                .filter_map(|n| n.strip_prefix(note_prefix).map(|n| n.to_string()))
+                .collect(),
+            judge,
+            ..Default::default()
+        };
+        [
+            arm("tools", "with tools: ", self.with_tools.clone(), self.with_tools_duration_ms, self.with_tools_correct, self.with_tools_judge.clone()),
+            arm("no-tools", "without tools: ", self.without_tools.clone(), self.without_tools_duration_ms, self.without_tools_correct, self.without_tools_judge.clone()),
+        ]
+    }
+}
+
+/// Reads a results JSONL file's lines, splitting rows in the older
+/// with/without-tools layout into one result per arm.
+pub fn parse_results(content: &str) -> Result<Vec<TestResult>, AgentError> {
+    let mut results = Vec::new();
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let value: serde_json::Value = serde_json::from_str(line)?;
+        if value.get("answer").is_none() && value.get("with_tools").is_some() {
+            let paired: PairedResult = serde_json::from_value(value)?;
+            results.extend(paired.split());
+        } else {
+            results.push(serde_json::from_value(value)?);
+        }
+    }
+    Ok(results)
+}
+
+pub async fn run_benchmarks(options: BenchmarkOptions) -> Result<String, AgentError> {
+    // placeholders resolve against the same "now" the tools will see
+    let now = options.run.clock().now();
+    let matrix = match &options.matrix {
+        Some(matrix) => matrix.clone(),
+        None => ArmMatrix::standard(&default_model_name()),
+    }.select(&options.arms)?;
+
+    let results_file = options.output.clone().unwrap_or_else(|| {
+        PathBuf::from(format!("results/benchmark_{}.jsonl", Utc::now().format("%Y%m%d_%H%M%S")))
+    });
+    RunManifest::new(&options, &matrix, now).save(&manifest_path(&results_file)).await?;
+    let writer = ResultsWriter::create(&results_file).await?;
+
+    run_plan(options, matrix, now, writer, Vec::new()).await
+}
+
+/// What a resume may change about the original run.
+#[derive(Debug, Clone, Default)]
+pub struct ResumeOptions {
+    pub concurrency: Option<usize>,
+    pub requests_per_minute: Option<u32>,
+    /// As `RunOptions::api_key`, which the manifest doesn't keep
+    pub api_key: Option<String>,
+}
+
+/// Continues an interrupted run from its results file and manifest. Arms
+/// already answered are kept, including ones that hit the loop limit; missing
+/// ones, and ones whose request failed, run again. Answers for cases no longer
+/// in the case files stay in the file.
+pub async fn resume_benchmarks(results_file: PathBuf, resume: ResumeOptions) -> Result<String, AgentError> {
+    let manifest_file = manifest_path(&results_file);
+    let mut manifest = RunManifest::load(&manifest_file).await?;
+    manifest.resumed_at.push(Utc::now());
+    manifest.save(&manifest_file).await?;
+
+    let mut options = manifest.to_options(results_file.clone());
+    if let Some(concurrency) = resume.concurrency {
+        options.concurrency = concurrency;
+    }
+    if let Some(rpm) = resume.requests_per_minute {
+        options.requests_per_minute = Some(rpm);
+    }
+    options.run.api_key = resume.api_key;
+
+    let existing: Vec<TestResult> = read_results_file(&results_file).await?
+        .into_iter()
+        .filter(|r| !r.failed_to_run())
+        .collect();
+    // failed answers are about to be replaced, and a torn last line would
+    // swallow the next append
+    write_results_file(&results_file, &existing).await?;
+    let writer = ResultsWriter::append(&results_file).await?;
+    run_plan(options, manifest.matrix, manifest.now, writer, existing).await
+}
+
+async fn run_plan(
+    options: BenchmarkOptions,
+    matrix: ArmMatrix,
+    now: chrono::DateTime<Utc>,
+    mut writer: ResultsWriter,
+    existing: Vec<TestResult>,
+) -> Result<String, AgentError> {
+    let test_cases = cases::load_selected_cases(&options.cases, now, &options.run.holidays).await?;
+
+    let judge = match options.judge_mode {
+        JudgeMode::Off => None,
+        _ => Some(Arc::new(judge::openai_judge(options.judge_model.clone()).await?)),
+    };
+
+    let mut run_options = RunOptions { verbose: false, ..options.run.clone() }; // verbose = false for benchmarks
+    if let Some(rpm) = options.requests_per_minute {
+        run_options.rate_limiter = Some(Arc::new(RateLimiter::per_minute(rpm)));
+    }
+    let slots = Arc::new(Semaphore::new(options.concurrency.max(1)));
+
+    let total = test_cases.len();
+    let arm_count = matrix.arms.len();
+    let repeat = options.repeat.max(1);
+    let mut slotted: Vec<Option<TestResult>> = vec![None; total * repeat * arm_count];
+    let existing_order: Vec<(String, String, usize)> = existing.iter()
+        .map(|r| (r.id.clone(), r.arm.clone(), r.repeat))
+        .collect();
+    let mut done: HashMap<(String, String, usize), TestResult> = existing.into_iter()
+        .map(|r| ((r.id.clone(), r.arm.clone(), r.repeat), r))
+        .collect();
+
+    let mut tasks = JoinSet::new();
+    for (case_index, test_case) in test_cases.iter().enumerate() {
+        let test_case = Arc::new(test_case.clone());
+        for run_index in 0..repeat {
+            for (arm_index, arm) in matrix.arms.iter().enumerate() {
+                let slot = (case_index * repeat + run_index) * arm_count + arm_index;
+                if let Some(result) = done.remove(&(test_case.id.clone(), arm.id.clone(), run_index)) {
+                    slotted[slot] = Some(result);
+                    continue;
+                }
+                let arm_run = ArmRun {
+                    case_index,
+                    total,
+                    run_index,
+                    repeat,
+                    options: run_options.clone(),
+                    arm: arm.clone(),
+                    slots: slots.clone(),
+                    judge: judge.clone(),
+                    judge_mode: options.judge_mode,
+                    tolerance: options.tolerance,
+                };
+                let test_case = test_case.clone();
+                tasks.spawn(async move { (slot, arm_run.run(&test_case).await) });
+            }
+        }
+    }
+
+    let mut output_lines = vec![
+        "=== Count von Count Tool Benchmarks ===".to_string(),
+        format!("Loaded {} test cases from YAML", total),
+        format!("Arms: {}", matrix.arms.iter().map(|a| a.id.as_str()).collect::<Vec<_>>().join(", ")),
+    ];
+    let already_done = slotted.iter().flatten().count();
+    if already_done > 0 {
+        output_lines.push(format!("Resumed: {} of {} answers already done", already_done, slotted.len()));
+    }
+    if repeat > 1 {
+        output_lines.push(format!("Each case runs {} times per arm", repeat));
+    }
+    if let Some(seed) = options.cases.shuffle_seed {
+        output_lines.push(format!("Shuffled with seed {} (pass --seed {} to repeat)", seed, seed));
+    }
+    output_lines.push("".to_string());
+
+    // written as they finish so an interrupted run loses nothing
+    while let Some(joined) = tasks.join_next().await {
+        let (slot, result) = joined
+            .map_err(|e| AgentError::InvalidInputError(format!("Benchmark task failed: {}", e)))?;
+        writer.write(&result).await?;
+        slotted[slot] = Some(result);
+    }
+    let mut results: Vec<TestResult> = slotted.into_iter().flatten().collect();
+    // earlier answers outside this plan (cases since edited or removed)
+    let unplanned: Vec<TestResult> = existing_order.into_iter().filter_map(|key| done.remove(&key)).collect();
+
+    let width = matrix.arms.iter().map(|a| a.id.len()).max().unwrap_or(0) + 1;
+    for case_results in results.chunks(arm_count.max(1)) {
+        let run_label = if repeat > 1 { format!(" (run {}/{})", case_results[0].repeat + 1, repeat) } else { String::new() };
+        output_lines.push(format!("Benchmark: {}{}", case_results[0].prompt, run_label));
+        for result in case_results {
+            output_lines.push(format!(
+                "  {:<width$} {} ({}ms, {} chars{}) [{}]",
+                format!("{}:", result.arm), truncate_string(&result.answer, 100), result.duration_ms, result.length,
+                tool_summary(result), verdict_label(result.correct),
+            ));
+            if let Some(verdict) = &result.judge {
+                output_lines.push(format!("  {:<width$} Judge: {:?} @ {:.2} - {}", "", verdict.verdict, verdict.confidence, verdict.rationale));
+            }
+        }
+        output_lines.push("".to_string());
+    }
+
+    for arm in &matrix.arms {
+        output_lines.push(accuracy_line(&arm.id, results.iter().filter(|r| r.arm == arm.id)));
+    }
+    output_lines.extend(pair_lines(&matrix, &results));
+    let review_count = results.iter().filter(|r| r.needs_review).count();
+    if review_count > 0 {
+        output_lines.push(format!("{} answer(s) flagged for human review", review_count));
+    }
+
+    // lines went out in completion order; leave the file in YAML then matrix
+    // order, followed by the unplanned answers
+    if !unplanned.is_empty() {
+        output_lines.push(format!("Kept {} earlier answer(s) for cases not in this run", unplanned.len()));
+    }
+    results.extend(unplanned);
+    write_results_file(&writer.path, &results).await?;
+    output_lines.push(format!("Results saved to: {}", writer.path.display()));
+    
+    Ok(output_lines.join("\n"))
+}
+
+/// Everything one arm of one case needs to run on its own task.
+struct ArmRun {
+    case_index: usize,
+    total: usize,
+    run_index: usize,
+    repeat: usize,
+    options: RunOptions,
+    arm: ArmSpec,
+    /// Limits how many arms are in flight at once
+    slots: Arc<Semaphore>,
+    judge: Option<Arc<Judge>>,
+    judge_mode: JudgeMode,
+    tolerance: Tolerance,
+}
+
+impl ArmRun {
+    /// Runs the arm once a slot is free. The clock starts after the slot is
+    /// acquired and rate-limiter waits are subtracted, so latency reflects the
+    /// requests themselves rather than how busy the run was.
+    async fn run(self, test_case: &TestCase) -> TestResult {
+        let (expected_output, answer, duration, trace, max_loops_exceeded) = {
+            let _permit = self.slots.acquire().await;
+            let run_label = if self.repeat > 1 { format!(" run {}/{}", self.run_index + 1, self.repeat) } else { String::new() };
+            println!("Running benchmark {}/{}{} [{}]: {}", self.case_index + 1, self.total, run_label, self.arm.id, test_case.prompt);
+            // the tools and a time-dependent expectation see the same instant,
+            // however long the run has been going
+            let now = self.options.clock().now();
+            let options = RunOptions { now: Some(now), ..self.options.clone() };
+            let expected_output = test_case.expected_placeholder.as_deref()
+                .and_then(|placeholder| resolvers::resolve_expected(placeholder, now, &options.holidays))
+                .unwrap_or_else(|| test_case.expected_output.clone());
+            let start = Instant::now();
+            let run = self.arm.run(&test_case.prompt, &options).await;
+            let duration = start.elapsed().saturating_sub(run.trace.rate_limit_wait);
+            let max_loops_exceeded = matches!(run.output, Err(AgentError::MaxLoopsExceeded { .. }));
+            (expected_output, run.output.unwrap_or_else(|e| format!("Error: {}", e)), duration, run.trace, max_loops_exceeded)
+        };
+
+        let mut result = TestResult {
+            id: test_case.id.clone(),
+            arm: self.arm.id.clone(),
+            model: self.arm.model.clone(),
+            repeat: self.run_index,
+            prompt: test_case.prompt.clone(),
+            expected_output,
+            expected_placeholder: test_case.expected_placeholder.clone(),
+            category: test_case.category.clone(),
+            tags: test_case.tags.clone(),
+            answer_type: test_case.answer_type,
+            tolerance: test_case.tolerance,
+            notes: test_case.notes.clone(),
+            expected_tools: test_case.expected_tools.clone(),
+            tool_calls: trace.tool_calls().cloned().collect(),
+            round_trips: trace.round_trips,
+            max_loops_exceeded,
+            length: answer.len(),
+            answer,
+            timestamp: Utc::now().to_rfc3339(),
+            duration_ms: duration.as_millis() as u64,
+            ..Default::default()
+        };
+        score_result(&mut result, self.tolerance);
+        if let Some(judge) = &self.judge {
+            // a failed judge call shouldn't sink the benchmark
+            if let Err(e) = judge.judge_result(&mut result, self.judge_mode).await {
+                result.review_notes.push(format!("judge: {}", e));
+            }
+        }
+        result
+    }
+}
+
+/// Auto-scores the answer, flagging the result when it can't be decided.
+/// A tolerance set on the case wins over the run's.
+pub fn score_result(result: &mut TestResult, tolerance: Tolerance) {
+    let tolerance = result.tolerance.unwrap_or(tolerance);
+    let score = score_typed(&result.answer, &result.expected_output, result.answer_type, tolerance);
+    result.correct = score.correct();
+    if score.verdict == Verdict::NeedsReview {
+        result.needs_review = true;
+        result.review_notes.push(score.reason);
+    }
+}
+
+/// For each pair of arms that differ only in the compared dimension: how
+/// many units each got right that the other didn't, with significance.
+fn pair_lines(matrix: &ArmMatrix, results: &[TestResult]) -> Vec<String> {
+    matrix.pair_ids().into_iter()
+        .map(|(a, b)| format!("Paired ({:?}) {}", matrix.compare, Comparison::between(&a, &b, results).line()))
+        .collect()
+}
+
+/// Arm ids in the order they first appear.
+pub(crate) fn arm_order(results: &[TestResult]) -> Vec<String> {
+    let mut arms: Vec<String> = Vec::new();
+    for result in results {
+        if !arms.contains(&result.arm) {
+            arms.push(result.arm.clone());
+        }
+    }
+    arms
+}
+
+/// `, tools: calculator, datetime (1 error)`, or nothing without tool calls.
+fn tool_summary(result: &TestResult) -> String {
+    if result.tool_calls.is_empty() {
+        return String::new();
+    }
+    let names: Vec<&str> = result.tool_calls.iter().map(|c| c.tool_name.as_str()).collect();
+    let errors = result.tool_errors();
+    let errors = if errors > 0 { format!(" ({} error{})", errors, if errors == 1 { "" } else { "s" }) } else { String::new() };
+    format!(", tools: {}{}", names.join(", "), errors)
+}
+
+fn verdict_label(correct: Option<bool>) -> &'static str {
+    match correct {
+        Some(true) => "correct",
+        Some(false) => "incorrect",
+        None => "needs review",
+    }
+}
+
+// over all answers and by `verdict()`, like the report, so a judged answer
+// counts and an undecided one counts as not correct
+fn accuracy_line<'a>(label: &str, results: impl Iterator<Item = &'a TestResult>) -> String {
+    let verdicts: Vec<Option<bool>> = results.map(TestResult::verdict).collect();
+    let correct = verdicts.iter().filter(|v| **v == Some(true)).count();
+    let undecided = verdicts.iter().filter(|v| v.is_none()).count();
+    let accuracy = if verdicts.is_empty() { 0.0 } else { 100.0 * correct as f64 / verdicts.len() as f64 };
+    format!("{} accuracy: {}/{} ({:.1}%, {} undecided)", label, correct, verdicts.len(), accuracy, undecided)
+}
+
+/// Appends results to a JSONL file one line at a time.
+struct ResultsWriter {
+    path: PathBuf,
+    file: tokio::fs::File,
+}
+
+impl ResultsWriter {
+    /// Starts a fresh results file, replacing any old one.
+    async fn create(path: &Path) -> Result<Self, AgentError> {
+        Self::open(path, false).await
+    }
+
+    async fn append(path: &Path) -> Result<Self, AgentError> {
+        Self::open(path, true).await
+    }
+
+    async fn open(path: &Path, append: bool) -> Result<Self, AgentError> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await
+                .map_err(|e| AgentError::InvalidInputError(format!("Could not create results directory: {}", e)))?;
+        }
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path).await
+            .map_err(|e| AgentError::InvalidInputError(format!("Could not open {}: {}", path.display(), e)))?;
+        Ok(Self { path: path.to_path_buf(), file })
+    }
+
+    async fn write(&mut self, result: &TestResult) -> Result<(), AgentError> {
+        let line = serde_json::to_string(result)
+            .map_err(|e| AgentError::InvalidInputError(format!("Failed to serialize result: {}", e)))?;
+        self.file.write_all(format!("{}\n", line).as_bytes()).await?;
+        self.file.flush().await?;
+        Ok(())
+    }
+}
+
+/// Reads a results file, dropping a torn last line left by an interrupted write.
+pub async fn read_results_file(path: &Path) -> Result<Vec<TestResult>, AgentError> {
+    let content = tokio::fs::read_to_string(path).await
+        .map_err(|e| AgentError::InvalidInputError(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    match parse_results(&content) {
+        Ok(results) => Ok(results),
+        Err(e) => {
+            let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+            let Some((_, complete)) = lines.split_last() else { return Err(e) };
+            parse_results(&complete.join("\n"))
+                .map_err(|e| AgentError::InvalidInputError(format!("Corrupt results file {}: {}", path.display(), e)))
+        },
+    }
+}
+
+/// Replaces `path` with `results`, via a temporary file so a crash mid-write
+/// can't lose what was there.
+async fn write_results_file(path: &Path, results: &[TestResult]) -> Result<(), AgentError> {
+    let mut content = String::new();
+    for result in results {
+        let line = serde_json::to_string(result)
+            .map_err(|e| AgentError::InvalidInputError(format!("Failed to serialize result: {}", e)))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+
+    let temp = path.with_extension("jsonl.tmp");
+    tokio::fs::write(&temp, content).await
+        .map_err(|e| AgentError::InvalidInputError(format!("Could not save results to {}: {}", temp.display(), e)))?;
+    tokio::fs::rename(&temp, path).await
+        .map_err(|e| AgentError::InvalidInputError(format!("Could not save results to {}: {}", path.display(), e)))?;
+    Ok(())
+}
+
+fn truncate_string(s: &str, max_len: usize) -> String {
+    if s.chars().count() > max_len {
+        let truncated: String = s.chars().take(max_len).collect();
+        format!("{}...", truncated)
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_string() {
+        assert_eq!(truncate_string("short", 10), "short");
+        assert_eq!(truncate_string("this is a very long string", 10), "this is a ...");
+    }
+
+    fn answered(answer: &str) -> TestResult {
+        TestResult {
+            prompt: "How many days until Thanksgiving?".to_string(),
+            expected_output: "98".to_string(),
+            answer: answer.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_score_result_flags_review() {
+        let mut scored = answered("There are **98** days until Thanksgiving!");
+        score_result(&mut scored, Tolerance::default());
+        assert_eq!(scored.correct, Some(true));
+        assert!(!scored.needs_review);
+
+        let mut undecided = answered("It depends on the year.");
+        score_result(&mut undecided, Tolerance::default());
+        assert_eq!(undecided.correct, None);
+        assert!(undecided.needs_review);
+        assert_eq!(undecided.review_notes.len(), 1);
+
+        let json = serde_json::to_value(&undecided).unwrap();
+        assert!(json["correct"].is_null());
+    }
+
+    #[test]
+    fn test_accuracy_line_counts_judged_answers() {
+        let judged = |decision| TestResult {
+            judge: Some(JudgeVerdict { verdict: decision, rationale: String::new(), confidence: 0.9 }),
+            ..answered("It depends")
+        };
+        let results = [
+            TestResult { correct: Some(true), ..answered("98") },
+            judged(judge::JudgeDecision::Correct),
+            judged(judge::JudgeDecision::Incorrect),
+            answered("It depends"),
+        ];
+        assert_eq!(accuracy_line("a", results.iter()), "a accuracy: 2/4 (50.0%, 1 undecided)");
+    }
+
+    #[test]
+    fn test_case_tolerance_wins() {
+        let mut result = answered("About 97 days");
+        result.tolerance = Some(Tolerance { absolute: 0.0, relative: 0.0 });
+        score_result(&mut result, Tolerance::default());
+        assert_eq!(result.correct, Some(false));
+    }
+
+    #[test]
+    fn test_paired_rows_are_split() {
+        let line = r#"{"prompt":"p","expected_output":"1","with_tools":"1","without_tools":"2","timestamp":"t","with_tools_duration_ms":5,"without_tools_duration_ms":6,"with_tools_length":1,"without_tools_length":1,"with_tools_correct":true,"without_tools_correct":null,"needs_review":true,"review_notes":["without tools: no number"]}"#;
+        let results = parse_results(line).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!((results[0].arm.as_str(), results[0].correct), ("tools", Some(true)));
+        assert_eq!((results[1].arm.as_str(), results[1].duration_ms), ("no-tools", 6));
+        assert_eq!(results[1].review_notes, vec!["no number"]);
+
+        let round_trip = serde_json::to_string(&results[0]).unwrap();
+        assert_eq!(parse_results(&round_trip).unwrap()[0].answer, "1");
+    }
+
+    #[test]
+    fn test_pair_lines() {
+        let matrix = ArmMatrix::standard("m");
+        let result = |id: &str, arm: &str, correct| TestResult {
+            id: id.to_string(),
+            arm: arm.to_string(),
+            correct: Some(correct),
+            ..Default::default()
+        };
+        let results = vec![
+            result("a", "m/tools", true), result("a", "m/no-tools", false),
+            result("b", "m/tools", true), result("b", "m/no-tools", true),
+        ];
+        assert_eq!(
+            pair_lines(&matrix, &results),
+            vec!["Paired (ToolSet) m/tools vs m/no-tools: both 1, only first 1, only second 0, neither 0; \
+                accuracy +50.0 [+0.0, +100.0] pts, McNemar p = 1.000; latency +0 [+0, +0] ms [not significant: accuracy, latency]"]
+        );
+    }
+}
@@ -0,0 +1,924 @@
+//! `cvc bench report`: summaries and charts from a results file without the
+//! Python tooling. Produces a self-contained HTML page (inline SVG), a
+//! Markdown summary and CSV exports.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use crate::benchmark::manifest::{manifest_path, RunManifest};
+use crate::benchmark::stats::{format_interval, format_p, Comparison};
+use crate::benchmark::{arm_order, read_results_file, TestResult};
+use crate::error::AgentError;
+
+const PALETTE: &[&str] = &["#3498db", "#e74c3c", "#27ae60", "#f39c12", "#9b59b6", "#1abc9c", "#34495e", "#e67e22"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+    Csv,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "html" => Ok(ReportFormat::Html),
+            "md" | "markdown" => Ok(ReportFormat::Markdown),
+            "csv" => Ok(ReportFormat::Csv),
+            other => Err(format!("Invalid report format '{}'. Use: html, md, or csv", other)),
+        }
+    }
+}
+
+/// Nearest-rank percentiles of a sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub mean: f64,
+}
+
+impl Percentiles {
+    pub fn of(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let rank = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len()) - 1];
+        Self {
+            p50: rank(0.50),
+            p90: rank(0.90),
+            p99: rank(0.99),
+            mean: mean(values).unwrap_or(0.0),
+        }
+    }
+}
+
+/// Tool-call counts over a set of answers.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ToolUsage {
+    pub answers: usize,
+    /// Answers that made at least one tool call
+    pub answers_with_tools: usize,
+    pub calls: usize,
+    pub errors: usize,
+    pub round_trips: usize,
+    pub max_loops_exceeded: usize,
+    /// Calls on cases that declare `expected_tools`
+    pub declared_calls: usize,
+    /// Of those, calls to a tool the case didn't expect
+    pub unnecessary_calls: usize,
+}
+
+impl ToolUsage {
+    pub fn of<'a>(results: impl IntoIterator<Item = &'a TestResult>) -> Self {
+        let mut usage = Self::default();
+        for r in results {
+            usage.answers += 1;
+            usage.answers_with_tools += usize::from(!r.tool_calls.is_empty());
+            usage.calls += r.tool_calls.len();
+            usage.errors += r.tool_errors();
+            usage.round_trips += r.round_trips;
+            usage.max_loops_exceeded += usize::from(r.max_loops_exceeded);
+            if let Some(unnecessary) = r.unnecessary_tool_calls() {
+                usage.declared_calls += r.tool_calls.len();
+                usage.unnecessary_calls += unnecessary;
+            }
+        }
+        usage
+    }
+
+    /// Share of answers that called any tool
+    pub fn call_rate(&self) -> Option<f64> {
+        (self.answers > 0).then(|| self.answers_with_tools as f64 / self.answers as f64)
+    }
+
+    pub fn calls_per_answer(&self) -> Option<f64> {
+        (self.answers > 0).then(|| self.calls as f64 / self.answers as f64)
+    }
+
+    pub fn unnecessary_rate(&self) -> Option<f64> {
+        (self.declared_calls > 0).then(|| self.unnecessary_calls as f64 / self.declared_calls as f64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArmStats {
+    pub arm: String,
+    pub answers: usize,
+    /// Answers with a verdict (auto-scorer, human or judge)
+    pub decided: usize,
+    pub correct: usize,
+    pub latency_ms: Percentiles,
+    pub mean_length: f64,
+    pub mean_length_correct: Option<f64>,
+    pub mean_length_wrong: Option<f64>,
+    pub tools: ToolUsage,
+}
+
+impl ArmStats {
+    /// Correct over all answers; undecided answers count as not correct, so
+    /// arms with different numbers of them stay comparable.
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.answers > 0).then(|| self.correct as f64 / self.answers as f64)
+    }
+
+    pub fn undecided(&self) -> usize {
+        self.answers - self.decided
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryStats {
+    pub category: String,
+    pub arm: String,
+    pub answers: usize,
+    pub decided: usize,
+    pub correct: usize,
+    pub tools: ToolUsage,
+}
+
+impl CategoryStats {
+    /// Correct over all answers, like `ArmStats::accuracy`.
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.answers > 0).then(|| self.correct as f64 / self.answers as f64)
+    }
+
+    pub fn undecided(&self) -> usize {
+        self.answers - self.decided
+    }
+}
+
+pub struct Report {
+    pub source: String,
+    pub arms: Vec<ArmStats>,
+    pub categories: Vec<CategoryStats>,
+    pub comparisons: Vec<Comparison>,
+    pub results: Vec<TestResult>,
+}
+
+impl Report {
+    /// `pairs` are the arms to compare as (first, second) ids.
+    pub fn from_results(results: Vec<TestResult>, source: impl Into<String>, pairs: &[(String, String)]) -> Self {
+        let arm_ids = arm_order(&results);
+
+        let arms = arm_ids.iter().map(|arm| {
+            let rows: Vec<&TestResult> = results.iter().filter(|r| &r.arm == arm).collect();
+            let lengths = |want: bool| -> Vec<f64> {
+                rows.iter().filter(|r| r.verdict() == Some(want)).map(|r| r.length as f64).collect()
+            };
+            ArmStats {
+                arm: arm.clone(),
+                answers: rows.len(),
+                decided: rows.iter().filter(|r| r.verdict().is_some()).count(),
+                correct: rows.iter().filter(|r| r.verdict() == Some(true)).count(),
+                latency_ms: Percentiles::of(&rows.iter().map(|r| r.duration_ms as f64).collect::<Vec<_>>()),
+                mean_length: mean(&rows.iter().map(|r| r.length as f64).collect::<Vec<_>>()).unwrap_or(0.0),
+                mean_length_correct: mean(&lengths(true)),
+                mean_length_wrong: mean(&lengths(false)),
+                tools: ToolUsage::of(rows.iter().copied()),
+            }
+        }).collect();
+
+        let mut category_names: Vec<String> = Vec::new();
+        for result in &results {
+            let name = category_of(result);
+            if !category_names.contains(&name) {
+                category_names.push(name);
+            }
+        }
+        let categories = category_names.iter()
+            .flat_map(|category| arm_ids.iter().map(move |arm| (category, arm)))
+            .map(|(category, arm)| {
+                let rows: Vec<&TestResult> = results.iter()
+                    .filter(|r| &r.arm == arm && &category_of(r) == category)
+                    .collect();
+                CategoryStats {
+                    category: category.clone(),
+                    arm: arm.clone(),
+                    answers: rows.len(),
+                    decided: rows.iter().filter(|r| r.verdict().is_some()).count(),
+                    correct: rows.iter().filter(|r| r.verdict() == Some(true)).count(),
+                    tools: ToolUsage::of(rows.iter().copied()),
+                }
+            })
+            .collect();
+
+        let comparisons = pairs.iter().map(|(a, b)| Comparison::between(a, b, &results)).collect();
+
+        Self { source: source.into(), arms, categories, comparisons, results }
+    }
+
+    fn category_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for stats in &self.categories {
+            if !names.contains(&stats.category.as_str()) {
+                names.push(&stats.category);
+            }
+        }
+        names
+    }
+
+    fn category_accuracy(&self, category: &str, arm: &str) -> Option<f64> {
+        self.categories.iter()
+            .find(|c| c.category == category && c.arm == arm)
+            .and_then(|c| c.accuracy())
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "## Benchmark results\n\nSource: `{}` ({} answers)\n", self.source, self.results.len());
+
+        md.push_str("| Arm | Accuracy | p50 | p90 | p99 | Avg length | Length (correct) | Length (wrong) |\n");
+        md.push_str("|---|---|---|---|---|---|---|---|\n");
+        for arm in &self.arms {
+            let _ = writeln!(
+                md, "| {} | {} | {:.0} ms | {:.0} ms | {:.0} ms | {:.0} | {} | {} |",
+                arm.arm, accuracy_cell(arm.correct, arm.answers, arm.undecided()),
+                arm.latency_ms.p50, arm.latency_ms.p90, arm.latency_ms.p99, arm.mean_length,
+                optional(arm.mean_length_correct), optional(arm.mean_length_wrong),
+            );
+        }
+
+        md.push_str("\n### Accuracy by category\n\n| Category |");
+        for arm in &self.arms {
+            let _ = write!(md, " {} |", arm.arm);
+        }
+        md.push_str("\n|---|");
+        md.push_str(&"---|".repeat(self.arms.len()));
+        md.push('\n');
+        for category in self.category_names() {
+            let _ = write!(md, "| {} |", category);
+            for arm in &self.arms {
+                let stats = self.categories.iter().find(|c| c.category == category && c.arm == arm.arm);
+                let _ = write!(md, " {} |", stats.map_or("-".to_string(), |s| accuracy_cell(s.correct, s.answers, s.undecided())));
+            }
+            md.push('\n');
+        }
+        md.push_str("\nAccuracy is correct answers over all answers. Undecided answers (no verdict from the scorer, a reviewer or the judge) count as not correct.\n");
+
+        if self.results.iter().any(|r| !r.tool_calls.is_empty() || r.round_trips > 0) {
+            md.push_str("\n### Tool use\n\n");
+            md.push_str("| Arm | Answers using tools | Calls per answer | Tool errors | Round trips per answer | Max loops hit | Unnecessary calls |\n");
+            md.push_str("|---|---|---|---|---|---|---|\n");
+            for arm in &self.arms {
+                let t = &arm.tools;
+                let _ = writeln!(
+                    md, "| {} | {} | {:.2} | {} | {:.2} | {} | {} |",
+                    arm.arm, rate_cell(t.call_rate(), t.answers_with_tools, t.answers), t.calls_per_answer().unwrap_or(0.0),
+                    t.errors, t.round_trips as f64 / t.answers.max(1) as f64, t.max_loops_exceeded,
+                    rate_cell(t.unnecessary_rate(), t.unnecessary_calls, t.declared_calls),
+                );
+            }
+
+            md.push_str("\n### Tool-call rate by category\n\n| Category |");
+            for arm in &self.arms {
+                let _ = write!(md, " {} |", arm.arm);
+            }
+            md.push_str("\n|---|");
+            md.push_str(&"---|".repeat(self.arms.len()));
+            md.push('\n');
+            for category in self.category_names() {
+                let _ = write!(md, "| {} |", category);
+                for arm in &self.arms {
+                    let t = self.categories.iter()
+                        .find(|c| c.category == category && c.arm == arm.arm)
+                        .map(|c| c.tools)
+                        .unwrap_or_default();
+                    let _ = write!(md, " {} |", rate_cell(t.call_rate(), t.answers_with_tools, t.answers));
+                }
+                md.push('\n');
+            }
+            md.push_str("\nUnnecessary calls are calls to a tool outside the case's `expected_tools`, counted only on cases that declare them.\n");
+        }
+
+        if !self.comparisons.is_empty() {
+            md.push_str("\n### Paired comparisons\n\n");
+            md.push_str("| First | Second | Units | Only first | Only second | Accuracy Δ pts (95% CI) | McNemar p | Latency Δ ms (95% CI) |\n");
+            md.push_str("|---|---|---|---|---|---|---|---|\n");
+            for c in &self.comparisons {
+                let flag = |significant: bool| if significant { "" } else { " (not significant)" };
+                let _ = writeln!(
+                    md, "| {} | {} | {} | {} | {} | {}{} | {} | {}{} |",
+                    c.first, c.second, c.units, c.only_first, c.only_second,
+                    c.accuracy_delta.map_or("-".to_string(), |d| format_interval(d, 1)), flag(c.accuracy_significant()),
+                    format_p(c.mcnemar_p),
+                    c.latency_delta_ms.map_or("-".to_string(), |d| format_interval(d, 0)), flag(c.latency_significant()),
+                );
+            }
+            let repeats = self.results.iter().map(|r| r.repeat + 1).max().unwrap_or(1);
+            let _ = writeln!(
+                md, "\nΔ is first minus second. Units are (case, run) pairs; {} run(s) per case. \
+                Intervals are case-level bootstrap percentiles. McNemar's test counts each case once (the arm right on more of its runs wins it); \
+                significance is McNemar p < 0.05 for accuracy and an interval excluding 0 for latency.",
+                repeats,
+            );
+        }
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let labels: Vec<String> = self.arms.iter().map(|a| a.arm.clone()).collect();
+        let per_arm = |f: &dyn Fn(&ArmStats) -> f64| -> Vec<f64> { self.arms.iter().map(f).collect() };
+
+        let mut charts = vec![
+            bar_chart("Accuracy", &labels, &per_arm(&|a| 100.0 * a.accuracy().unwrap_or(0.0)), "%", Some(100.0)),
+            grouped_bar_chart(
+                "Latency",
+                &labels,
+                &[
+                    ("p50".to_string(), per_arm(&|a| a.latency_ms.p50)),
+                    ("p90".to_string(), per_arm(&|a| a.latency_ms.p90)),
+                    ("p99".to_string(), per_arm(&|a| a.latency_ms.p99)),
+                ],
+                "ms",
+                None,
+            ),
+            bar_chart("Average Response Length", &labels, &per_arm(&|a| a.mean_length), "chars", None),
+            grouped_bar_chart(
+                "Length by Correctness",
+                &labels,
+                &[
+                    ("correct".to_string(), per_arm(&|a| a.mean_length_correct.unwrap_or(0.0))),
+                    ("wrong".to_string(), per_arm(&|a| a.mean_length_wrong.unwrap_or(0.0))),
+                ],
+                "chars",
+                None,
+            ),
+        ];
+
+        let categories: Vec<String> = self.category_names().into_iter().map(String::from).collect();
+        let series: Vec<(String, Vec<f64>)> = self.arms.iter()
+            .map(|arm| {
+                let values = categories.iter()
+                    .map(|c| 100.0 * self.category_accuracy(c, &arm.arm).unwrap_or(0.0))
+                    .collect();
+                (arm.arm.clone(), values)
+            })
+            .collect();
+        charts.push(grouped_bar_chart("Accuracy by Category", &categories, &series, "%", Some(100.0)));
+
+        if self.arms.iter().any(|a| a.tools.calls > 0) {
+            let series: Vec<(String, Vec<f64>)> = self.arms.iter()
+                .map(|arm| {
+                    let values = categories.iter()
+                        .map(|c| {
+                            let stats = self.categories.iter().find(|s| &s.category == c && s.arm == arm.arm);
+                            100.0 * stats.and_then(|s| s.tools.call_rate()).unwrap_or(0.0)
+                        })
+                        .collect();
+                    (arm.arm.clone(), values)
+                })
+                .collect();
+            charts.push(grouped_bar_chart("Tool-Call Rate by Category", &categories, &series, "% of answers", Some(100.0)));
+        }
+
+        let per_prompt = |f: &dyn Fn(&TestResult) -> f64| -> Vec<(String, Vec<f64>)> {
+            self.arms.iter()
+                .map(|arm| (arm.arm.clone(), self.results.iter().filter(|r| r.arm == arm.arm).map(f).collect()))
+                .collect()
+        };
+        charts.push(line_chart("Time per Prompt", &per_prompt(&|r| r.duration_ms as f64), "ms"));
+        charts.push(line_chart("Length per Prompt", &per_prompt(&|r| r.length as f64), "chars"));
+
+        let mut rows = String::new();
+        for result in &self.results {
+            let verdict = match result.verdict() {
+                Some(true) => "correct",
+                Some(false) => "incorrect",
+                None => "needs review",
+            };
+            let _ = writeln!(
+                rows, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td></tr>",
+                escape_html(&result.id), escape_html(&result.arm), escape_html(&result.prompt), escape_html(&result.expected_output),
+                escape_html(&result.answer), verdict.replace(' ', "-"), verdict, result.duration_ms,
+            );
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Benchmark report</title>
+<style>
+body {{ font-family: -apple-system, "Segoe UI", sans-serif; margin: 2em auto; max-width: 1100px; color: #222; }}
+table {{ border-collapse: collapse; margin: 1em 0; font-size: 14px; }}
+th, td {{ border: 1px solid #ddd; padding: 4px 8px; text-align: left; vertical-align: top; }}
+th {{ background: #f4f4f4; }}
+.charts {{ display: flex; flex-wrap: wrap; gap: 1em; }}
+.correct {{ color: #27ae60; }} .incorrect {{ color: #e74c3c; }} .needs-review {{ color: #f39c12; }}
+details td:nth-child(5) {{ max-width: 420px; white-space: pre-wrap; }}
+</style>
+</head>
+<body>
+<h1>Benchmark report</h1>
+{summary}
+<div class="charts">
+{charts}
+</div>
+<details>
+<summary>All answers ({count})</summary>
+<table>
+<tr><th>Case</th><th>Arm</th><th>Prompt</th><th>Expected</th><th>Answer</th><th>Verdict</th><th>ms</th></tr>
+{rows}</table>
+</details>
+</body>
+</html>
+"#,
+            summary = markdown_tables_to_html(&self.to_markdown()),
+            charts = charts.join("\n"),
+            count = self.results.len(),
+            rows = rows,
+        )
+    }
+
+    /// One row per answer.
+    pub fn results_csv(&self) -> String {
+        let mut csv = String::from("id,arm,model,repeat,category,prompt,expected_output,answer,correct,needs_review,duration_ms,length,\
+            tool_calls,tool_errors,round_trips,max_loops_exceeded,unnecessary_tool_calls\n");
+        for r in &self.results {
+            let correct = r.verdict().map_or(String::new(), |c| c.to_string());
+            let fields = [
+                r.id.clone(), r.arm.clone(), r.model.clone(), r.repeat.to_string(), category_of(r), r.prompt.clone(), r.expected_output.clone(),
+                r.answer.clone(), correct, r.needs_review.to_string(), r.duration_ms.to_string(), r.length.to_string(),
+                r.tool_calls.len().to_string(), r.tool_errors().to_string(), r.round_trips.to_string(), r.max_loops_exceeded.to_string(),
+                r.unnecessary_tool_calls().map_or(String::new(), |n| n.to_string()),
+            ];
+            csv_row(&mut csv, &fields);
+        }
+        csv
+    }
+
+    /// One row per arm.
+    pub fn summary_csv(&self) -> String {
+        let mut csv = String::from("arm,answers,decided,undecided,correct,accuracy,p50_ms,p90_ms,p99_ms,mean_ms,mean_length,mean_length_correct,mean_length_wrong,\
+            answers_with_tools,tool_calls,tool_errors,round_trips,max_loops_exceeded,declared_tool_calls,unnecessary_tool_calls\n");
+        for a in &self.arms {
+            let fields = [
+                a.arm.clone(), a.answers.to_string(), a.decided.to_string(), a.undecided().to_string(), a.correct.to_string(),
+                a.accuracy().map_or(String::new(), |v| format!("{:.4}", v)),
+                format!("{:.0}", a.latency_ms.p50), format!("{:.0}", a.latency_ms.p90), format!("{:.0}", a.latency_ms.p99),
+                format!("{:.0}", a.latency_ms.mean), format!("{:.1}", a.mean_length),
+                a.mean_length_correct.map_or(String::new(), |v| format!("{:.1}", v)),
+                a.mean_length_wrong.map_or(String::new(), |v| format!("{:.1}", v)),
+                a.tools.answers_with_tools.to_string(), a.tools.calls.to_string(), a.tools.errors.to_string(),
+                a.tools.round_trips.to_string(), a.tools.max_loops_exceeded.to_string(),
+                a.tools.declared_calls.to_string(), a.tools.unnecessary_calls.to_string(),
+            ];
+            csv_row(&mut csv, &fields);
+        }
+        csv
+    }
+
+    /// One row per category and arm.
+    pub fn categories_csv(&self) -> String {
+        let mut csv = String::from("category,arm,answers,decided,undecided,correct,accuracy,answers_with_tools,tool_calls,unnecessary_tool_calls\n");
+        for c in &self.categories {
+            let fields = [
+                c.category.clone(), c.arm.clone(), c.answers.to_string(), c.decided.to_string(), c.undecided().to_string(),
+                c.correct.to_string(), c.accuracy().map_or(String::new(), |v| format!("{:.4}", v)),
+                c.tools.answers_with_tools.to_string(), c.tools.calls.to_string(),
+                c.tools.unnecessary_calls.to_string(),
+            ];
+            csv_row(&mut csv, &fields);
+        }
+        csv
+    }
+}
+
+impl Report {
+    /// One row per tool call.
+    pub fn tool_calls_csv(&self) -> String {
+        let mut csv = String::from("id,arm,repeat,category,call,tool,arguments,result,error,expected\n");
+        for r in &self.results {
+            for (i, call) in r.tool_calls.iter().enumerate() {
+                let expected = r.expected_tools.as_ref().map_or(String::new(), |tools| tools.contains(&call.tool_name).to_string());
+                let fields = [
+                    r.id.clone(), r.arm.clone(), r.repeat.to_string(), category_of(r), (i + 1).to_string(), call.tool_name.clone(),
+                    call.arguments.clone(), call.result.clone().unwrap_or_default(), call.error.clone().unwrap_or_default(), expected,
+                ];
+                csv_row(&mut csv, &fields);
+            }
+        }
+        csv
+    }
+
+    /// One row per compared pair of arms.
+    pub fn comparisons_csv(&self) -> String {
+        let mut csv = String::from("first,second,units,cases,both,only_first,only_second,neither,cases_favoring_first,cases_favoring_second,mcnemar_p,\
+            accuracy_delta,accuracy_low,accuracy_high,latency_delta_ms,latency_low_ms,latency_high_ms,accuracy_significant,latency_significant\n");
+        for c in &self.comparisons {
+            let interval = |i: Option<crate::benchmark::stats::Interval>| match i {
+                Some(i) => [format!("{:.2}", i.estimate), format!("{:.2}", i.low), format!("{:.2}", i.high)],
+                None => Default::default(),
+            };
+            let mut fields = vec![
+                c.first.clone(), c.second.clone(), c.units.to_string(), c.cases.to_string(), c.both.to_string(),
+                c.only_first.to_string(), c.only_second.to_string(), c.neither.to_string(),
+                c.cases_favoring_first.to_string(), c.cases_favoring_second.to_string(), format!("{:.6}", c.mcnemar_p),
+            ];
+            fields.extend(interval(c.accuracy_delta));
+            fields.extend(interval(c.latency_delta_ms));
+            fields.push(c.accuracy_significant().to_string());
+            fields.push(c.latency_significant().to_string());
+            csv_row(&mut csv, &fields);
+        }
+        csv
+    }
+}
+
+/// Arms to compare: the run's matrix pairs when its manifest is next to the
+/// results, else every two arms in the file.
+async fn report_pairs(results_file: &Path, results: &[TestResult]) -> Vec<(String, String)> {
+    if let Ok(manifest) = RunManifest::load(&manifest_path(results_file)).await {
+        return manifest.matrix.pair_ids();
+    }
+    let arms = arm_order(results);
+    let mut pairs = Vec::new();
+    for (i, a) in arms.iter().enumerate() {
+        for b in &arms[i + 1..] {
+            pairs.push((a.clone(), b.clone()));
+        }
+    }
+    pairs
+}
+
+/// Reads `results_file` and writes the requested formats into `out_dir`
+/// (default: `<results stem>_report/` next to it). Returns the files written.
+pub async fn write_report(results_file: &Path, out_dir: Option<PathBuf>, formats: &[ReportFormat]) -> Result<Vec<PathBuf>, AgentError> {
+    let results = read_results_file(results_file).await?;
+    let pairs = report_pairs(results_file, &results).await;
+    let report = Report::from_results(results, results_file.display().to_string(), &pairs);
+
+    let out_dir = out_dir.unwrap_or_else(|| {
+        let stem = results_file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        results_file.with_file_name(format!("{}_report", stem))
+    });
+    tokio::fs::create_dir_all(&out_dir).await
+        .map_err(|e| AgentError::InvalidInputError(format!("Could not create {}: {}", out_dir.display(), e)))?;
+
+    let mut files: Vec<(&str, String)> = Vec::new();
+    for format in formats {
+        match format {
+            ReportFormat::Html => files.push(("report.html", report.to_html())),
+            ReportFormat::Markdown => files.push(("report.md", report.to_markdown())),
+            ReportFormat::Csv => {
+                files.push(("results.csv", report.results_csv()));
+                files.push(("summary.csv", report.summary_csv()));
+                files.push(("categories.csv", report.categories_csv()));
+                files.push(("comparisons.csv", report.comparisons_csv()));
+                files.push(("tool_calls.csv", report.tool_calls_csv()));
+            },
+        }
+    }
+
+    let mut written = Vec::new();
+    for (name, content) in files {
+        let path = out_dir.join(name);
+        tokio::fs::write(&path, content).await
+            .map_err(|e| AgentError::InvalidInputError(format!("Could not write {}: {}", path.display(), e)))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+fn category_of(result: &TestResult) -> String {
+    result.category.clone().unwrap_or_else(|| "Uncategorized".to_string())
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn accuracy_cell(correct: usize, answers: usize, undecided: usize) -> String {
+    if answers == 0 {
+        return "-".to_string();
+    }
+    let cell = format!("{:.1}% ({}/{})", 100.0 * correct as f64 / answers as f64, correct, answers);
+    if undecided > 0 { format!("{}, {} undecided", cell, undecided) } else { cell }
+}
+
+fn rate_cell(rate: Option<f64>, count: usize, total: usize) -> String {
+    rate.map_or("-".to_string(), |r| format!("{:.1}% ({}/{})", 100.0 * r, count, total))
+}
+
+fn optional(value: Option<f64>) -> String {
+    value.map_or("-".to_string(), |v| format!("{:.0}", v))
+}
+
+fn csv_row(csv: &mut String, fields: &[String]) {
+    let escaped: Vec<String> = fields.iter()
+        .map(|f| if f.contains([',', '"', '\n', '\r']) { format!("\"{}\"", f.replace('"', "\"\"")) } else { f.clone() })
+        .collect();
+    csv.push_str(&escaped.join(","));
+    csv.push('\n');
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Just enough Markdown for our own summary: headings, paragraphs, pipe tables.
+fn markdown_tables_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut in_table = false;
+    for line in markdown.lines() {
+        let cells = || line.trim_matches('|').split('|').map(|c| escape_html(c.trim())).collect::<Vec<_>>();
+        if line.starts_with('|') {
+            if line.starts_with("|---") {
+                continue;
+            }
+            let tag = if in_table { "td" } else { "th" };
+            if !in_table {
+                html.push_str("<table>\n");
+                in_table = true;
+            }
+            let row: String = cells().iter().map(|c| format!("<{tag}>{c}</{tag}>")).collect();
+            let _ = writeln!(html, "<tr>{}</tr>", row);
+            continue;
+        }
+        if in_table {
+            html.push_str("</table>\n");
+            in_table = false;
+        }
+        if let Some(heading) = line.strip_prefix("### ") {
+            let _ = writeln!(html, "<h3>{}</h3>", escape_html(heading));
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            let _ = writeln!(html, "<h2>{}</h2>", escape_html(heading));
+        } else if !line.trim().is_empty() {
+            let _ = writeln!(html, "<p>{}</p>", escape_html(&line.replace('`', "")));
+        }
+    }
+    if in_table {
+        html.push_str("</table>\n");
+    }
+    html
+}
+
+const CHART_WIDTH: f64 = 520.0;
+const CHART_HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 56.0;
+const MARGIN_TOP: f64 = 36.0;
+const MARGIN_BOTTOM: f64 = 90.0;
+const MARGIN_RIGHT: f64 = 16.0;
+
+struct Plot {
+    svg: String,
+    max: f64,
+}
+
+impl Plot {
+    fn new(title: &str, max: f64, unit: &str) -> Self {
+        let max = if max > 0.0 { max } else { 1.0 };
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="11">
+<text x="{cx}" y="20" text-anchor="middle" font-size="14" font-weight="bold">{title}</text>
+"#,
+            w = CHART_WIDTH, h = CHART_HEIGHT, cx = CHART_WIDTH / 2.0, title = escape_html(title),
+        );
+        // y axis with five gridlines
+        for step in 0..=4 {
+            let value = max * step as f64 / 4.0;
+            let y = MARGIN_TOP + Self::plot_height() * (1.0 - step as f64 / 4.0);
+            let _ = writeln!(
+                svg, r##"<line x1="{x1}" y1="{y:.1}" x2="{x2}" y2="{y:.1}" stroke="#e5e5e5"/><text x="{tx}" y="{ty:.1}" text-anchor="end">{value}</text>"##,
+                x1 = MARGIN_LEFT, x2 = CHART_WIDTH - MARGIN_RIGHT, tx = MARGIN_LEFT - 4.0, ty = y + 4.0, value = short_number(value),
+            );
+        }
+        let _ = writeln!(
+            svg, r#"<text x="12" y="{y}" transform="rotate(-90 12 {y})" text-anchor="middle">{unit}</text>"#,
+            y = MARGIN_TOP + Self::plot_height() / 2.0, unit = escape_html(unit),
+        );
+        Self { svg, max }
+    }
+
+    fn plot_width() -> f64 {
+        CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT
+    }
+
+    fn plot_height() -> f64 {
+        CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        MARGIN_TOP + Self::plot_height() * (1.0 - (value / self.max).clamp(0.0, 1.0))
+    }
+
+    fn x_label(&mut self, x: f64, label: &str) {
+        let y = CHART_HEIGHT - MARGIN_BOTTOM + 14.0;
+        let _ = writeln!(
+            self.svg, r#"<text x="{x:.1}" y="{y}" text-anchor="end" transform="rotate(-30 {x:.1} {y})">{label}</text>"#,
+            label = escape_html(label),
+        );
+    }
+
+    fn legend(&mut self, names: &[&str]) {
+        for (i, name) in names.iter().enumerate() {
+            let y = MARGIN_TOP + 4.0 + 14.0 * i as f64;
+            let x = CHART_WIDTH - MARGIN_RIGHT - 130.0;
+            let _ = writeln!(
+                self.svg, r#"<rect x="{x}" y="{y}" width="10" height="10" fill="{color}"/><text x="{tx}" y="{ty}">{name}</text>"#,
+                color = PALETTE[i % PALETTE.len()], tx = x + 14.0, ty = y + 9.0, name = escape_html(name),
+            );
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.svg.push_str("</svg>");
+        self.svg
+    }
+}
+
+fn bar_chart(title: &str, labels: &[String], values: &[f64], unit: &str, max: Option<f64>) -> String {
+    grouped_bar_chart(title, labels, &[(String::new(), values.to_vec())], unit, max)
+}
+
+/// Bars for each label, one per series; a single unnamed series colours by label.
+fn grouped_bar_chart(title: &str, labels: &[String], series: &[(String, Vec<f64>)], unit: &str, max: Option<f64>) -> String {
+    let top = max.unwrap_or_else(|| nice_max(series.iter().flat_map(|(_, v)| v.iter().copied())));
+    let mut plot = Plot::new(title, top, unit);
+    let single = series.len() == 1 && series[0].0.is_empty();
+
+    let slot = Plot::plot_width() / labels.len().max(1) as f64;
+    let bar = (slot * 0.8) / series.len().max(1) as f64;
+    for (i, label) in labels.iter().enumerate() {
+        let left = MARGIN_LEFT + slot * i as f64 + slot * 0.1;
+        for (s, (_, values)) in series.iter().enumerate() {
+            let value = values.get(i).copied().unwrap_or(0.0);
+            let y = plot.y(value);
+            let color = PALETTE[if single { i } else { s } % PALETTE.len()];
+            let _ = writeln!(
+                plot.svg, r#"<rect x="{x:.1}" y="{y:.1}" width="{w:.1}" height="{h:.1}" fill="{color}" opacity="0.85"><title>{label}: {value:.1}</title></rect>"#,
+                x = left + bar * s as f64, w = bar, h = CHART_HEIGHT - MARGIN_BOTTOM - y, label = escape_html(label),
+            );
+            if single || series.len() <= 3 {
+                let _ = writeln!(
+                    plot.svg, r#"<text x="{x:.1}" y="{ty:.1}" text-anchor="middle" font-size="10">{value}</text>"#,
+                    x = left + bar * (s as f64 + 0.5), ty = y - 3.0, value = short_number(value),
+                );
+            }
+        }
+        plot.x_label(left + slot * 0.4, label);
+    }
+    if !single {
+        let names: Vec<&str> = series.iter().map(|(name, _)| name.as_str()).collect();
+        plot.legend(&names);
+    }
+    plot.finish()
+}
+
+fn line_chart(title: &str, series: &[(String, Vec<f64>)], unit: &str) -> String {
+    let top = nice_max(series.iter().flat_map(|(_, v)| v.iter().copied()));
+    let mut plot = Plot::new(title, top, unit);
+    let points = series.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+    let step = Plot::plot_width() / (points.max(2) - 1) as f64;
+
+    for (s, (_, values)) in series.iter().enumerate() {
+        let coords: Vec<String> = values.iter().enumerate()
+            .map(|(i, v)| format!("{:.1},{:.1}", MARGIN_LEFT + step * i as f64, plot.y(*v)))
+            .collect();
+        let _ = writeln!(
+            plot.svg, r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            coords.join(" "), PALETTE[s % PALETTE.len()],
+        );
+    }
+    let _ = writeln!(
+        plot.svg, r#"<text x="{x}" y="{y}" text-anchor="middle">prompt (1-{points})</text>"#,
+        x = MARGIN_LEFT + Plot::plot_width() / 2.0, y = CHART_HEIGHT - MARGIN_BOTTOM + 18.0,
+    );
+    let names: Vec<&str> = series.iter().map(|(name, _)| name.as_str()).collect();
+    plot.legend(&names);
+    plot.finish()
+}
+
+// Rounds the axis top up to 1, 2 or 5 times a power of ten.
+fn nice_max(values: impl Iterator<Item = f64>) -> f64 {
+    let max = values.fold(0.0_f64, f64::max);
+    if max <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(max.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].iter().map(|m| m * magnitude).find(|top| *top >= max).unwrap_or(max)
+}
+
+fn short_number(value: f64) -> String {
+    if value >= 10_000.0 {
+        format!("{:.0}k", value / 1000.0)
+    } else if value.fract() == 0.0 || value >= 100.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::ToolCallRecord;
+
+    fn result(id: &str, arm: &str, category: &str, correct: Option<bool>, duration_ms: u64, length: usize) -> TestResult {
+        TestResult {
+            id: id.to_string(),
+            arm: arm.to_string(),
+            category: Some(category.to_string()),
+            prompt: format!("prompt {}", id),
+            answer: "x".repeat(length),
+            correct,
+            duration_ms,
+            length,
+            ..Default::default()
+        }
+    }
+
+    fn sample() -> Report {
+        Report::from_results(vec![
+            result("a", "tools", "Math", Some(true), 100, 10),
+            result("a", "no-tools", "Math", Some(false), 50, 40),
+            result("b", "tools", "Dates, hard", Some(true), 300, 20),
+            result("b", "no-tools", "Dates, hard", None, 70, 30),
+        ], "results.jsonl", &[("tools".to_string(), "no-tools".to_string())])
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let values: Vec<f64> = (1..=100).map(f64::from).collect();
+        let p = Percentiles::of(&values);
+        assert_eq!((p.p50, p.p90, p.p99), (50.0, 90.0, 99.0));
+        assert_eq!(p.mean, 50.5);
+        assert_eq!(Percentiles::of(&[7.0]).p99, 7.0);
+    }
+
+    #[test]
+    fn test_arm_and_category_stats() {
+        let report = sample();
+        let tools = &report.arms[0];
+        assert_eq!((tools.arm.as_str(), tools.correct, tools.decided), ("tools", 2, 2));
+        assert_eq!(tools.mean_length_correct, Some(15.0));
+        assert_eq!(tools.mean_length_wrong, None);
+        assert_eq!((report.arms[1].accuracy(), report.arms[1].undecided()), (Some(0.0), 1));
+        assert_eq!(report.category_accuracy("Dates, hard", "no-tools"), Some(0.0));
+        assert_eq!(report.category_accuracy("Dates, hard", "judge"), None);
+    }
+
+    #[test]
+    fn test_outputs() {
+        let report = sample();
+        let md = report.to_markdown();
+        assert!(md.contains("| tools | 100.0% (2/2) | 100 ms | 300 ms | 300 ms | 15 | 15 | - |"), "{}", md);
+        assert!(md.contains("| Dates, hard | 100.0% (1/1) | 0.0% (0/1), 1 undecided |"), "{}", md);
+        assert!(md.contains("| no-tools | 0.0% (0/2), 1 undecided |"), "{}", md);
+
+        let html = report.to_html();
+        assert!(html.contains("<svg") && html.contains("Accuracy by Category"));
+        assert!(!html.contains("<script"));
+
+        let csv = report.categories_csv();
+        assert!(csv.contains("\"Dates, hard\",tools,1,1,0,1,1.0000"), "{}", csv);
+        assert!(csv.contains("\"Dates, hard\",no-tools,1,0,1,0,0.0000"), "{}", csv);
+        assert!(report.summary_csv().contains("\nno-tools,2,1,1,0,0.0000,"));
+        assert_eq!(report.results_csv().lines().count(), 5);
+
+        assert!(md.contains("| tools | no-tools | 2 | 1 | 0 | +100.0 [+100.0, +100.0] (not significant) | 1.000 | +140 [+50, +230] |"), "{}", md);
+        assert!(report.comparisons_csv().lines().nth(1).unwrap().ends_with(",false,true"));
+    }
+
+    #[test]
+    fn test_tool_usage() {
+        let call = |tool: &str, error: Option<&str>| ToolCallRecord {
+            tool_name: tool.to_string(),
+            arguments: "{}".to_string(),
+            result: error.is_none().then(|| "ok".to_string()),
+            error: error.map(String::from),
+        };
+        let mut math = result("a", "tools", "Math", Some(true), 100, 10);
+        math.expected_tools = Some(vec!["calculator".to_string()]);
+        math.tool_calls = vec![call("datetime", None), call("calculator", Some("parse error"))];
+        math.round_trips = 3;
+        let mut looped = result("b", "tools", "Dates", None, 100, 10);
+        looped.tool_calls = vec![call("datetime", None)];
+        looped.max_loops_exceeded = true;
+        looped.round_trips = 5;
+        let plain = result("c", "tools", "Math", Some(true), 100, 10);
+
+        let report = Report::from_results(vec![math, looped, plain], "r.jsonl", &[]);
+        let tools = report.arms[0].tools;
+        assert_eq!((tools.answers_with_tools, tools.calls, tools.errors, tools.max_loops_exceeded), (2, 3, 1, 1));
+        assert_eq!((tools.unnecessary_calls, tools.declared_calls), (1, 2));
+
+        let md = report.to_markdown();
+        assert!(md.contains("| tools | 66.7% (2/3) | 1.00 | 1 | 2.67 | 1 | 50.0% (1/2) |"), "{}", md);
+        assert!(md.contains("| Math | 50.0% (1/2) |"), "{}", md);
+        assert!(report.tool_calls_csv().contains("a,tools,0,Math,1,datetime,{},ok,,false"));
+    }
+
+    #[test]
+    fn test_nice_max() {
+        assert_eq!(nice_max([73.0].into_iter()), 100.0);
+        assert_eq!(nice_max([1234.0].into_iter()), 2000.0);
+        assert_eq!(nice_max(std::iter::empty()), 1.0);
+    }
+}
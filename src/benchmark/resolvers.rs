@@ -0,0 +1,267 @@
+//! Named resolvers for time-dependent expected answers.
+//!
+//! `test_cases.yaml` writes these as `days_to_thanksgiving (98)`: the resolver
+//! name, then the value it had when the case was written. At run time the name
+//! is looked up here and the expectation is recomputed from the run's clock.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use crate::tools::astronomy::{next_phase, next_season, MoonPhase, Season};
+use crate::tools::holidays::{HolidayCalendar, DEFAULT_REGION};
+
+type Resolver = fn(DateTime<Utc>, &HolidayCalendar) -> String;
+
+const RESOLVERS: &[(&str, Resolver)] = &[
+    // named days, from the run's holiday calendar (US)
+    ("days_to_christmas", |now, holidays| days_to_holiday(now, holidays, "Christmas")),
+    ("days_to_new_year", |now, holidays| days_to_holiday(now, holidays, "New Year's Day")),
+    ("days_to_valentines", |now, holidays| days_to_holiday(now, holidays, "Valentine's Day")),
+    ("days_to_july_4", |now, holidays| days_to_holiday(now, holidays, "Independence Day")),
+    ("days_to_halloween", |now, holidays| days_to_holiday(now, holidays, "Halloween")),
+    ("days_to_march_15", |now, _| days_to_annual(now, 3, 15)),
+    ("days_to_april_1", |now, holidays| days_to_holiday(now, holidays, "April Fools' Day")),
+    ("days_to_earth_day", |now, holidays| days_to_holiday(now, holidays, "Earth Day")),
+    ("days_to_groundhog_day", |now, holidays| days_to_holiday(now, holidays, "Groundhog Day")),
+    ("days_to_st_patricks", |now, holidays| days_to_holiday(now, holidays, "St. Patrick's Day")),
+    ("days_to_veterans_day", |now, holidays| days_to_holiday(now, holidays, "Veterans Day")),
+    ("days_to_thanksgiving", |now, holidays| days_to_holiday(now, holidays, "Thanksgiving")),
+    ("days_to_labor_day", |now, holidays| days_to_holiday(now, holidays, "Labor Day")),
+    ("days_to_memorial_day", |now, holidays| days_to_holiday(now, holidays, "Memorial Day")),
+    ("days_to_mothers_day", |now, holidays| days_to_holiday(now, holidays, "Mother's Day")),
+    ("days_to_fathers_day", |now, holidays| days_to_holiday(now, holidays, "Father's Day")),
+    ("days_to_columbus_day", |now, holidays| days_to_holiday(now, holidays, "Columbus Day")),
+    ("days_to_indigenous_peoples_day", |now, holidays| days_to_holiday(now, holidays, "Indigenous Peoples' Day")),
+    ("days_to_presidents_day", |now, holidays| days_to_holiday(now, holidays, "Presidents' Day")),
+    ("days_to_mlk_day", |now, holidays| days_to_holiday(now, holidays, "MLK Day")),
+    // astronomical
+    ("days_to_summer_solstice", |now, _| days_to_season(now, Season::JuneSolstice)),
+    ("days_to_winter_solstice", |now, _| days_to_season(now, Season::DecemberSolstice)),
+    ("days_to_full_moon", |now, _| days_to_full_moon(now)),
+    // calendar spans
+    ("days_to_month_end", |now, _| (last_day_of_month(now.date_naive()) - now.date_naive()).num_days().to_string()),
+    ("days_to_year_end", |now, _| days_to_annual(now, 12, 31)),
+    ("days_to_month_end_from_15th", |now, _| (last_day_of_month(now.date_naive()).day() - 15).to_string()),
+    ("weeks_to_dec_31", |now, _| {
+        let days = days_to_annual(now, 12, 31).parse::<f64>().unwrap_or(0.0);
+        format!("{}", (days / 7.0).round())
+    }),
+    ("months_to_june_8", |now, _| months_until(now.date_naive(), 6, 8).to_string()),
+    ("leap_days", |now, holidays| days_to_holiday(now, holidays, "Leap Day")),
+    ("sleep_hours_to_new_year", |now, _| {
+        let days: i64 = days_to_annual(now, 1, 1).parse().unwrap_or(0);
+        (days * 8).to_string()
+    }),
+    ("business_days_left", |now, _| business_days_left(now)),
+    ("tuesdays_left", |now, _| weekdays_left_in_year(now.date_naive(), Weekday::Tue).to_string()),
+    ("sundays_left", |now, _| weekdays_left_in_year(now.date_naive(), Weekday::Sun).to_string()),
+    ("weekends_left", |now, _| weekdays_left_in_year(now.date_naive(), Weekday::Sat).to_string()),
+    ("days_since_friday_13th", |now, _| days_since_friday_13th(now)),
+    // time of day
+    ("timestamp_and_seconds", |now, _| {
+        let midnight = (now.date_naive() + Duration::days(1)).and_time(NaiveTime::MIN).and_utc();
+        (midnight - now).num_seconds().to_string()
+    }),
+    ("hours_since_midnight", |now, _| {
+        let midnight = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+        format!("{:.2}", (now - midnight).num_seconds() as f64 / 3600.0)
+    }),
+    ("seconds_this_hour", |now, _| (now.minute() * 60 + now.second()).to_string()),
+    ("time_and_minutes_left", |now, _| (60 - now.minute()).to_string()),
+    ("hours_to_monday_9am", |now, _| hours_until_weekday_at(now, Weekday::Mon, 9).to_string()),
+    ("hours_to_sunday_noon", |now, _| {
+        // the prompt sets "today" to Wednesday, keep only the time of day
+        let hours_into_day = now.hour() as i64;
+        (4 * 24 + 12 - hours_into_day).to_string()
+    }),
+    ("time_plus_2h45m", |now, _| {
+        // the prompt sets the time to quarter past, keep only the hour
+        format!("{:02}:00", (now.hour() + 3) % 24)
+    }),
+    ("time_plus_1000_min", |now, _| (now + Duration::minutes(1000)).format("%H:%M").to_string()),
+    ("intervals_since_6am", |now, _| {
+        let six = now.date_naive().and_hms_opt(6, 0, 0).unwrap_or_default().and_utc();
+        ((now - six).num_minutes() / 15).to_string()
+    }),
+    ("seconds_to_even_hour", |now, _| {
+        let hours_ahead = if now.hour() % 2 == 0 { 2 } else { 1 };
+        let top_of_hour = now.date_naive().and_hms_opt(now.hour(), 0, 0).unwrap_or_default().and_utc();
+        (top_of_hour + Duration::hours(hours_ahead) - now).num_seconds().to_string()
+    }),
+];
+
+pub fn resolver_names() -> Vec<&'static str> {
+    RESOLVERS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Resolves a placeholder expectation such as `days_to_christmas (126)`, with
+/// named days taken from `holidays`. Returns `None` when the expectation is a
+/// literal value or names no resolver.
+pub fn resolve_expected(expected: &str, now: DateTime<Utc>, holidays: &HolidayCalendar) -> Option<String> {
+    let name = expected.split(|c: char| c.is_whitespace() || c == '(').next()?;
+    RESOLVERS.iter()
+        .find(|(resolver_name, _)| *resolver_name == name)
+        .map(|(_, resolver)| resolver(now, holidays))
+}
+
+/// Days from today to the next occurrence of a holiday (today counts as 0).
+fn days_to_holiday(now: DateTime<Utc>, holidays: &HolidayCalendar, name: &str) -> String {
+    let today = now.date_naive();
+    holidays.next_on_or_after(name, DEFAULT_REGION, today)
+        .map(|day| (day - today).num_days().to_string())
+        .unwrap_or_default()
+}
+
+fn days_to_annual(now: DateTime<Utc>, month: u32, day: u32) -> String {
+    days_to_rule(now, |year| NaiveDate::from_ymd_opt(year, month, day).unwrap_or_default())
+}
+
+/// Days from today to the next date produced by `rule` (today counts as 0).
+fn days_to_rule(now: DateTime<Utc>, rule: impl Fn(i32) -> NaiveDate) -> String {
+    let today = now.date_naive();
+    let this_year = rule(today.year());
+    let next = if this_year >= today { this_year } else { rule(today.year() + 1) };
+    (next - today).num_days().to_string()
+}
+
+fn last_day_of_month(date: NaiveDate) -> NaiveDate {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or_default().pred_opt().unwrap_or(date)
+}
+
+/// Whole months until the next `month`/`day`.
+fn months_until(today: NaiveDate, month: u32, day: u32) -> i32 {
+    let mut months = (month as i32 - today.month() as i32).rem_euclid(12);
+    if months == 0 && today.day() > day {
+        months = 12;
+    }
+    if today.day() > day { months - 1 } else { months }
+}
+
+/// Weekdays from today (inclusive) through the end of the month.
+fn business_days_left(now: DateTime<Utc>) -> String {
+    let today = now.date_naive();
+    let end = last_day_of_month(today);
+    today.iter_days()
+        .take_while(|d| *d <= end)
+        .filter(|d| !matches!(d.weekday(), Weekday::Sat | Weekday::Sun))
+        .count()
+        .to_string()
+}
+
+/// Occurrences of `weekday` after today through December 31st, counting today
+/// only for Saturdays so a weekend in progress still counts.
+fn weekdays_left_in_year(today: NaiveDate, weekday: Weekday) -> usize {
+    let end = NaiveDate::from_ymd_opt(today.year(), 12, 31).unwrap_or(today);
+    let start = if weekday == Weekday::Sat { today } else { today.succ_opt().unwrap_or(today) };
+    start.iter_days()
+        .take_while(|d| *d <= end)
+        .filter(|d| d.weekday() == weekday)
+        .count()
+}
+
+fn days_since_friday_13th(now: DateTime<Utc>) -> String {
+    let today = now.date_naive();
+    let mut day = today;
+    while !(day.day() == 13 && day.weekday() == Weekday::Fri) {
+        day = day.pred_opt().unwrap_or(day);
+    }
+    (today - day).num_days().to_string()
+}
+
+fn hours_until_weekday_at(now: DateTime<Utc>, weekday: Weekday, hour: u32) -> i64 {
+    let mut day = now.date_naive();
+    loop {
+        let target = day.and_hms_opt(hour, 0, 0).unwrap_or_default().and_utc();
+        if day.weekday() == weekday && target > now {
+            return (target - now).num_hours();
+        }
+        day = day.succ_opt().unwrap_or(day);
+    }
+}
+
+fn days_to_full_moon(now: DateTime<Utc>) -> String {
+    let full_moon = next_phase(MoonPhase::Full, now);
+    (full_moon.date_naive() - now.date_naive()).num_days().to_string()
+}
+
+/// Days to the next solstice or equinox, by UTC date (today counts as 0).
+fn days_to_season(now: DateTime<Utc>, season: Season) -> String {
+    let today = now.date_naive();
+    let start_of_today = today.and_time(NaiveTime::MIN).and_utc();
+    (next_season(season, start_of_today).date_naive() - today).num_days().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::parse_instant;
+
+    // roughly when test_cases.yaml was written; the values in parentheses there
+    // were computed around this instant
+    fn written_at() -> DateTime<Utc> {
+        parse_instant("2025-08-21T12:50:00Z").unwrap()
+    }
+
+    fn resolve(expected: &str, now: DateTime<Utc>) -> Option<String> {
+        resolve_expected(expected, now, &HolidayCalendar::builtin())
+    }
+
+    #[test]
+    fn test_literal_expectations_are_not_resolved() {
+        assert_eq!(resolve("1,705,343", written_at()), None);
+        assert_eq!(resolve("unknown_placeholder (3)", written_at()), None);
+    }
+
+    // recorded values that were already off when written (e.g. the 2026 June
+    // solstice falls on the 21st, not the 20th)
+    const KNOWN_WRONG: &[&str] = &["days_to_full_moon", "hours_to_sunday_noon", "days_to_summer_solstice"];
+
+    #[test]
+    fn test_resolvers_match_recorded_values() {
+        let path = std::path::Path::new("test_cases.yaml");
+        let yaml = std::fs::read_to_string(path).unwrap();
+        let cases = crate::benchmark::cases::parse_test_cases(&yaml, path, written_at(), &HolidayCalendar::builtin()).unwrap();
+        let mut checked = 0;
+        for case in cases {
+            let Some(expected) = case.expected_placeholder else { continue };
+            let Some((name, rest)) = expected.split_once(" (") else { continue };
+            let recorded = rest.trim_end_matches(')');
+            if recorded == "varies" || KNOWN_WRONG.contains(&name) {
+                continue;
+            }
+            let resolved = resolve(name, written_at())
+                .unwrap_or_else(|| panic!("no resolver for {}", name));
+            assert_eq!(resolved, recorded, "resolver {}", name);
+            checked += 1;
+        }
+        assert!(checked > 30);
+    }
+
+    #[test]
+    fn test_holidays_count_today_as_zero() {
+        assert_eq!(resolve("days_to_christmas", parse_instant("2025-12-25T08:00:00Z").unwrap()), Some("0".to_string()));
+        assert_eq!(resolve("days_to_christmas", parse_instant("2025-12-26T08:00:00Z").unwrap()), Some("364".to_string()));
+    }
+
+    #[test]
+    fn test_holidays_come_from_the_run_calendar() {
+        let calendar: HolidayCalendar = serde_yaml::from_str(
+            "holidays:\n  - {name: Thanksgiving, regions: [US], type: fixed, month: 11, day: 1}\n"
+        ).unwrap();
+        assert_eq!(resolve("days_to_thanksgiving", written_at()), Some("98".to_string()));
+        assert_eq!(resolve_expected("days_to_thanksgiving", written_at(), &calendar), Some("72".to_string()));
+    }
+
+    #[test]
+    fn test_time_plus_2h45m_starts_from_quarter_past() {
+        assert_eq!(resolve("time_plus_2h45m", parse_instant("2026-08-31T14:40:00Z").unwrap()), Some("17:00".to_string()));
+        assert_eq!(resolve("time_plus_2h45m", parse_instant("2026-08-31T22:05:00Z").unwrap()), Some("01:00".to_string()));
+    }
+
+    #[test]
+    fn test_astronomical_resolvers() {
+        assert_eq!(resolve("days_to_full_moon", written_at()), Some("17".to_string()));
+        assert_eq!(resolve("days_to_winter_solstice", written_at()), Some("122".to_string()));
+        // the solstice instant is later in the day, but the day itself counts as 0
+        assert_eq!(resolve("days_to_summer_solstice", parse_instant("2026-06-21T00:30:00Z").unwrap()), Some("0".to_string()));
+    }
+}
@@ -0,0 +1,655 @@
+//! `cvc bench score`: a terminal UI for human scoring. Every arm of a case is
+//! shown side by side with the expected value and the auto-scorer's proposal;
+//! the scored file is rewritten after every change.
+
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+use crate::benchmark::manifest::{manifest_path, RunManifest};
+use crate::benchmark::scoring::{score_typed, Score, Tolerance};
+use crate::benchmark::{parse_results, TestResult};
+use crate::error::AgentError;
+
+/// `results/run.jsonl` -> `results/run_scored.jsonl`
+pub fn scored_path(results_file: &Path) -> PathBuf {
+    let stem = results_file.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    results_file.with_file_name(format!("{}_scored.jsonl", stem))
+}
+
+/// One undoable change: the row as it was, and where the cursor was.
+struct Edit {
+    index: usize,
+    before: TestResult,
+    case: usize,
+    arm: usize,
+}
+
+/// Scoring state, independent of the terminal.
+pub struct ScoreSession {
+    pub results: Vec<TestResult>,
+    output: PathBuf,
+    tolerance: Tolerance,
+    /// Result indices per (case, repeat), arms in file order
+    cases: Vec<Vec<usize>>,
+    /// Position in `cases`
+    pub case: usize,
+    /// Position within the current case
+    pub arm: usize,
+    /// Only visit cases with an arm that has no verdict
+    pub unscored_only: bool,
+    undo: Vec<Edit>,
+}
+
+impl ScoreSession {
+    pub fn new(results: Vec<TestResult>, output: PathBuf, tolerance: Tolerance) -> Self {
+        let mut keys: Vec<(&str, &str, usize)> = Vec::new();
+        let mut cases: Vec<Vec<usize>> = Vec::new();
+        for (index, result) in results.iter().enumerate() {
+            // legacy rows have no id; their prompt identifies the case
+            let key = (result.id.as_str(), result.prompt.as_str(), result.repeat);
+            match keys.iter().position(|k| *k == key) {
+                Some(case) => cases[case].push(index),
+                None => {
+                    keys.push(key);
+                    cases.push(vec![index]);
+                },
+            }
+        }
+        Self { results, output, tolerance, cases, case: 0, arm: 0, unscored_only: false, undo: Vec::new() }
+    }
+
+    /// Resumes from the scored file when there is one, else starts from the results.
+    pub fn open(results_file: &Path, output: Option<PathBuf>) -> Result<Self, AgentError> {
+        let output = output.unwrap_or_else(|| scored_path(results_file));
+        let source = if output.exists() { output.as_path() } else { results_file };
+        let content = std::fs::read_to_string(source)
+            .map_err(|e| AgentError::InvalidInputError(format!("Failed to read {}: {}", source.display(), e)))?;
+        let results = parse_results(&content)?;
+        if results.is_empty() {
+            return Err(AgentError::InvalidInputError(format!("{} has no results to score", source.display())));
+        }
+        let tolerance = std::fs::read_to_string(manifest_path(results_file)).ok()
+            .and_then(|json| serde_json::from_str::<RunManifest>(&json).ok())
+            .map(|m| m.tolerance)
+            .unwrap_or_default();
+        Ok(Self::new(results, output, tolerance))
+    }
+
+    pub fn output(&self) -> &Path {
+        &self.output
+    }
+
+    pub fn case_count(&self) -> usize {
+        self.cases.len()
+    }
+
+    pub fn current(&self) -> &[usize] {
+        &self.cases[self.case]
+    }
+
+    pub fn focused(&self) -> usize {
+        self.cases[self.case][self.arm]
+    }
+
+    pub fn unscored_count(&self) -> usize {
+        self.results.iter().filter(|r| r.correct.is_none()).count()
+    }
+
+    /// What the auto-scorer makes of an answer, re-run with the case's or the run's tolerance.
+    pub fn proposal(&self, index: usize) -> Score {
+        let r = &self.results[index];
+        score_typed(&r.answer, &r.expected_output, r.answer_type, r.tolerance.unwrap_or(self.tolerance))
+    }
+
+    fn is_unscored(&self, case: usize) -> bool {
+        self.cases[case].iter().any(|i| self.results[*i].correct.is_none())
+    }
+
+    fn visible(&self, case: usize) -> bool {
+        !self.unscored_only || self.is_unscored(case)
+    }
+
+    fn edit(&mut self, index: usize, change: impl FnOnce(&mut TestResult)) {
+        self.undo.push(Edit { index, before: self.results[index].clone(), case: self.case, arm: self.arm });
+        change(&mut self.results[index]);
+    }
+
+    /// Records a human verdict for the focused arm and moves on.
+    pub fn mark(&mut self, correct: bool) {
+        self.edit(self.focused(), |r| {
+            r.correct = Some(correct);
+            r.needs_review = false;
+            r.reviewed = true;
+        });
+        self.advance();
+    }
+
+    /// Takes the auto-scorer's verdict, if it has one.
+    pub fn accept_proposal(&mut self) -> bool {
+        match self.proposal(self.focused()).correct() {
+            Some(correct) => {
+                self.mark(correct);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Sets the focused arm's note; an empty note clears it.
+    pub fn set_note(&mut self, note: &str) {
+        let note = note.trim();
+        self.edit(self.focused(), |r| {
+            r.reviewer_note = (!note.is_empty()).then(|| note.to_string());
+        });
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo.pop() else { return false };
+        self.results[edit.index] = edit.before;
+        self.case = edit.case;
+        self.arm = edit.arm;
+        true
+    }
+
+    /// Next arm of this case not yet reviewed, else the next visible case.
+    pub fn advance(&mut self) {
+        let pending = |s: &Self, i: usize| !s.results[i].reviewed && (!s.unscored_only || s.results[i].correct.is_none());
+        if let Some(arm) = (self.arm + 1..self.current().len()).find(|a| pending(self, self.current()[*a])) {
+            self.arm = arm;
+            return;
+        }
+        self.next_case();
+    }
+
+    pub fn next_case(&mut self) -> bool {
+        match (self.case + 1..self.cases.len()).find(|c| self.visible(*c)) {
+            Some(case) => {
+                self.case = case;
+                self.arm = 0;
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn previous_case(&mut self) -> bool {
+        match (0..self.case).rev().find(|c| self.visible(*c)) {
+            Some(case) => {
+                self.case = case;
+                self.arm = 0;
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn next_arm(&mut self) {
+        self.arm = (self.arm + 1) % self.current().len();
+    }
+
+    /// Turns the unscored filter on or off; when on, leaves a scored case.
+    pub fn toggle_filter(&mut self) {
+        self.unscored_only = !self.unscored_only;
+        if self.unscored_only && !self.visible(self.case) && !self.next_case() {
+            self.previous_case();
+        }
+    }
+
+    /// Jumps to a case by its 1-based number or its id. Clears the filter if
+    /// it hides the target.
+    pub fn jump(&mut self, target: &str) -> Result<(), String> {
+        let target = target.trim();
+        let case = match target.parse::<usize>() {
+            Ok(n) if (1..=self.cases.len()).contains(&n) => n - 1,
+            Ok(n) => return Err(format!("No case {} (1-{})", n, self.cases.len())),
+            Err(_) => self.cases.iter()
+                .position(|c| self.results[c[0]].id == target)
+                .ok_or_else(|| format!("No case with id '{}'", target))?,
+        };
+        if !self.visible(case) {
+            self.unscored_only = false;
+        }
+        self.case = case;
+        self.arm = 0;
+        Ok(())
+    }
+
+    /// Writes every result to the scored file (temp file, then rename).
+    pub fn save(&self) -> Result<(), AgentError> {
+        let mut content = String::new();
+        for result in &self.results {
+            content.push_str(&serde_json::to_string(result)?);
+            content.push('\n');
+        }
+        let temp = self.output.with_extension("jsonl.tmp");
+        std::fs::write(&temp, content)
+            .and_then(|_| std::fs::rename(&temp, &self.output))
+            .map_err(|e| AgentError::InvalidInputError(format!("Could not save {}: {}", self.output.display(), e)))
+    }
+}
+
+enum Mode {
+    Normal,
+    Note(String),
+    Jump(String),
+}
+
+/// The session plus what the keyboard is doing.
+pub struct ScoreApp {
+    pub session: ScoreSession,
+    mode: Mode,
+    status: String,
+    scroll: u16,
+    quit: bool,
+}
+
+impl ScoreApp {
+    pub fn new(session: ScoreSession) -> Self {
+        Self { session, mode: Mode::Normal, status: String::new(), scroll: 0, quit: false }
+    }
+
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+
+    /// Applies one key press, saving if it changed anything.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<(), AgentError> {
+        let before = (self.session.case, self.session.arm);
+        let changed = match &mut self.mode {
+            Mode::Note(text) | Mode::Jump(text) => match key.code {
+                KeyCode::Char(c) => {
+                    text.push(c);
+                    false
+                },
+                KeyCode::Backspace => {
+                    text.pop();
+                    false
+                },
+                KeyCode::Esc => {
+                    self.mode = Mode::Normal;
+                    false
+                },
+                KeyCode::Enter => self.finish_input(),
+                _ => false,
+            },
+            Mode::Normal => self.normal_key(key.code),
+        };
+        if (self.session.case, self.session.arm) != before {
+            self.scroll = 0;
+        }
+        if changed {
+            self.session.save()?;
+        }
+        Ok(())
+    }
+
+    fn finish_input(&mut self) -> bool {
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Note(text) => {
+                self.session.set_note(&text);
+                self.status = "Note saved".to_string();
+                true
+            },
+            Mode::Jump(target) => {
+                self.status = match self.session.jump(&target) {
+                    Ok(()) => String::new(),
+                    Err(e) => e,
+                };
+                false
+            },
+            Mode::Normal => false,
+        }
+    }
+
+    fn normal_key(&mut self, code: KeyCode) -> bool {
+        self.status.clear();
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('c') => {
+                self.session.mark(true);
+                true
+            },
+            KeyCode::Char('n') | KeyCode::Char('x') => {
+                self.session.mark(false);
+                true
+            },
+            KeyCode::Enter | KeyCode::Char('a') => {
+                let accepted = self.session.accept_proposal();
+                if !accepted {
+                    self.status = "The auto-scorer has no verdict here; press y or n".to_string();
+                }
+                accepted
+            },
+            KeyCode::Char('s') | KeyCode::Char(' ') => {
+                self.session.advance();
+                false
+            },
+            KeyCode::Char('u') => {
+                let undone = self.session.undo();
+                self.status = if undone { "Undone" } else { "Nothing to undo" }.to_string();
+                undone
+            },
+            KeyCode::Char('e') => {
+                let note = self.session.results[self.session.focused()].reviewer_note.clone().unwrap_or_default();
+                self.mode = Mode::Note(note);
+                false
+            },
+            KeyCode::Char('g') | KeyCode::Char('/') => {
+                self.mode = Mode::Jump(String::new());
+                false
+            },
+            KeyCode::Char('f') => {
+                self.session.toggle_filter();
+                false
+            },
+            KeyCode::Tab => {
+                self.session.next_arm();
+                false
+            },
+            KeyCode::Right | KeyCode::Char('l') => {
+                if !self.session.next_case() {
+                    self.status = "Last case".to_string();
+                }
+                false
+            },
+            KeyCode::Left | KeyCode::Char('h') => {
+                if !self.session.previous_case() {
+                    self.status = "First case".to_string();
+                }
+                false
+            },
+            KeyCode::PageDown | KeyCode::Down => {
+                self.scroll = self.scroll.saturating_add(if code == KeyCode::Down { 1 } else { 10 });
+                false
+            },
+            KeyCode::PageUp | KeyCode::Up => {
+                self.scroll = self.scroll.saturating_sub(if code == KeyCode::Up { 1 } else { 10 });
+                false
+            },
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.quit = true;
+                false
+            },
+            _ => false,
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let session = &self.session;
+        let first = &session.results[session.current()[0]];
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(6), Constraint::Length(3)])
+            .split(frame.area());
+
+        let title = format!(
+            " Case {}/{} · {} · {} unscored{} ",
+            session.case + 1, session.case_count(), if first.id.is_empty() { "-" } else { &first.id },
+            session.unscored_count(), if session.unscored_only { " · showing unscored only" } else { "" },
+        );
+        let mut expected = vec![Span::styled("Expected: ", Style::new().add_modifier(Modifier::BOLD)), Span::raw(first.expected_output.clone())];
+        if let Some(placeholder) = &first.expected_placeholder {
+            expected.push(Span::styled(format!("  (from {})", placeholder), Style::new().fg(Color::DarkGray)));
+        }
+        if let Some(category) = &first.category {
+            expected.push(Span::styled(format!("  · {}", category), Style::new().fg(Color::DarkGray)));
+        }
+        let mut header = vec![
+            Line::from(vec![Span::styled("Prompt: ", Style::new().add_modifier(Modifier::BOLD)), Span::raw(first.prompt.clone())]),
+            Line::from(expected),
+        ];
+        if let Some(notes) = &first.notes {
+            header.push(Line::styled(format!("Case notes: {}", notes), Style::new().fg(Color::DarkGray)));
+        }
+        frame.render_widget(
+            Paragraph::new(header).wrap(Wrap { trim: false }).block(Block::default().borders(Borders::ALL).title(title)),
+            rows[0],
+        );
+
+        let arms = session.current();
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, arms.len() as u32); arms.len()])
+            .split(rows[1]);
+        for (position, (&index, area)) in arms.iter().zip(columns.iter()).enumerate() {
+            let focused = position == session.arm;
+            let parts = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(6)])
+                .split(*area);
+            let result = &session.results[index];
+            let border = if focused { Style::new().fg(Color::Yellow) } else { Style::new() };
+            let title = format!(" {} · {} ms · {} chars ", result.arm, result.duration_ms, result.length);
+            frame.render_widget(
+                Paragraph::new(result.answer.clone())
+                    .wrap(Wrap { trim: false })
+                    .scroll((if focused { self.scroll } else { 0 }, 0))
+                    .block(Block::default().borders(Borders::TOP | Borders::LEFT | Borders::RIGHT).border_style(border).title(title)),
+                parts[0],
+            );
+            frame.render_widget(
+                Paragraph::new(self.details(index))
+                    .wrap(Wrap { trim: true })
+                    .block(Block::default().borders(Borders::LEFT | Borders::RIGHT | Borders::BOTTOM).border_style(border)),
+                parts[1],
+            );
+        }
+
+        let footer = match &self.mode {
+            Mode::Note(text) => Line::from(format!("Note (enter saves, esc cancels): {}_", text)),
+            Mode::Jump(text) => Line::from(format!("Jump to case number or id (enter): {}_", text)),
+            Mode::Normal => Line::from(
+                "y/n correct/incorrect · enter accept auto · s skip · u undo · e note · g jump · f unscored · tab arm · ←→ case · q quit",
+            ),
+        };
+        let status = Line::styled(
+            if self.status.is_empty() { format!("Saving to {}", session.output().display()) } else { self.status.clone() },
+            Style::new().fg(Color::DarkGray),
+        );
+        frame.render_widget(Paragraph::new(vec![footer, status]).block(Block::default().borders(Borders::TOP)), rows[2]);
+    }
+
+    fn details(&self, index: usize) -> Vec<Line<'static>> {
+        let result = &self.session.results[index];
+        let proposal = self.session.proposal(index);
+        let (label, color) = match proposal.correct() {
+            None => ("needs review", Color::Yellow),
+            decided => verdict_style(decided),
+        };
+        let mut lines = vec![Line::from(vec![
+            Span::raw("Auto: "),
+            Span::styled(label, Style::new().fg(color)),
+            Span::styled(format!(" — {}", proposal.reason), Style::new().fg(Color::DarkGray)),
+        ])];
+        if let Some(judge) = &result.judge {
+            lines.push(Line::styled(
+                format!("Judge: {:?} @ {:.2} — {}", judge.verdict, judge.confidence, judge.rationale),
+                Style::new().fg(Color::DarkGray),
+            ));
+        }
+        let (label, color) = verdict_style(result.correct);
+        let source = if result.reviewed { " (you)" } else if result.correct.is_some() { " (auto)" } else { "" };
+        lines.push(Line::from(vec![
+            Span::raw("Verdict: "),
+            Span::styled(format!("{}{}", label, source), Style::new().fg(color).add_modifier(Modifier::BOLD)),
+        ]));
+        if let Some(note) = &result.reviewer_note {
+            lines.push(Line::from(format!("Note: {}", note)));
+        }
+        lines
+    }
+}
+
+fn verdict_style(correct: Option<bool>) -> (&'static str, Color) {
+    match correct {
+        Some(true) => ("correct", Color::Green),
+        Some(false) => ("incorrect", Color::Red),
+        None => ("unscored", Color::Yellow),
+    }
+}
+
+/// Runs the scoring UI until `q`. Returns a one-line summary.
+pub fn run_score_ui(results_file: &Path, output: Option<PathBuf>, unscored_only: bool) -> Result<String, AgentError> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Err(AgentError::InvalidInputError("bench score needs an interactive terminal".to_string()));
+    }
+    let mut session = ScoreSession::open(results_file, output)?;
+    if unscored_only {
+        session.toggle_filter();
+    }
+    session.save()?;
+    let mut app = ScoreApp::new(session);
+
+    let mut terminal = ratatui::init();
+    let outcome = (|| -> Result<(), AgentError> {
+        while !app.quit {
+            terminal.draw(|frame| app.draw(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                app.handle_key(key)?;
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    outcome?;
+
+    Ok(format!(
+        "Scored file: {} ({} of {} answers still unscored)",
+        app.session.output().display(), app.session.unscored_count(), app.session.results.len(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn result(id: &str, arm: &str, answer: &str, correct: Option<bool>) -> TestResult {
+        TestResult {
+            id: id.to_string(),
+            arm: arm.to_string(),
+            prompt: format!("prompt {}", id),
+            expected_output: "4".to_string(),
+            answer: answer.to_string(),
+            correct,
+            needs_review: correct.is_none(),
+            ..Default::default()
+        }
+    }
+
+    fn session(dir: &str) -> ScoreSession {
+        let dir = std::env::temp_dir().join(format!("cvc-score-{}-{}", dir, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        ScoreSession::new(vec![
+            result("a", "tools", "The answer is 4", Some(true)),
+            result("a", "no-tools", "It depends", None),
+            result("b", "tools", "5", Some(false)),
+            result("b", "no-tools", "4", Some(true)),
+            result("c", "tools", "maybe", None),
+            result("c", "no-tools", "4", None),
+        ], dir.join("run_scored.jsonl"), Tolerance::default())
+    }
+
+    fn press(app: &mut ScoreApp, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            app.handle_key(KeyEvent::from(code)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_scored_path() {
+        assert_eq!(scored_path(Path::new("results/run.jsonl")), PathBuf::from("results/run_scored.jsonl"));
+    }
+
+    #[test]
+    fn test_cases_group_arms() {
+        let session = session("group");
+        assert_eq!(session.case_count(), 3);
+        assert_eq!(session.current(), &[0, 1]);
+        assert_eq!(session.unscored_count(), 3);
+    }
+
+    #[test]
+    fn test_mark_advance_and_undo() {
+        let mut session = session("mark");
+        session.mark(true);
+        assert_eq!((session.case, session.arm), (0, 1));
+        session.mark(false);
+        assert_eq!((session.case, session.arm), (1, 0));
+        assert_eq!(session.results[1].correct, Some(false));
+        assert!(session.results[1].reviewed && !session.results[1].needs_review);
+
+        assert!(session.undo());
+        assert_eq!((session.case, session.arm), (0, 1));
+        assert_eq!(session.results[1].correct, None);
+        assert!(!session.results[1].reviewed);
+    }
+
+    #[test]
+    fn test_filter_and_jump() {
+        let mut session = session("filter");
+        session.toggle_filter();
+        assert_eq!(session.case, 0);
+        assert!(session.next_case());
+        assert_eq!(session.case, 2, "case b is fully scored");
+        assert!(!session.next_case());
+
+        session.jump("b").unwrap();
+        assert_eq!(session.case, 1);
+        assert!(!session.unscored_only, "jumping to a scored case clears the filter");
+        assert!(session.jump("9").is_err());
+        assert!(session.jump("zzz").is_err());
+    }
+
+    #[test]
+    fn test_keys_save_every_change() {
+        let mut app = ScoreApp::new(session("keys"));
+        let output = app.session.output().to_path_buf();
+
+        // accept the auto verdict on a/tools, then note and mark a/no-tools
+        press(&mut app, "\n");
+        press(&mut app, "eriddle answer\n");
+        let saved = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(saved[0].correct, Some(true));
+        assert_eq!(saved[1].reviewer_note.as_deref(), Some("riddle answer"));
+
+        press(&mut app, "n");
+        let saved = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(saved[1].correct, Some(false));
+
+        press(&mut app, "u");
+        let saved = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(saved[1].correct, None);
+
+        // the auto-scorer can't read "maybe", so enter does nothing
+        press(&mut app, "gc\n\n");
+        assert_eq!(app.session.focused(), 4);
+        assert!(app.status().contains("no verdict"));
+
+        let _ = std::fs::remove_file(&output);
+    }
+
+    #[test]
+    fn test_draw_shows_arms_side_by_side() {
+        let app = ScoreApp::new(session("draw"));
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains("Case 1/3"));
+        assert!(screen.contains("Expected: 4"));
+        assert!(screen.contains("tools · 0 ms") && screen.contains("no-tools · 0 ms"));
+        assert!(screen.contains("Auto: correct"));
+    }
+}
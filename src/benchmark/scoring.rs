@@ -0,0 +1,486 @@
+//! Automatic scoring of free-form model answers against `expected_output`.
+//!
+//! The scorer pulls out the answer's final number (or clock time) and compares
+//! it to the expectation within a tolerance. When it can't tell, it says so
+//! and the case is left for human review rather than guessed.
+
+use std::sync::LazyLock;
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+static BOXED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\\boxed\{").unwrap());
+static BOLD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\*\*([^*]+)\*\*").unwrap());
+static DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2})?)?").unwrap()
+});
+static TIME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(\d{1,2}):(\d{2})(?::\d{2})?(?:\s*([ap])\.?m\.?\b)?").unwrap()
+});
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)([-−])?\$?(\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d+(?:\.\d+)?|\.\d+)(?:\s*(thousand|million|billion|trillion)\b)?").unwrap()
+});
+
+static MONTH_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?\s+(\d{1,2})(?:st|nd|rd|th)?(?:,?\s+(\d{4}))?").unwrap()
+});
+static ISO_DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(\d{4})-(\d{2})-(\d{2})\b").unwrap());
+static CLOCK_DURATION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(\d+):(\d{2})(?::(\d{2}))?\b").unwrap());
+static UNIT_DURATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(\d+(?:\.\d+)?)\s*(hours?|hrs?|h|minutes?|mins?|m|seconds?|secs?|s)\b").unwrap()
+});
+
+/// What kind of value a case expects, which decides how answers are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnswerType {
+    /// A number, or a clock time written as `H:MM`
+    #[default]
+    Number,
+    /// A calendar date
+    Date,
+    /// `H:MM[:SS]`/`M:SS` or an amount with units; tolerance is in seconds
+    Duration,
+    /// Free text, matched case-insensitively; anything else goes to review
+    Text,
+}
+
+/// Accepted distance between answer and expectation: whichever of the
+/// absolute and relative bounds is looser wins.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Tolerance {
+    pub absolute: f64,
+    pub relative: f64,
+}
+
+impl Default for Tolerance {
+    // the README's rule of thumb: +/- 1 is a success
+    fn default() -> Self {
+        Self { absolute: 1.0, relative: 0.0 }
+    }
+}
+
+impl Tolerance {
+    pub fn accepts(&self, actual: f64, expected: f64) -> bool {
+        let bound = self.absolute.max(self.relative * expected.abs());
+        (actual - expected).abs() <= bound + f64::EPSILON * expected.abs().max(1.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    NeedsReview,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub verdict: Verdict,
+    /// The value the scorer read as the model's answer, if any
+    pub extracted: Option<String>,
+    pub reason: String,
+}
+
+impl Score {
+    fn new(verdict: Verdict, extracted: Option<String>, reason: impl Into<String>) -> Self {
+        Self { verdict, extracted, reason: reason.into() }
+    }
+
+    /// `Some(true/false)` when decided, `None` when it needs a human.
+    pub fn correct(&self) -> Option<bool> {
+        match self.verdict {
+            Verdict::Correct => Some(true),
+            Verdict::Incorrect => Some(false),
+            Verdict::NeedsReview => None,
+        }
+    }
+}
+
+enum Expected {
+    Number(f64),
+    /// minutes since midnight
+    Time(u32),
+}
+
+pub fn score_answer(answer: &str, expected: &str, tolerance: Tolerance) -> Score {
+    score_typed(answer, expected, AnswerType::Number, tolerance)
+}
+
+pub fn score_typed(answer: &str, expected: &str, answer_type: AnswerType, tolerance: Tolerance) -> Score {
+    if answer.trim_start().starts_with("Error:") {
+        return Score::new(Verdict::Incorrect, None, "run failed with an error");
+    }
+
+    match answer_type {
+        AnswerType::Number => {},
+        AnswerType::Date => return score_date(answer, expected),
+        AnswerType::Duration => return score_duration(answer, expected, tolerance),
+        AnswerType::Text => return score_text(answer, expected),
+    }
+
+    let Some(expected_value) = parse_expected(expected) else {
+        return Score::new(Verdict::NeedsReview, None, format!("expected value '{}' is not numeric", expected));
+    };
+
+    match expected_value {
+        Expected::Number(expected) => score_number(answer, expected, tolerance),
+        Expected::Time(expected) => score_time(answer, expected),
+    }
+}
+
+fn score_number(answer: &str, expected: f64, tolerance: Tolerance) -> Score {
+    let Some(primary) = final_number(answer) else {
+        return Score::new(Verdict::NeedsReview, None, "no number found in answer");
+    };
+
+    if tolerance.accepts(primary, expected) {
+        return Score::new(Verdict::Correct, Some(format_number(primary)), "final number within tolerance");
+    }
+
+    // the expected value shows up somewhere else, so the "final" pick may be wrong
+    if numbers(&clean(answer)).into_iter().any(|n| tolerance.accepts(n, expected)) {
+        return Score::new(
+            Verdict::NeedsReview,
+            Some(format_number(primary)),
+            "final number is off, but the expected value appears elsewhere in the answer",
+        );
+    }
+
+    Score::new(Verdict::Incorrect, Some(format_number(primary)), "final number outside tolerance")
+}
+
+fn score_time(answer: &str, expected: u32) -> Score {
+    let times: Vec<(u32, bool)> = TIME.captures_iter(answer).filter_map(|c| parse_time_captures(&c)).collect();
+    let Some(&(last, explicit_meridiem)) = times.last() else {
+        return Score::new(Verdict::NeedsReview, None, "no clock time found in answer");
+    };
+
+    let matches = |minutes: u32, explicit: bool| {
+        minutes == expected || (!explicit && minutes % 720 == expected % 720)
+    };
+    let extracted = Some(format!("{}:{:02}", last / 60, last % 60));
+
+    if matches(last, explicit_meridiem) {
+        Score::new(Verdict::Correct, extracted, "final time matches")
+    } else if times.iter().any(|&(t, e)| matches(t, e)) {
+        Score::new(Verdict::NeedsReview, extracted, "final time is off, but the expected time appears elsewhere")
+    } else {
+        Score::new(Verdict::Incorrect, extracted, "final time does not match")
+    }
+}
+
+fn score_date(answer: &str, expected: &str) -> Score {
+    let Some((expected, _)) = dates(expected).pop().filter(|(_, has_year)| *has_year) else {
+        return Score::new(Verdict::NeedsReview, None, format!("expected value '{}' is not a date", expected));
+    };
+    let found = dates(answer);
+    let Some(&(last, has_year)) = found.last() else {
+        return Score::new(Verdict::NeedsReview, None, "no date found in answer");
+    };
+
+    let extracted = Some(last.format("%Y-%m-%d").to_string());
+    if last == expected && has_year {
+        Score::new(Verdict::Correct, extracted, "final date matches")
+    } else if last.format("%m-%d").to_string() == expected.format("%m-%d").to_string() && !has_year {
+        Score::new(Verdict::NeedsReview, extracted, "final date matches but gives no year")
+    } else if found.iter().any(|(d, _)| *d == expected) {
+        Score::new(Verdict::NeedsReview, extracted, "final date is off, but the expected date appears elsewhere")
+    } else {
+        Score::new(Verdict::Incorrect, extracted, "final date does not match")
+    }
+}
+
+// Dates in order of appearance, with whether a year was written. Dates without
+// a year get year 0 as a stand-in.
+fn dates(text: &str) -> Vec<(NaiveDate, bool)> {
+    let mut found: Vec<(usize, NaiveDate, bool)> = ISO_DATE.captures_iter(text)
+        .filter_map(|c| {
+            let date = NaiveDate::from_ymd_opt(c[1].parse().ok()?, c[2].parse().ok()?, c[3].parse().ok()?)?;
+            Some((c.get(0)?.start(), date, true))
+        })
+        .collect();
+
+    found.extend(MONTH_DATE.captures_iter(text).filter_map(|c| {
+        let month = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"]
+            .iter()
+            .position(|m| c[1].eq_ignore_ascii_case(m))? as u32 + 1;
+        let year = c.get(3).and_then(|y| y.as_str().parse().ok());
+        let date = NaiveDate::from_ymd_opt(year.unwrap_or(0), month, c[2].parse().ok()?)?;
+        Some((c.get(0)?.start(), date, year.is_some()))
+    }));
+
+    found.sort_by_key(|(start, _, _)| *start);
+    found.into_iter().map(|(_, date, has_year)| (date, has_year)).collect()
+}
+
+fn score_duration(answer: &str, expected: &str, tolerance: Tolerance) -> Score {
+    // colon forms are compared as written: "6:34" pace vs "2:52" marathon
+    // can't be told apart without the question
+    if let Some(expected_parts) = clock_parts(expected).pop() {
+        let found = clock_parts(answer);
+        let Some(last) = found.last() else {
+            return Score::new(Verdict::NeedsReview, None, "no H:MM style duration found in answer");
+        };
+        let extracted = Some(last.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(":"));
+        return if *last == expected_parts {
+            Score::new(Verdict::Correct, extracted, "final duration matches")
+        } else if found.contains(&expected_parts) {
+            Score::new(Verdict::NeedsReview, extracted, "final duration is off, but the expected one appears elsewhere")
+        } else {
+            Score::new(Verdict::Incorrect, extracted, "final duration does not match")
+        };
+    }
+
+    let Some(expected_seconds) = unit_durations(expected).pop() else {
+        return Score::new(Verdict::NeedsReview, None, format!("expected value '{}' is not a duration", expected));
+    };
+    let found = unit_durations(answer);
+    let Some(&last) = found.last() else {
+        return Score::new(Verdict::NeedsReview, None, "no duration with units found in answer");
+    };
+
+    let extracted = Some(format!("{}s", format_number(last)));
+    if tolerance.accepts(last, expected_seconds) {
+        Score::new(Verdict::Correct, extracted, "final duration within tolerance")
+    } else if found.iter().any(|d| tolerance.accepts(*d, expected_seconds)) {
+        Score::new(Verdict::NeedsReview, extracted, "final duration is off, but the expected one appears elsewhere")
+    } else {
+        Score::new(Verdict::Incorrect, extracted, "final duration outside tolerance")
+    }
+}
+
+fn clock_parts(text: &str) -> Vec<Vec<u32>> {
+    CLOCK_DURATION.captures_iter(text)
+        .map(|c| c.iter().skip(1).flatten().filter_map(|m| m.as_str().parse().ok()).collect())
+        .collect()
+}
+
+// "2 hours, 45 minutes" is one duration: adjacent parts joined only by
+// spaces, commas or "and" are summed.
+fn unit_durations(text: &str) -> Vec<f64> {
+    let mut durations: Vec<f64> = Vec::new();
+    let mut previous_end: Option<usize> = None;
+
+    for c in UNIT_DURATION.captures_iter(text) {
+        let Some(whole) = c.get(0) else { continue };
+        let Ok(amount) = c[1].parse::<f64>() else { continue };
+        let unit = c[2].to_lowercase();
+        let seconds = amount * if unit.starts_with('h') { 3600.0 } else if unit.starts_with('m') { 60.0 } else { 1.0 };
+
+        let joined = previous_end.is_some_and(|end| {
+            let gap = text[end..whole.start()].trim().trim_end_matches(',').trim();
+            gap.is_empty() || gap == "," || gap.eq_ignore_ascii_case("and")
+        });
+        match durations.last_mut() {
+            Some(last) if joined => *last += seconds,
+            _ => durations.push(seconds),
+        }
+        previous_end = Some(whole.end());
+    }
+    durations
+}
+
+fn score_text(answer: &str, expected: &str) -> Score {
+    let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    if normalize(answer).contains(&normalize(expected)) {
+        Score::new(Verdict::Correct, Some(expected.to_string()), "answer contains the expected text")
+    } else {
+        Score::new(Verdict::NeedsReview, None, "expected text not found verbatim")
+    }
+}
+
+fn parse_time_captures(captures: &regex::Captures) -> Option<(u32, bool)> {
+    let mut hours: u32 = captures.get(1)?.as_str().parse().ok()?;
+    let minutes: u32 = captures.get(2)?.as_str().parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    let meridiem = captures.get(3).map(|m| m.as_str().to_lowercase());
+    match meridiem.as_deref() {
+        Some("p") if hours < 12 => hours += 12,
+        Some("a") if hours == 12 => hours = 0,
+        _ => {},
+    }
+    Some((hours * 60 + minutes, meridiem.is_some()))
+}
+
+fn parse_expected(expected: &str) -> Option<Expected> {
+    let trimmed = expected.trim();
+    if let Some(captures) = TIME.captures(trimmed)
+        && captures.get(0)?.as_str().len() == trimmed.len()
+    {
+        return parse_time_captures(&captures).map(|(minutes, _)| Expected::Time(minutes));
+    }
+
+    let captures = NUMBER.captures(trimmed)?;
+    // only accept expectations that are a number and nothing else
+    if captures.get(0)?.as_str().len() != trimmed.len() {
+        return None;
+    }
+    number_from_captures(&captures).map(Expected::Number)
+}
+
+/// The answer's final number: `\boxed{}` beats the last bolded number, which
+/// beats the last number in the text.
+pub fn final_number(answer: &str) -> Option<f64> {
+    if let Some(boxed) = last_boxed(answer)
+        && let Some(n) = numbers(&clean(&boxed)).last()
+    {
+        return Some(*n);
+    }
+
+    let bold = BOLD.captures_iter(answer)
+        .filter_map(|c| numbers(&clean(c.get(1)?.as_str())).last().copied())
+        .last();
+    if bold.is_some() {
+        return bold;
+    }
+
+    numbers(&clean(answer)).last().copied()
+}
+
+fn last_boxed(text: &str) -> Option<String> {
+    let start = BOXED.find_iter(text).last()?.end();
+    let mut depth = 1;
+    for (i, c) in text[start..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(text[start..start + i].to_string());
+                }
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+// Undo LaTeX number formatting and drop dates/times so their parts aren't read as numbers.
+fn clean(text: &str) -> String {
+    let text = text.replace("{,}", ",").replace("\\,", "").replace("\\!", "");
+    let text = DATE.replace_all(&text, " ");
+    TIME.replace_all(&text, " ").into_owned()
+}
+
+fn numbers(text: &str) -> Vec<f64> {
+    NUMBER.captures_iter(text)
+        .filter(|c| {
+            // "-" only counts as a sign when it isn't joining two words or numbers
+            let start = c.get(0).map_or(0, |m| m.start());
+            !text[..start].chars().last().is_some_and(|p| p.is_alphanumeric() || p == '.')
+        })
+        .filter_map(|c| number_from_captures(&c))
+        .collect()
+}
+
+fn number_from_captures(captures: &regex::Captures) -> Option<f64> {
+    let digits = captures.get(2)?.as_str().replace(',', "");
+    let mut value: f64 = digits.parse().ok()?;
+    if let Some(scale) = captures.get(3) {
+        value *= match scale.as_str().to_lowercase().as_str() {
+            "thousand" => 1e3,
+            "million" => 1e6,
+            "billion" => 1e9,
+            _ => 1e12,
+        };
+    }
+    if captures.get(1).is_some() {
+        value = -value;
+    }
+    Some(value)
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verdict(answer: &str, expected: &str) -> Verdict {
+        score_answer(answer, expected, Tolerance::default()).verdict
+    }
+
+    #[test]
+    fn test_final_number_formats() {
+        assert_eq!(final_number("The result is 1,705,343."), Some(1705343.0));
+        assert_eq!(final_number("about 20,211.254 in total"), Some(20211.254));
+        assert_eq!(final_number("roughly 1.7 million people"), Some(1_700_000.0));
+        assert_eq!(final_number("It comes to -50,196"), Some(-50196.0));
+        assert_eq!(final_number("It comes to −4832"), Some(-4832.0));
+        assert_eq!(final_number("so \\boxed{1{,}425} and then 7 more words"), Some(1425.0));
+        assert_eq!(final_number("Step 1: 3 + 4 = **7**. Done in 2 steps"), Some(7.0));
+        assert_eq!(final_number("Tip is $23.01"), Some(23.01));
+    }
+
+    #[test]
+    fn test_dates_are_not_numbers() {
+        assert_eq!(final_number("There are 126 days (as of 2025-08-21)."), Some(126.0));
+        assert_eq!(final_number("126 days left, checked at 2025-08-21T12:50:00Z"), Some(126.0));
+    }
+
+    #[test]
+    fn test_tolerance() {
+        assert_eq!(verdict("That's 98 days!", "98"), Verdict::Correct);
+        assert_eq!(verdict("That's 99 days!", "98"), Verdict::Correct);
+        assert_eq!(verdict("That's 100 days!", "98"), Verdict::Incorrect);
+
+        let relative = Tolerance { absolute: 0.0, relative: 0.01 };
+        assert_eq!(score_answer("1,700,000", "1,705,343", relative).verdict, Verdict::Correct);
+        assert_eq!(score_answer("1,600,000", "1,705,343", relative).verdict, Verdict::Incorrect);
+    }
+
+    #[test]
+    fn test_undecidable_cases_need_review() {
+        assert_eq!(verdict("I can't say for sure.", "98"), Verdict::NeedsReview);
+        assert_eq!(verdict("It's 98 days, or 100 if you count weekends", "98"), Verdict::NeedsReview);
+        assert_eq!(verdict("Anything", "current_time"), Verdict::NeedsReview);
+    }
+
+    #[test]
+    fn test_errors_are_incorrect() {
+        assert_eq!(verdict("Error: Agent conversation exceeded maximum loops (5)", "5"), Verdict::Incorrect);
+    }
+
+    #[test]
+    fn test_time_answers() {
+        assert_eq!(verdict("You'd need a pace of about 6:34 per mile.", "6:34"), Verdict::Correct);
+        assert_eq!(verdict("It will be 4:05 PM.", "16:05"), Verdict::Correct);
+        assert_eq!(verdict("It will be 4:05 AM.", "16:05"), Verdict::Incorrect);
+        assert_eq!(verdict("Around 6:35 per mile", "6:34"), Verdict::Incorrect);
+    }
+
+    #[test]
+    fn test_date_answers() {
+        let score = |answer| score_typed(answer, "2025-11-27", AnswerType::Date, Tolerance::default()).verdict;
+        assert_eq!(score("Thanksgiving falls on November 27th, 2025."), Verdict::Correct);
+        assert_eq!(score("That's 2025-11-27"), Verdict::Correct);
+        assert_eq!(score("It's on Nov 27"), Verdict::NeedsReview);
+        assert_eq!(score("It's on November 28, 2025"), Verdict::Incorrect);
+    }
+
+    #[test]
+    fn test_duration_answers() {
+        let score = |answer, expected| score_typed(answer, expected, AnswerType::Duration, Tolerance::default()).verdict;
+        assert_eq!(score("A pace of 6:34 per mile", "6:34"), Verdict::Correct);
+        assert_eq!(score("That's 2 hours and 45 minutes", "165 minutes"), Verdict::Correct);
+        assert_eq!(score("That's 2 hours, 30 minutes", "165 minutes"), Verdict::Incorrect);
+        assert_eq!(score("A long time", "165 minutes"), Verdict::NeedsReview);
+    }
+
+    #[test]
+    fn test_text_answers() {
+        let score = |answer| score_typed(answer, "Friday", AnswerType::Text, Tolerance::default()).verdict;
+        assert_eq!(score("It will be a friday!"), Verdict::Correct);
+        assert_eq!(score("Probably the weekend"), Verdict::NeedsReview);
+    }
+}
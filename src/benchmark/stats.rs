@@ -0,0 +1,356 @@
+//! Paired comparisons between arms: McNemar's test on correctness and
+//! bootstrap confidence intervals for accuracy and latency deltas.
+//!
+//! The unit is one (case, repeat) answered by both arms. Repeats of a case
+//! aren't independent (at temperature 0 they're near copies), so McNemar's
+//! test runs on one paired outcome per case and bootstrap resamples whole
+//! cases.
+
+use std::collections::HashMap;
+use crate::benchmark::TestResult;
+
+/// Resamples per interval
+pub const BOOTSTRAP_SAMPLES: usize = 2000;
+/// Fixed so a report reproduces exactly
+pub const BOOTSTRAP_SEED: u64 = 0x5eed;
+pub const ALPHA: f64 = 0.05;
+
+/// splitmix64: small, seedable and identical on every platform.
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// A point estimate with a 95% confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub estimate: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Interval {
+    pub fn excludes_zero(&self) -> bool {
+        self.low > 0.0 || self.high < 0.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub first: String,
+    pub second: String,
+    /// (case, repeat) units both arms answered
+    pub units: usize,
+    pub cases: usize,
+    pub both: usize,
+    pub only_first: usize,
+    pub only_second: usize,
+    pub neither: usize,
+    /// Cases where the first arm was right on a larger share of repeats
+    pub cases_favoring_first: usize,
+    pub cases_favoring_second: usize,
+    /// Exact two-sided McNemar p-value over the discordant cases
+    pub mcnemar_p: f64,
+    /// first minus second, in percentage points, over every unit with an
+    /// undecided answer counted wrong, as in arm accuracy; `None` without units
+    pub accuracy_delta: Option<Interval>,
+    /// first minus second mean latency, in ms
+    pub latency_delta_ms: Option<Interval>,
+}
+
+struct Unit {
+    case: usize,
+    /// Both verdicts, when both arms were decided
+    verdicts: Option<(bool, bool)>,
+    /// Whether each arm was right; undecided counts as not
+    correct: (bool, bool),
+    latency: (f64, f64),
+}
+
+impl Comparison {
+    pub fn between(first: &str, second: &str, results: &[TestResult]) -> Self {
+        // results from before case ids go by their prompt
+        let key = |r: &TestResult| if r.id.is_empty() { r.prompt.clone() } else { r.id.clone() };
+        let of_second: HashMap<(String, usize), &TestResult> = results.iter()
+            .filter(|r| r.arm == second)
+            .map(|r| ((key(r), r.repeat), r))
+            .collect();
+
+        let mut case_ids: Vec<String> = Vec::new();
+        let mut units = Vec::new();
+        // results order, so bootstrap draws don't depend on hash order
+        for a in results.iter().filter(|r| r.arm == first) {
+            let case_id = key(a);
+            let Some(b) = of_second.get(&(case_id.clone(), a.repeat)) else { continue };
+            let case = case_ids.iter().position(|id| *id == case_id).unwrap_or_else(|| {
+                case_ids.push(case_id);
+                case_ids.len() - 1
+            });
+            units.push(Unit {
+                case,
+                verdicts: a.verdict().zip(b.verdict()),
+                correct: (a.verdict() == Some(true), b.verdict() == Some(true)),
+                latency: (a.duration_ms as f64, b.duration_ms as f64),
+            });
+        }
+
+        let count = |want: (bool, bool)| units.iter().filter(|u| u.verdicts == Some(want)).count();
+        let (only_first, only_second) = (count((true, false)), count((false, true)));
+        let (cases_favoring_first, cases_favoring_second) = case_outcomes(&units, case_ids.len());
+        Self {
+            first: first.to_string(),
+            second: second.to_string(),
+            units: units.len(),
+            cases: case_ids.len(),
+            both: count((true, true)),
+            only_first,
+            only_second,
+            neither: count((false, false)),
+            cases_favoring_first,
+            cases_favoring_second,
+            mcnemar_p: mcnemar_exact(cases_favoring_first, cases_favoring_second),
+            accuracy_delta: bootstrap(&units, case_ids.len(), accuracy_delta),
+            latency_delta_ms: bootstrap(&units, case_ids.len(), latency_delta),
+        }
+    }
+
+    pub fn accuracy_significant(&self) -> bool {
+        self.mcnemar_p < ALPHA
+    }
+
+    pub fn latency_significant(&self) -> bool {
+        self.latency_delta_ms.is_some_and(|d| d.excludes_zero())
+    }
+
+    /// One summary line, ending with what isn't significant.
+    pub fn line(&self) -> String {
+        let mut line = format!(
+            "{} vs {}: both {}, only first {}, only second {}, neither {}",
+            self.first, self.second, self.both, self.only_first, self.only_second, self.neither,
+        );
+        if let Some(delta) = self.accuracy_delta {
+            line.push_str(&format!("; accuracy {} pts, McNemar p = {}", format_interval(delta, 1), format_p(self.mcnemar_p)));
+        }
+        if let Some(delta) = self.latency_delta_ms {
+            line.push_str(&format!("; latency {} ms", format_interval(delta, 0)));
+        }
+        let mut weak = Vec::new();
+        if !self.accuracy_significant() {
+            weak.push("accuracy");
+        }
+        if !self.latency_significant() {
+            weak.push("latency");
+        }
+        if !weak.is_empty() {
+            line.push_str(&format!(" [not significant: {}]", weak.join(", ")));
+        }
+        line
+    }
+}
+
+/// `+21.0 [+12.0, +30.0]`
+pub fn format_interval(interval: Interval, decimals: usize) -> String {
+    format!("{:+.d$} [{:+.d$}, {:+.d$}]", interval.estimate, interval.low, interval.high, d = decimals)
+}
+
+pub fn format_p(p: f64) -> String {
+    if p < 0.001 { "<0.001".to_string() } else { format!("{:.3}", p) }
+}
+
+/// Exact (binomial) McNemar test: under no difference, each discordant unit
+/// is equally likely to favour either arm.
+pub fn mcnemar_exact(only_first: usize, only_second: usize) -> f64 {
+    let n = only_first + only_second;
+    if n == 0 {
+        return 1.0;
+    }
+    let k = only_first.min(only_second);
+    // sum of C(n, i) / 2^n for i <= k, in logs so large n doesn't underflow
+    let mut log_choose = 0.0;
+    let mut tail = 0.0;
+    for i in 0..=k {
+        if i > 0 {
+            log_choose += ((n - i + 1) as f64).ln() - (i as f64).ln();
+        }
+        tail += (log_choose - n as f64 * std::f64::consts::LN_2).exp();
+    }
+    (2.0 * tail).min(1.0)
+}
+
+/// Collapses each case to one paired outcome: the arm right on more of the
+/// case's decided repeats wins it, and ties count as concordant. With one
+/// repeat this is the usual discordant-pair count.
+fn case_outcomes(units: &[Unit], cases: usize) -> (usize, usize) {
+    let mut wins = vec![0i64; cases];
+    for unit in units {
+        if let Some((a, b)) = unit.verdicts {
+            wins[unit.case] += i64::from(a) - i64::from(b);
+        }
+    }
+    (wins.iter().filter(|w| **w > 0).count(), wins.iter().filter(|w| **w < 0).count())
+}
+
+fn accuracy_delta(units: &[&Unit]) -> Option<f64> {
+    if units.is_empty() {
+        return None;
+    }
+    let wins = |pick: fn(&(bool, bool)) -> bool| units.iter().filter(|u| pick(&u.correct)).count() as f64;
+    Some(100.0 * (wins(|c| c.0) - wins(|c| c.1)) / units.len() as f64)
+}
+
+fn latency_delta(units: &[&Unit]) -> Option<f64> {
+    if units.is_empty() {
+        return None;
+    }
+    Some(units.iter().map(|u| u.latency.0 - u.latency.1).sum::<f64>() / units.len() as f64)
+}
+
+/// Percentile bootstrap over cases.
+fn bootstrap(units: &[Unit], cases: usize, statistic: fn(&[&Unit]) -> Option<f64>) -> Option<Interval> {
+    let all: Vec<&Unit> = units.iter().collect();
+    let estimate = statistic(&all)?;
+
+    let mut by_case: Vec<Vec<&Unit>> = vec![Vec::new(); cases];
+    for unit in units {
+        by_case[unit.case].push(unit);
+    }
+
+    let mut rng = SplitMix64::new(BOOTSTRAP_SEED);
+    let mut draws: Vec<f64> = (0..BOOTSTRAP_SAMPLES)
+        .filter_map(|_| {
+            let sample: Vec<&Unit> = (0..cases).flat_map(|_| by_case[rng.below(cases)].iter().copied()).collect();
+            statistic(&sample)
+        })
+        .collect();
+    if draws.is_empty() {
+        return None;
+    }
+    draws.sort_by(|a, b| a.total_cmp(b));
+    let at = |q: f64| draws[((q * draws.len() as f64) as usize).min(draws.len() - 1)];
+    Some(Interval { estimate, low: at(ALPHA / 2.0), high: at(1.0 - ALPHA / 2.0) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, repeat: usize, arm: &str, correct: bool, duration_ms: u64) -> TestResult {
+        TestResult {
+            id: id.to_string(),
+            repeat,
+            arm: arm.to_string(),
+            correct: Some(correct),
+            duration_ms,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_mcnemar_exact() {
+        assert_eq!(mcnemar_exact(0, 0), 1.0);
+        assert_eq!(mcnemar_exact(3, 3), 1.0);
+        // 2 * (C(11,0) + C(11,1)) / 2^11
+        assert!((mcnemar_exact(10, 1) - 24.0 / 2048.0).abs() < 1e-12);
+        assert!(mcnemar_exact(900, 1100) < 1e-5);
+        assert_eq!(mcnemar_exact(1, 10), mcnemar_exact(10, 1));
+    }
+
+    #[test]
+    fn test_clear_difference_is_significant() {
+        let mut results = Vec::new();
+        for case in 0..40 {
+            let id = format!("c{}", case);
+            for repeat in 0..2 {
+                results.push(result(&id, repeat, "tools", case % 10 != 0, 900));
+                results.push(result(&id, repeat, "no-tools", case % 2 == 0, 500));
+            }
+        }
+        let comparison = Comparison::between("tools", "no-tools", &results);
+        assert_eq!((comparison.units, comparison.cases), (80, 40));
+        assert_eq!((comparison.only_first, comparison.only_second), (40, 8));
+        assert_eq!((comparison.cases_favoring_first, comparison.cases_favoring_second), (20, 4));
+        assert!(comparison.accuracy_significant());
+        let delta = comparison.accuracy_delta.unwrap();
+        assert_eq!(delta.estimate, 40.0);
+        assert!(delta.low > 10.0 && delta.high <= 70.0, "{:?}", delta);
+        assert_eq!(comparison.latency_delta_ms.unwrap().estimate, 400.0);
+        assert!(!comparison.line().contains("not significant"), "{}", comparison.line());
+    }
+
+    #[test]
+    fn test_repeats_do_not_inflate_significance() {
+        let answers = |repeats: usize| -> Vec<TestResult> {
+            let mut results = Vec::new();
+            for case in 0..5 {
+                let id = format!("c{}", case);
+                for repeat in 0..repeats {
+                    results.push(result(&id, repeat, "tools", true, 100));
+                    results.push(result(&id, repeat, "no-tools", case >= 3, 100));
+                }
+            }
+            results
+        };
+        let once = Comparison::between("tools", "no-tools", &answers(1));
+        let five = Comparison::between("tools", "no-tools", &answers(5));
+        assert_eq!((once.only_first, five.only_first), (3, 15));
+        assert_eq!((five.cases_favoring_first, five.cases_favoring_second), (3, 0));
+        assert!((once.mcnemar_p - 0.25).abs() < 1e-12);
+        assert_eq!(five.mcnemar_p, once.mcnemar_p);
+        assert!(!five.accuracy_significant());
+    }
+
+    #[test]
+    fn test_small_difference_is_flagged() {
+        let results = vec![
+            result("a", 0, "x", true, 100), result("a", 0, "y", false, 120),
+            result("b", 0, "x", true, 300), result("b", 0, "y", true, 90),
+            result("c", 0, "x", false, 200), result("c", 0, "y", false, 210),
+        ];
+        let comparison = Comparison::between("x", "y", &results);
+        assert_eq!(comparison.mcnemar_p, 1.0);
+        assert!(!comparison.accuracy_significant());
+        assert!(comparison.accuracy_delta.unwrap().low <= 0.0);
+        assert!(comparison.line().ends_with("[not significant: accuracy, latency]"), "{}", comparison.line());
+    }
+
+    #[test]
+    fn test_undecided_units_are_left_out() {
+        let mut unscored = result("b", 0, "y", false, 10);
+        unscored.correct = None;
+        let results = vec![result("a", 0, "x", true, 10), result("a", 0, "y", false, 10), result("b", 0, "x", true, 10), unscored];
+        let comparison = Comparison::between("x", "y", &results);
+        assert_eq!(comparison.units, 2);
+        assert_eq!(comparison.only_first + comparison.both + comparison.neither + comparison.only_second, 1);
+        assert_eq!(comparison.accuracy_delta.unwrap().estimate, 100.0);
+
+        // the delta has the arm accuracies' denominator: x 1/2, y 1/2
+        let mut undecided = result("b", 0, "x", true, 10);
+        undecided.correct = None;
+        let results = vec![result("a", 0, "x", true, 10), result("a", 0, "y", false, 10), undecided, result("b", 0, "y", true, 10)];
+        assert_eq!(Comparison::between("x", "y", &results).accuracy_delta.unwrap().estimate, 0.0);
+    }
+
+    #[test]
+    fn test_results_without_ids_pair_by_prompt() {
+        let legacy = |prompt: &str, arm: &str, correct: bool| TestResult { prompt: prompt.to_string(), ..result("", 0, arm, correct, 10) };
+        let results = vec![legacy("p1", "x", true), legacy("p1", "y", false), legacy("p2", "x", true), legacy("p2", "y", true)];
+        let comparison = Comparison::between("x", "y", &results);
+        assert_eq!((comparison.units, comparison.cases), (2, 2));
+        assert_eq!((comparison.only_first, comparison.both), (1, 1));
+    }
+}
@@ -1,73 +1,217 @@
-use crate::agent::Agent;
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use crate::agent::{Agent, AgentRun};
+use crate::clock::{system_clock, Clock, FixedClock, OffsetClock};
 use crate::error::AgentError;
 use crate::openai::OpenAIModel;
-use crate::tools::create_default_registry;
+use crate::policy::ToolPolicy;
+use crate::rate_limit::RateLimiter;
+use crate::tools::{create_registry, ToolRegistry};
+use crate::tools::holidays::HolidayCalendar;
+use crate::tools::units::UnitTable;
 use crate::types::Message;
 
-pub async fn run_cli(prompt: String, verbose: bool) -> Result<String, AgentError> {
-    if prompt.trim().is_empty() {
-        return Err(AgentError::InvalidInputError(
-            "Please provide a non-empty prompt".to_string()
-        ));
+/// Model for both the tools and no-tools agents unless `MODEL_NAME` is set,
+/// so the two only differ in their tools.
+pub const DEFAULT_MODEL: &str = "gpt-4.1-nano";
+
+pub fn default_model_name() -> String {
+    std::env::var("MODEL_NAME").unwrap_or_else(|_| DEFAULT_MODEL.to_string())
+}
+
+/// Persona for the CLI and the benchmark's default system prompt.
+pub const DEFAULT_PERSONA: &str = "You are Count von Count, a helpful assistant who loves counting and numbers! Respond naturally and enthusiastically as Count von Count.";
+
+// How each tool is introduced to the model, in prompt order.
+const TOOL_LINES: &[(&str, &str)] = &[
+    ("calculator", "Evaluates arithmetic expressions exactly, like '2 + 2', '4561 / 7', '2^100' or '52! / (5! * 47!)'. Keeps results between calls: assign with 'total = 1847 * 923' and reuse 'total', 'ans' or '$1' rather than retyping numbers. Cannot handle dates or date arithmetic."),
+    ("datetime", "Gets the current date/time (UTC, or an IANA time zone) only. Cannot calculate differences between dates."),
+    ("date_math", "Date arithmetic on YYYY-MM-DD dates: days/weeks/months/business days between two dates, adding or subtracting durations, day of week, day of year, ISO week, leap years."),
+    ("holidays", "Dates of named holidays and events (Thanksgiving, Easter, Mother's Day, Leap Day, ...) by year and region, and their next occurrence from a date."),
+    ("astronomy", "Moon phases and the next full/new moon, equinoxes and solstices, and sunrise/sunset for a latitude and longitude."),
+    ("timezone", "Current time in any IANA time zone, converting a time between zones, and UTC offset/daylight saving status."),
+    ("units", "Converts length, mass, volume, temperature, area, speed, data sizes (KB/MB vs KiB/MiB), energy and time, e.g. '3.5 ft to cm'."),
+    ("statistics", "Count, sum, mean, median, mode, variance, standard deviation, percentiles and z-scores of a list of numbers, and linear regression and correlation of paired lists."),
+    ("finance", "Simple and compound interest, loan payments and amortization schedules, future and present value, NPV, IRR, CAGR and inflation, with money rounded to cents."),
+];
+
+// Usage guidance, included when every tool it mentions is available.
+const TOOL_GUIDANCE: &[(&[&str], &str)] = &[
+    (&["datetime", "date_math"], "For date calculations (like 'days until X'), get the current date with 'datetime', then use 'date_math' to count. Do not count days yourself or subtract dates with the calculator tool."),
+    (&["holidays"], "Look up the dates of named days with 'holidays' rather than from memory."),
+    (&["astronomy"], "Use 'astronomy' for moon phases, equinoxes and solstices."),
+    (&["units"], "Convert units with 'units' rather than from memory."),
+    (&["finance"], "Use 'finance' for interest, loans and growth rates."),
+];
+
+/// The system prompt for `persona` with `tools`: the tool list and the
+/// guidance that applies to it, or a no-tools notice. The CLI and every
+/// benchmark arm build their prompt here.
+pub fn system_prompt(persona: &str, tools: &ToolRegistry) -> String {
+    if tools.is_empty() {
+        return format!("{}\n\nYou do NOT have access to any tools - answer using only your built-in knowledge.", persona);
     }
 
-    let api_key = std::env::var("OPENAI_API_KEY")
-        .map_err(|_| AgentError::ConfigurationError(
-            "OPENAI_API_KEY not set".to_string()
-        ))?;
+    let mut specs = tools.to_tool_specs();
+    let position = |name: &str| TOOL_LINES.iter().position(|(tool, _)| *tool == name).unwrap_or(TOOL_LINES.len());
+    specs.sort_by(|a, b| position(&a.name).cmp(&position(&b.name)).then_with(|| a.name.cmp(&b.name)));
+    let listed: Vec<String> = specs.iter().enumerate().map(|(i, spec)| {
+        let line = TOOL_LINES.iter().find(|(tool, _)| *tool == spec.name).map_or(spec.description.as_str(), |(_, line)| line);
+        format!("{}. '{}' - {}", i + 1, spec.name, line)
+    }).collect();
+
+    let names = tools.tool_names();
+    let guidance: Vec<&str> = TOOL_GUIDANCE.iter()
+        .filter(|(needs, _)| needs.iter().all(|tool| names.iter().any(|name| name == tool)))
+        .map(|(_, text)| *text)
+        .collect();
+
+    let mut prompt = format!("{}\n\nAvailable tools:\n{}", persona, listed.join("\n"));
+    if !guidance.is_empty() {
+        prompt.push_str("\n\n");
+        prompt.push_str(&guidance.join(" "));
+    }
+    prompt
+}
 
-    let model_name = std::env::var("MODEL_NAME")
-        .unwrap_or_else(|_| "gpt-4.1-nano".to_string());
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub verbose: bool,
+    pub policy: ToolPolicy,
+    /// Pin "now" for every time-aware tool (replays a run as of this instant)
+    pub now: Option<DateTime<Utc>>,
+    /// Shift the running clock by this much (ignored when `now` is set); a
+    /// resumed run uses it to carry on from the original run's time
+    pub clock_offset: Option<chrono::Duration>,
+    /// Shared across concurrent runs so they respect one request budget
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Built-in holidays plus any from `--holidays`
+    pub holidays: HolidayCalendar,
+    /// Built-in units plus any from `--units`
+    pub units: UnitTable,
+    /// Key for every model request instead of the environment's (each
+    /// provider's `api_key_env`); never written to a run manifest
+    pub api_key: Option<String>,
+}
+
+impl RunOptions {
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        match (self.now, self.clock_offset) {
+            (Some(instant), _) => Arc::new(FixedClock::new(instant)),
+            (None, Some(offset)) => Arc::new(OffsetClock::new(system_clock(), offset)),
+            (None, None) => system_clock(),
+        }
+    }
 
-    let model = Box::new(OpenAIModel::new(api_key, model_name));
-    let tool_registry = create_default_registry();
-    let agent = Agent::new(model, tool_registry);
+    /// `api_key` when set, otherwise the environment variable `var`.
+    pub fn api_key(&self, var: &str) -> Result<String, AgentError> {
+        match &self.api_key {
+            Some(key) => Ok(key.clone()),
+            None => std::env::var(var).map_err(|_| AgentError::ConfigurationError(format!("{} not set", var))),
+        }
+    }
+}
 
-    let system_message = Message::system(
-        "You are Count von Count, a helpful assistant who loves counting and numbers! 
+pub async fn run_cli(prompt: String, verbose: bool) -> Result<String, AgentError> {
+    run_cli_with_options(prompt, RunOptions { verbose, ..Default::default() }).await
+}
+
+pub async fn run_cli_with_options(prompt: String, options: RunOptions) -> Result<String, AgentError> {
+    run_cli_traced(prompt, options).await.output
+}
 
-        Available tools:
-        1. 'calculator' - Evaluates arithmetic expressions like '2 + 2', '15 * 7 + 23', etc. Cannot handle dates or date arithmetic.
-        2. 'datetime' - Gets the current date/time only. Cannot calculate differences between dates.
+async fn run_cli_traced(prompt: String, options: RunOptions) -> AgentRun {
+    match build_tools_agent(&prompt, &options) {
+        Ok((agent, messages)) => agent.run_traced(messages, true, options.verbose).await,
+        Err(e) => AgentRun::failed(e),
+    }
+}
 
-        For date calculations (like 'days until X'), use your built-in knowledge to calculate manually after getting the current date. Do not try to subtract dates with the calculator tool.
+fn build_tools_agent(prompt: &str, options: &RunOptions) -> Result<(Agent, Vec<Message>), AgentError> {
+    if prompt.trim().is_empty() {
+        return Err(AgentError::InvalidInputError(
+            "Please provide a non-empty prompt".to_string()
+        ));
+    }
 
-        Respond naturally and enthusiastically as Count von Count."
-    );
+    let api_key = options.api_key("OPENAI_API_KEY")?;
+
+    let model = Box::new(OpenAIModel::new(api_key, default_model_name()));
+    let clock = options.clock();
+    let tool_registry = create_registry(clock.clone(), options.holidays.clone(), options.units.clone());
+    let system_message = Message::system(&system_prompt(DEFAULT_PERSONA, &tool_registry));
+    let mut agent = Agent::new(model, tool_registry)
+        .with_policy(options.policy.clone())
+        .with_clock(clock);
+    if let Some(limiter) = &options.rate_limiter {
+        agent = agent.with_rate_limiter(limiter.clone());
+    }
 
     let messages = vec![
         system_message,
-        Message::user(&prompt),
+        Message::user(prompt),
     ];
 
-    agent.run_conversation(messages, true, verbose).await
+    Ok((agent, messages))
 }
 
 pub async fn run_cli_no_tools(prompt: String) -> Result<String, AgentError> {
+    run_cli_no_tools_traced(prompt, RunOptions::default()).await.output
+}
+
+// Only the rate limiter in `options` applies; there are no tools to police or
+// clock to read.
+async fn run_cli_no_tools_traced(prompt: String, options: RunOptions) -> AgentRun {
+    match build_no_tools_agent(&prompt, &options) {
+        Ok((agent, messages)) => agent.run_traced(messages, false, false).await,
+        Err(e) => AgentRun::failed(e),
+    }
+}
+
+fn build_no_tools_agent(prompt: &str, options: &RunOptions) -> Result<(Agent, Vec<Message>), AgentError> {
     if prompt.trim().is_empty() {
         return Err(AgentError::InvalidInputError(
             "Please provide a non-empty prompt".to_string()
         ));
     }
 
-    let api_key = std::env::var("OPENAI_API_KEY")
-        .map_err(|_| AgentError::ConfigurationError(
-            "OPENAI_API_KEY not set".to_string()
-        ))?;
-
-    let model_name = std::env::var("MODEL_NAME")
-        .unwrap_or_else(|_| "gpt-4o-mini".to_string());
-
-    let model = Box::new(OpenAIModel::new(api_key, model_name));
-    let tool_registry = crate::tools::ToolRegistry::new(); // Empty registry
-    let agent = Agent::new(model, tool_registry);
+    let api_key = options.api_key("OPENAI_API_KEY")?;
 
-    let system_message = Message::system("You are Count von Count, a helpful assistant who loves counting and numbers! You do NOT have access to any tools - answer using only your built-in knowledge.");
+    let model = Box::new(OpenAIModel::new(api_key, default_model_name()));
+    let tool_registry = ToolRegistry::new(); // Empty registry
+    let system_message = Message::system(&system_prompt(DEFAULT_PERSONA, &tool_registry));
+    let mut agent = Agent::new(model, tool_registry);
+    if let Some(limiter) = &options.rate_limiter {
+        agent = agent.with_rate_limiter(limiter.clone());
+    }
 
     let messages = vec![
         system_message,
-        Message::user(&prompt),
+        Message::user(prompt),
     ];
 
-    agent.run_conversation(messages, false, false).await
+    Ok((agent, messages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::create_default_registry;
+
+    #[test]
+    fn test_system_prompt_follows_tool_set() {
+        let registry = create_default_registry();
+        let with = system_prompt(DEFAULT_PERSONA, &registry);
+        assert!(with.starts_with(DEFAULT_PERSONA));
+        assert!(with.contains("\n1. 'calculator' - ") && with.contains("\n9. 'finance' - "));
+        assert!(with.contains("then use 'date_math' to count") && with.contains("Convert units with 'units'"));
+
+        let subset = registry.subset(&["units".to_string(), "calculator".to_string()]).unwrap();
+        let partial = system_prompt(DEFAULT_PERSONA, &subset);
+        assert!(partial.contains("1. 'calculator' - ") && partial.contains("2. 'units' - "));
+        assert!(partial.contains("Convert units with 'units'") && !partial.contains("date_math"));
+
+        let without = system_prompt(DEFAULT_PERSONA, &ToolRegistry::new());
+        assert!(without.starts_with(DEFAULT_PERSONA) && without.contains("do NOT have access"));
+    }
 }
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use crate::error::AgentError;
+
+/// Source of "now" for every time-aware component, so runs can be replayed as
+/// of a given instant.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Always reports the same instant.
+pub struct FixedClock {
+    instant: DateTime<Utc>,
+}
+
+impl FixedClock {
+    pub fn new(instant: DateTime<Utc>) -> Self {
+        Self { instant }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.instant
+    }
+}
+
+/// Another clock shifted by a constant offset; time keeps moving.
+pub struct OffsetClock {
+    base: Arc<dyn Clock>,
+    offset: Duration,
+}
+
+impl OffsetClock {
+    pub fn new(base: Arc<dyn Clock>, offset: Duration) -> Self {
+        Self { base, offset }
+    }
+
+    /// System time shifted so that it reads `instant` right now.
+    pub fn starting_at(instant: DateTime<Utc>) -> Self {
+        Self::new(Arc::new(SystemClock), instant - Utc::now())
+    }
+}
+
+impl Clock for OffsetClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.base.now() + self.offset
+    }
+}
+
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+/// Parses an RFC 3339 instant, a naive `YYYY-MM-DDTHH:MM:SS` (taken as UTC) or
+/// a bare date (midnight UTC).
+pub fn parse_instant(input: &str) -> Result<DateTime<Utc>, AgentError> {
+    let input = input.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    if let Ok(naive) = NaiveDateTime::parse_from_str(input, "%Y-%m-%dT%H:%M:%S") {
+        return Ok(naive.and_utc());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+    Err(AgentError::InvalidInputError(format!(
+        "Invalid instant '{}'. Expected RFC 3339, e.g. 2025-08-22T12:00:00Z", input
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let instant = parse_instant("2025-08-22T12:00:00Z").unwrap();
+        let clock = FixedClock::new(instant);
+        assert_eq!(clock.now(), instant);
+        assert_eq!(clock.now(), instant);
+    }
+
+    #[test]
+    fn test_offset_clock() {
+        let base = parse_instant("2025-08-22T12:00:00Z").unwrap();
+        let clock = OffsetClock::new(Arc::new(FixedClock::new(base)), Duration::hours(3));
+        assert_eq!(clock.now().to_rfc3339(), "2025-08-22T15:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_instant_formats() {
+        assert_eq!(parse_instant("2025-08-22T14:00:00+02:00").unwrap().to_rfc3339(), "2025-08-22T12:00:00+00:00");
+        assert_eq!(parse_instant("2025-08-22T12:00:00").unwrap().to_rfc3339(), "2025-08-22T12:00:00+00:00");
+        assert_eq!(parse_instant("2025-08-22").unwrap().to_rfc3339(), "2025-08-22T00:00:00+00:00");
+        assert!(parse_instant("next tuesday").is_err());
+    }
+}
@@ -38,6 +38,12 @@ pub enum AgentError {
     #[error("Agent conversation exceeded maximum loops ({max_loops})")]
     MaxLoopsExceeded { max_loops: usize },
 
+    #[error("Permission denied for tool '{tool_name}': {reason}")]
+    PermissionDenied {
+        tool_name: String,
+        reason: String,
+    },
+
     #[error("Invalid tool arguments for '{tool_name}': {details}")]
     InvalidToolArguments {
         tool_name: String,
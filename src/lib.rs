@@ -1,14 +1,18 @@
 pub mod error;
+pub mod clock;
 pub mod types;
 pub mod traits;
 pub mod openai;
 pub mod tools;
+pub mod policy;
+pub mod rate_limit;
+pub mod trace;
 pub mod agent;
 pub mod cli;
 pub mod benchmark;
 
-pub use cli::{run_cli, run_cli_no_tools};
-pub use benchmark::run_benchmarks;
+pub use cli::{run_cli, run_cli_no_tools, run_cli_with_options, RunOptions};
+pub use benchmark::{resume_benchmarks, run_benchmarks};
 pub use error::AgentError;
 
 #[cfg(test)]
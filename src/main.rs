@@ -1,6 +1,20 @@
 use clap::{Parser, Subcommand};
-use countvoncount::{run_cli, run_cli_no_tools, run_benchmarks};
+use countvoncount::{run_cli_no_tools, run_cli_with_options, run_benchmarks, resume_benchmarks, AgentError, RunOptions};
+use countvoncount::benchmark::{ArmMatrix, BenchmarkOptions, CaseSelection, ResumeOptions};
+use countvoncount::benchmark::matrix::MatrixConfig;
+use countvoncount::benchmark::diff::diff_files;
+use countvoncount::benchmark::report::{write_report, ReportFormat};
+use countvoncount::benchmark::score_ui::run_score_ui;
+use countvoncount::cli::default_model_name;
+use countvoncount::benchmark::judge::{judge_agreement, openai_judge, JudgeMode};
+use countvoncount::benchmark::scoring::Tolerance;
+use countvoncount::clock::parse_instant;
+use countvoncount::policy::ToolPolicy;
+use countvoncount::tools::holidays::HolidayCalendar;
+use countvoncount::tools::units::UnitTable;
 use dotenvy::dotenv;
+use regex::Regex;
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "cvc")]
@@ -16,23 +30,292 @@ struct Args {
 
     #[arg(long)]
     no_tools: bool,
+
+    /// YAML file setting each tool to allow, deny or ask
+    #[arg(long, global = true)]
+    policy: Option<String>,
+
+    /// Pretend the current time is this instant, e.g. 2025-08-22T12:00:00Z
+    #[arg(long, global = true, value_parser = parse_now)]
+    now: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// YAML file of holidays to add to the holidays tool's built-in ones
+    #[arg(long, global = true)]
+    holidays: Option<String>,
+
+    /// YAML file of units to add to the units tool's built-in ones
+    #[arg(long, global = true)]
+    units: Option<String>,
+}
+
+/// Run options from the global flags, loading the policy and tool data files.
+fn load_run_options(args: &Args) -> Result<RunOptions, AgentError> {
+    Ok(RunOptions {
+        verbose: args.verbose,
+        policy: args.policy.as_deref().map_or_else(|| Ok(ToolPolicy::default()), ToolPolicy::from_yaml_file)?,
+        now: args.now,
+        holidays: args.holidays.as_deref().map_or_else(|| Ok(HolidayCalendar::builtin()), HolidayCalendar::from_yaml_file)?,
+        units: args.units.as_deref().map_or_else(|| Ok(UnitTable::builtin()), UnitTable::from_yaml_file)?,
+        ..Default::default()
+    })
+}
+
+fn parse_now(value: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    parse_instant(value).map_err(|e| e.to_string())
 }
 
 #[derive(Subcommand)]
 enum Commands {
+    /// Alias for `bench run`
     #[command(long_flag = "combine")]
-    Combine,
+    Combine(Box<BenchRunArgs>),
+
+    /// Benchmark commands
+    Bench {
+        #[command(subcommand)]
+        command: BenchCommands,
+    },
+
+    /// Report how often the LLM judge agrees with human labels in a scored file
+    #[command(name = "judge-agreement")]
+    JudgeAgreement {
+        file: String,
+
+        #[arg(long)]
+        judge_model: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BenchCommands {
+    /// Run test cases with and without tools
+    Run(Box<BenchRunArgs>),
+
+    /// Continue an interrupted run, skipping answers already in its results file
+    Resume {
+        /// Results file of the run; its `.manifest.json` must sit next to it
+        file: PathBuf,
+
+        /// Override the run's concurrency
+        #[arg(long)]
+        concurrency: Option<usize>,
+
+        /// Override the run's requests-per-minute limit
+        #[arg(long)]
+        rpm: Option<u32>,
+    },
+
+    /// Write an HTML report with charts, a Markdown summary and CSV exports
+    Report {
+        /// Results file (`.jsonl`)
+        file: PathBuf,
+
+        /// Output directory [default: <results stem>_report next to the file]
+        #[arg(long)]
+        out_dir: Option<PathBuf>,
+
+        /// Comma-separated formats: html, md, csv [default: all]
+        #[arg(long, value_delimiter = ',', default_values = ["html", "md", "csv"])]
+        format: Vec<ReportFormat>,
+    },
+
+    /// Score answers by hand in a terminal UI; progress goes to <results>_scored.jsonl
+    Score {
+        /// Results file (`.jsonl`); scoring resumes from its `_scored` file if there is one
+        file: PathBuf,
+
+        /// Scored file [default: <results stem>_scored.jsonl]
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Start with only cases that have an unscored answer
+        #[arg(long)]
+        unscored: bool,
+    },
+
+    /// Compare two runs: cases that flipped per arm, and latency and length changes
+    Diff {
+        old: PathBuf,
+        new: PathBuf,
+
+        /// Exit with status 1 if any arm's accuracy drops by more than this many points
+        #[arg(long)]
+        max_drop: Option<f64>,
+    },
+}
+
+#[derive(clap::Args)]
+struct BenchRunArgs {
+    /// Test case file or directory of YAML files (repeatable) [default: test_cases.yaml]
+    #[arg(long = "cases")]
+    cases: Vec<PathBuf>,
+
+    /// Only run cases whose prompt matches this regex
+    #[arg(long)]
+    filter: Option<Regex>,
+
+    /// Only run cases whose category contains this (case-insensitive)
+    #[arg(long)]
+    category: Option<String>,
+
+    /// Run at most this many cases (after filtering and shuffling)
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Shuffle case order
+    #[arg(long)]
+    shuffle: bool,
+
+    /// Seed for --shuffle (random if omitted; printed either way)
+    #[arg(long, requires = "shuffle")]
+    seed: Option<u64>,
+
+    /// Results file [default: results/benchmark_<timestamp>.jsonl]
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// YAML matrix of models, tool sets, system prompts and sampling settings
+    /// [default: the default model with and without tools]
+    #[arg(long)]
+    matrix: Option<PathBuf>,
+
+    /// Models to run, replacing the matrix's models (`model` or `provider:model`)
+    #[arg(long, value_delimiter = ',')]
+    models: Vec<String>,
+
+    /// Only run these arms: ids like gpt-4.1-nano/tools, or a label such as
+    /// tools or a model name for every arm carrying it
+    #[arg(long, value_delimiter = ',')]
+    arms: Vec<String>,
+
+    /// Absolute tolerance when auto-scoring numeric answers
+    #[arg(long, default_value_t = 1.0)]
+    abs_tolerance: f64,
+
+    /// Relative tolerance (fraction of the expected value)
+    #[arg(long, default_value_t = 0.0)]
+    rel_tolerance: f64,
+
+    /// Send answers to an LLM judge: off, review (only undecided answers) or all
+    #[arg(long, default_value = "off")]
+    judge: JudgeMode,
+
+    /// Judge model (defaults to JUDGE_MODEL_NAME, then gpt-4.1-mini)
+    #[arg(long)]
+    judge_model: Option<String>,
+
+    /// Number of arms (model conversations) to run in parallel
+    #[arg(long, default_value_t = 1)]
+    concurrency: usize,
+
+    /// Client-side limit on model requests per minute, shared by all workers
+    #[arg(long, default_value_t = 300)]
+    rpm: u32,
+
+    /// Run each case this many times per arm, for confidence intervals
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    repeat: u32,
+}
+
+impl BenchRunArgs {
+    fn into_options(self, run: RunOptions) -> Result<BenchmarkOptions, AgentError> {
+        let mut config: MatrixConfig = match &self.matrix {
+            Some(path) => MatrixConfig::from_yaml_file(path)?,
+            None => MatrixConfig::default(),
+        };
+        if !self.models.is_empty() {
+            config.models = self.models;
+        }
+        if config.models.is_empty() {
+            config.models.push(default_model_name());
+        }
+
+        let shuffle_seed = self.shuffle.then(|| {
+            self.seed.unwrap_or_else(|| chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64)
+        });
+
+        Ok(BenchmarkOptions {
+            cases: CaseSelection {
+                paths: self.cases,
+                filter: self.filter,
+                category: self.category,
+                limit: self.limit,
+                shuffle_seed,
+            },
+            matrix: Some(ArmMatrix::from_config(config)?),
+            arms: self.arms,
+            output: self.output,
+            run,
+            tolerance: Tolerance { absolute: self.abs_tolerance, relative: self.rel_tolerance },
+            judge_mode: self.judge,
+            judge_model: self.judge_model,
+            concurrency: self.concurrency,
+            requests_per_minute: Some(self.rpm),
+            repeat: self.repeat as usize,
+        })
+    }
+}
+
+async fn bench_run(args: BenchRunArgs, run: Result<RunOptions, AgentError>) {
+    let result = match run.and_then(|run| args.into_options(run)) {
+        Ok(options) => run_benchmarks(options).await,
+        Err(e) => Err(e),
+    };
+    print_or_exit(result);
+}
+
+fn print_or_exit(result: Result<String, AgentError>) {
+    match result {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() {
     dotenv().ok(); 
     let args = Args::parse();
+    let run_options = load_run_options(&args);
     
     match args.command {
-        Some(Commands::Combine) => {
-            match run_benchmarks().await {
-                Ok(output) => println!("{}", output),
+        Some(Commands::Combine(run_args)) => bench_run(*run_args, run_options).await,
+        Some(Commands::Bench { command: BenchCommands::Run(run_args) }) => bench_run(*run_args, run_options).await,
+        Some(Commands::Bench { command: BenchCommands::Resume { file, concurrency, rpm } }) => {
+            print_or_exit(resume_benchmarks(file, ResumeOptions { concurrency, requests_per_minute: rpm, ..Default::default() }).await);
+        },
+        Some(Commands::Bench { command: BenchCommands::Report { file, out_dir, format } }) => {
+            let written = write_report(&file, out_dir, &format).await.map(|paths| {
+                paths.iter().map(|p| format!("Wrote {}", p.display())).collect::<Vec<_>>().join("\n")
+            });
+            print_or_exit(written);
+        },
+        Some(Commands::Bench { command: BenchCommands::Score { file, output, unscored } }) => {
+            print_or_exit(run_score_ui(&file, output, unscored));
+        },
+        Some(Commands::Bench { command: BenchCommands::Diff { old, new, max_drop } }) => {
+            match diff_files(&old, &new, max_drop).await {
+                Ok((output, failed)) => {
+                    println!("{}", output);
+                    if failed {
+                        std::process::exit(1);
+                    }
+                },
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        },
+        Some(Commands::JudgeAgreement { file, judge_model }) => {
+            let report = match openai_judge(judge_model).await {
+                Ok(judge) => judge_agreement(&file, &judge).await,
+                Err(e) => Err(e),
+            };
+            match report {
+                Ok(report) => println!("{}", report.summary()),
                 Err(e) => {
                     eprintln!("Error: {}", e);
                     std::process::exit(1);
@@ -45,7 +328,14 @@ async fn main() {
                     let result = if args.no_tools {
                         run_cli_no_tools(prompt).await
                     } else {
-                        run_cli(prompt, args.verbose).await
+                        let run = match run_options {
+                            Ok(run) => run,
+                            Err(e) => {
+                                eprintln!("Error: {}", e);
+                                std::process::exit(1);
+                            },
+                        };
+                        run_cli_with_options(prompt, run).await
                     };
                     
                     match result {
@@ -63,4 +353,28 @@ async fn main() {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use countvoncount::benchmark::manifest::RunManifest;
+    use countvoncount::policy::PermissionMode;
+
+    #[test]
+    fn test_bench_run_manifest_records_policy() {
+        let path = std::env::temp_dir().join(format!("cvc-policy-{}.yaml", std::process::id()));
+        std::fs::write(&path, "default: deny\ntools:\n  calculator: {mode: allow}\n").unwrap();
+
+        let args = Args::parse_from(["cvc", "bench", "run", "--policy", path.to_str().unwrap()]);
+        let run = load_run_options(&args).unwrap();
+        let Some(Commands::Bench { command: BenchCommands::Run(run_args) }) = args.command else {
+            panic!("expected bench run");
+        };
+        let options = run_args.into_options(run).unwrap();
+        let manifest = RunManifest::new(&options, options.matrix.as_ref().unwrap(), chrono::Utc::now());
+        assert_eq!(manifest.policy.default_mode, PermissionMode::Deny);
+        assert_eq!(manifest.policy.mode_for("calculator"), PermissionMode::Allow);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::error::AgentError;
 use crate::traits::Model;
-use crate::types::{Message, ModelResponse, ToolSpec, ToolCall, ToolFunction};
+use crate::types::{Message, ModelResponse, Sampling, ToolSpec, ToolCall, ToolFunction};
 use serde::{Deserialize, Serialize};
 
 
@@ -18,10 +18,14 @@ struct OpenAIFunction {
     parameters: serde_json::Value,
 }
 
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
 pub struct OpenAIModel {
     client: reqwest::Client, 
     api_key: String,
     model_name: String,
+    base_url: String,
+    sampling: Sampling,
 }
 
 impl OpenAIModel {
@@ -30,8 +34,21 @@ impl OpenAIModel {
             client: reqwest::Client::new(),
             api_key,
             model_name,
+            base_url: OPENAI_BASE_URL.to_string(),
+            sampling: Sampling::default(),
         }
     }
+
+    /// Any OpenAI-compatible chat completions endpoint, e.g. `http://localhost:11434/v1`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_sampling(mut self, sampling: Sampling) -> Self {
+        self.sampling = sampling;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -45,8 +62,17 @@ impl Model for OpenAIModel {
         let mut request = serde_json::json!({
             "model": self.model_name,
             "messages": messages,
-            "temperature": 0.0 // for debugging
+            "temperature": self.sampling.temperature,
         });
+        if let Some(top_p) = self.sampling.top_p {
+            request["top_p"] = serde_json::json!(top_p);
+        }
+        if let Some(max_tokens) = self.sampling.max_tokens {
+            request["max_tokens"] = serde_json::json!(max_tokens);
+        }
+        if let Some(seed) = self.sampling.seed {
+            request["seed"] = serde_json::json!(seed);
+        }
 
         if let Some(tool_specs) = tools {
             let openai_tools: Vec<OpenAITool> = tool_specs.into_iter().map(|spec| {
@@ -63,7 +89,7 @@ impl Model for OpenAIModel {
             request["tools"] = serde_json::json!(openai_tools);
         }
 
-        let response = self.client.post("https://api.openai.com/v1/chat/completions")
+        let response = self.client.post(format!("{}/chat/completions", self.base_url))
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
             .json(&request)
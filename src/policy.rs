@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Component, Path, PathBuf};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::AgentError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionMode {
+    Allow,
+    Deny,
+    Ask,
+}
+
+/// Restriction on a single tool argument. A call that violates any constraint
+/// is denied before the tool's mode is even considered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ArgConstraint {
+    PathPrefix { arg: String, prefixes: Vec<String> },
+    OneOf { arg: String, values: Vec<String> },
+    MaxLength { arg: String, max: usize },
+}
+
+impl ArgConstraint {
+    fn check(&self, args: &HashMap<String, Value>) -> Result<(), String> {
+        match self {
+            ArgConstraint::PathPrefix { arg, prefixes } => {
+                let value = required(args, arg)?;
+                let path = value.as_str()
+                    .ok_or_else(|| format!("argument '{}' must be a string path", arg))?;
+                let normalized = normalize_path(path)
+                    .ok_or_else(|| format!("argument '{}' escapes its root: {}", arg, path))?;
+                let allowed = prefixes.iter()
+                    .filter_map(|p| normalize_path(p))
+                    .any(|prefix| normalized.starts_with(&prefix));
+                if allowed {
+                    Ok(())
+                } else {
+                    Err(format!("argument '{}' = '{}' is outside allowed paths [{}]", arg, path, prefixes.join(", ")))
+                }
+            },
+            ArgConstraint::OneOf { arg, values } => {
+                let value = required(args, arg)?;
+                let text = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                if values.contains(&text) {
+                    Ok(())
+                } else {
+                    Err(format!("argument '{}' = '{}' is not one of [{}]", arg, text, values.join(", ")))
+                }
+            },
+            ArgConstraint::MaxLength { arg, max } => {
+                let value = required(args, arg)?;
+                let len = value.as_str().map(|s| s.chars().count()).unwrap_or_else(|| value.to_string().len());
+                if len <= *max {
+                    Ok(())
+                } else {
+                    Err(format!("argument '{}' is {} chars, limit is {}", arg, len, max))
+                }
+            },
+        }
+    }
+}
+
+// A constrained argument must be present, otherwise leaving it out (and
+// letting the tool fall back to a default) would bypass the rule.
+fn required<'a>(args: &'a HashMap<String, Value>, arg: &str) -> Result<&'a Value, String> {
+    args.get(arg).ok_or_else(|| format!("argument '{}' is constrained by policy and must be given", arg))
+}
+
+// Lexical normalization only (no filesystem access), so `..` can't be used to
+// climb out of an allowed prefix.
+fn normalize_path(path: &str) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            },
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolRule {
+    pub mode: PermissionMode,
+    #[serde(default)]
+    pub constraints: Vec<ArgConstraint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolPolicy {
+    #[serde(default = "default_mode", rename = "default")]
+    pub default_mode: PermissionMode,
+    #[serde(default)]
+    pub tools: HashMap<String, ToolRule>,
+}
+
+fn default_mode() -> PermissionMode {
+    PermissionMode::Allow
+}
+
+/// Outcome of checking a call against the policy, before any prompting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyCheck {
+    Allow,
+    Deny(String),
+    Ask,
+}
+
+impl ToolPolicy {
+    pub fn allow_all() -> Self {
+        Self {
+            default_mode: PermissionMode::Allow,
+            tools: HashMap::new(),
+        }
+    }
+
+    pub fn deny_all() -> Self {
+        Self {
+            default_mode: PermissionMode::Deny,
+            tools: HashMap::new(),
+        }
+    }
+
+    pub fn with_rule(mut self, tool_name: &str, mode: PermissionMode, constraints: Vec<ArgConstraint>) -> Self {
+        self.tools.insert(tool_name.to_string(), ToolRule { mode, constraints });
+        self
+    }
+
+    pub fn from_yaml_file(path: &str) -> Result<Self, AgentError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AgentError::ConfigurationError(format!("Failed to read policy file {}: {}", path, e)))?;
+        serde_yaml::from_str(&content)
+            .map_err(|e| AgentError::ConfigurationError(format!("Failed to parse policy file {}: {}", path, e)))
+    }
+
+    pub fn mode_for(&self, tool_name: &str) -> PermissionMode {
+        self.tools.get(tool_name).map_or(self.default_mode, |rule| rule.mode)
+    }
+
+    pub fn check(&self, tool_name: &str, args: &HashMap<String, Value>) -> PolicyCheck {
+        if let Some(rule) = self.tools.get(tool_name) {
+            for constraint in &rule.constraints {
+                if let Err(reason) = constraint.check(args) {
+                    return PolicyCheck::Deny(reason);
+                }
+            }
+        }
+
+        match self.mode_for(tool_name) {
+            PermissionMode::Allow => PolicyCheck::Allow,
+            PermissionMode::Deny => PolicyCheck::Deny(format!("tool '{}' is denied by policy", tool_name)),
+            PermissionMode::Ask => PolicyCheck::Ask,
+        }
+    }
+}
+
+impl Default for ToolPolicy {
+    fn default() -> Self {
+        Self::allow_all()
+    }
+}
+
+/// A recorded permission decision for one tool call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    pub tool_name: String,
+    pub arguments: String,
+    pub mode: PermissionMode,
+    pub allowed: bool,
+    pub reason: String,
+}
+
+/// Answers "ask" decisions.
+#[async_trait]
+pub trait Approver: Send + Sync {
+    async fn approve(&self, tool_name: &str, args: &HashMap<String, Value>) -> Result<bool, String>;
+}
+
+/// Prompts on the terminal; auto-denies when stdin or stderr is not a TTY.
+pub struct TerminalApprover;
+
+#[async_trait]
+impl Approver for TerminalApprover {
+    async fn approve(&self, tool_name: &str, args: &HashMap<String, Value>) -> Result<bool, String> {
+        if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+            return Err("approval required but no interactive terminal is available".to_string());
+        }
+
+        let pretty_args = serde_json::to_string_pretty(args).unwrap_or_else(|_| format!("{:?}", args));
+        let tool_name = tool_name.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let mut stderr = std::io::stderr();
+            let _ = writeln!(stderr, "Tool '{}' wants to run with arguments:\n{}", tool_name, pretty_args);
+            let _ = write!(stderr, "Allow? [y/N]: ");
+            let _ = stderr.flush();
+
+            let mut answer = String::new();
+            std::io::stdin().lock().read_line(&mut answer)
+                .map_err(|e| format!("failed to read approval: {}", e))?;
+            Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+        })
+        .await
+        .map_err(|e| format!("approval prompt failed: {}", e))?
+    }
+}
+
+/// Fixed answer for every prompt, mainly for tests and unattended runs.
+pub struct StaticApprover(pub bool);
+
+#[async_trait]
+impl Approver for StaticApprover {
+    async fn approve(&self, _tool_name: &str, _args: &HashMap<String, Value>) -> Result<bool, String> {
+        Ok(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    }
+
+    #[test]
+    fn test_default_policy_allows_everything() {
+        let policy = ToolPolicy::default();
+        assert_eq!(policy.check("calculator", &HashMap::new()), PolicyCheck::Allow);
+    }
+
+    #[test]
+    fn test_rule_overrides_default() {
+        let policy = ToolPolicy::deny_all().with_rule("calculator", PermissionMode::Allow, vec![]);
+        assert_eq!(policy.check("calculator", &HashMap::new()), PolicyCheck::Allow);
+        assert!(matches!(policy.check("http", &HashMap::new()), PolicyCheck::Deny(_)));
+    }
+
+    #[test]
+    fn test_path_prefix_constraint() {
+        let policy = ToolPolicy::allow_all().with_rule(
+            "read_file",
+            PermissionMode::Ask,
+            vec![ArgConstraint::PathPrefix { arg: "path".to_string(), prefixes: vec!["./data".to_string()] }],
+        );
+
+        assert_eq!(policy.check("read_file", &args(&[("path", json!("data/a.txt"))])), PolicyCheck::Ask);
+        assert!(matches!(policy.check("read_file", &args(&[("path", json!("/etc/passwd"))])), PolicyCheck::Deny(_)));
+        assert!(matches!(policy.check("read_file", &args(&[("path", json!("data/../../etc"))])), PolicyCheck::Deny(_)));
+    }
+
+    #[test]
+    fn test_missing_constrained_argument_is_denied() {
+        let policy = ToolPolicy::allow_all()
+            .with_rule("read_file", PermissionMode::Allow, vec![ArgConstraint::PathPrefix { arg: "path".to_string(), prefixes: vec!["./data".to_string()] }])
+            .with_rule("datetime", PermissionMode::Allow, vec![ArgConstraint::MaxLength { arg: "format".to_string(), max: 10 }]);
+
+        let PolicyCheck::Deny(reason) = policy.check("read_file", &args(&[("encoding", json!("utf-8"))])) else {
+            panic!("missing path should be denied");
+        };
+        assert!(reason.contains("'path' is constrained by policy and must be given"));
+        assert!(matches!(policy.check("datetime", &HashMap::new()), PolicyCheck::Deny(_)));
+    }
+
+    #[test]
+    fn test_policy_from_yaml() {
+        let policy: ToolPolicy = serde_yaml::from_str(r#"
+default: deny
+tools:
+  datetime:
+    mode: ask
+    constraints:
+      - { type: one_of, arg: format, values: [iso, human] }
+"#).unwrap();
+
+        assert_eq!(policy.check("datetime", &args(&[("format", json!("iso"))])), PolicyCheck::Ask);
+        assert!(matches!(policy.check("datetime", &args(&[("format", json!("local"))])), PolicyCheck::Deny(_)));
+        assert!(matches!(policy.check("calculator", &HashMap::new()), PolicyCheck::Deny(_)));
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Client-side limiter shared by every request in a run: hands out evenly
+/// spaced start slots so concurrent workers can't burst past the rate.
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn per_minute(requests: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(60) / requests.max(1),
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// Waits for the next free slot and returns how long that took.
+    pub async fn acquire(&self) -> Duration {
+        let now = Instant::now();
+        let slot = {
+            let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let slot = next_slot.map_or(now, |next| next.max(now));
+            *next_slot = Some(slot + self.interval);
+            slot
+        };
+
+        tokio::time::sleep_until(slot).await;
+        slot - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_requests_are_spaced() {
+        let limiter = RateLimiter::per_minute(60);
+
+        assert_eq!(limiter.acquire().await, Duration::ZERO);
+        assert_eq!(limiter.acquire().await, Duration::from_secs(1));
+        assert_eq!(limiter.acquire().await, Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_time_is_not_banked() {
+        let limiter = RateLimiter::per_minute(60);
+        limiter.acquire().await;
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(limiter.acquire().await, Duration::ZERO);
+        assert_eq!(limiter.acquire().await, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,623 @@
+use std::collections::HashMap;
+use std::fmt;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{One, Pow, Signed, ToPrimitive, Zero};
+
+/// Larger exact results fall back to floating point (about 120,000 digits).
+const MAX_EXACT_BITS: u64 = 400_000;
+const MAX_FACTORIAL: u64 = 10_000;
+/// A fraction longer than this isn't worth showing next to its decimal.
+const MAX_FRACTION_LEN: usize = 40;
+
+pub const FUNCTIONS: &[&str] = &[
+    "abs", "floor", "ceil", "round", "sqrt", "min", "max", "log", "exp", "ln", "log10", "log2",
+    "sin", "cos", "tan", "asin", "acos", "atan",
+];
+/// Functions that take more than one argument, separated by commas.
+pub const MULTI_ARGUMENT_FUNCTIONS: &[&str] = &["min", "max", "log"];
+pub const CONSTANTS: &[&str] = &["pi", "e"];
+
+/// A result: exact while only rationals are involved, floating point once
+/// something irrational (`sqrt(2)`, `pi`) is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Exact(BigRational),
+    Approx(f64),
+}
+
+impl Number {
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Exact(r) => r.to_f64().unwrap_or(f64::NAN),
+            Number::Approx(f) => *f,
+        }
+    }
+
+    /// The exact value when it is a whole number.
+    fn as_integer(&self) -> Option<BigInt> {
+        match self {
+            Number::Exact(r) if r.is_integer() => Some(r.to_integer()),
+            _ => None,
+        }
+    }
+
+    fn approx(value: f64) -> Result<Self, String> {
+        if value.is_finite() {
+            Ok(Number::Approx(value))
+        } else if value.is_nan() {
+            Err("Result is not a real number".to_string())
+        } else {
+            Err("Result is too large".to_string())
+        }
+    }
+
+    /// Whole numbers in full, other results to `places` decimal places. A
+    /// rounded exact result says what it was rounded from.
+    pub fn format(&self, places: usize) -> String {
+        match self {
+            Number::Exact(r) if r.is_integer() => r.to_integer().to_string(),
+            Number::Exact(r) => {
+                let decimal = round_decimal(r, places);
+                match terminating_places(r.denom()) {
+                    Some(needed) if needed <= places => decimal,
+                    _ => {
+                        let fraction = format!("{}/{}", r.numer(), r.denom());
+                        if fraction.len() <= MAX_FRACTION_LEN {
+                            format!("{} (rounded from {})", decimal, fraction)
+                        } else {
+                            format!("{} (rounded)", decimal)
+                        }
+                    }
+                }
+            }
+            Number::Approx(f) => format_float(*f, places),
+        }
+    }
+}
+
+pub(crate) fn ten_pow(places: usize) -> BigInt {
+    Pow::pow(BigInt::from(10), places)
+}
+
+/// Rounds half away from zero and drops trailing zeros.
+fn round_decimal(value: &BigRational, places: usize) -> String {
+    let scaled = (value * BigRational::from_integer(ten_pow(places))).round().to_integer();
+    let digits = format!("{:0>width$}", scaled.abs().to_string(), width = places + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - places);
+    let fraction = fraction.trim_end_matches('0');
+    let sign = if scaled.is_negative() { "-" } else { "" };
+    if fraction.is_empty() {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+/// Decimal places `1/denominator` needs, or `None` if it repeats forever.
+fn terminating_places(denominator: &BigInt) -> Option<usize> {
+    let mut rest = denominator.clone();
+    let mut counts = [0usize; 2];
+    for (count, factor) in counts.iter_mut().zip([2, 5]) {
+        let factor = BigInt::from(factor);
+        while rest.is_multiple_of(&factor) {
+            rest /= &factor;
+            *count += 1;
+        }
+    }
+    rest.is_one().then(|| counts[0].max(counts[1]))
+}
+
+fn format_float(value: f64, places: usize) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-6..1e16).contains(&magnitude) {
+        return format!("{:e}", value);
+    }
+    let fixed = format!("{:.*}", places, value);
+    let trimmed = if fixed.contains('.') { fixed.trim_end_matches('0').trim_end_matches('.') } else { &fixed };
+    if trimmed == "-0" { "0".to_string() } else { trimmed.to_string() }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(10))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Name(String),
+    Op(char),
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(expression: &str) -> Result<Vec<(usize, Token)>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // an exponent, but not the constant `e` after a number
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let digits_at = if matches!(chars.get(i + 1), Some('+' | '-')) { i + 2 } else { i + 1 };
+                if chars.get(digits_at).is_some_and(|d| d.is_ascii_digit()) {
+                    i = digits_at;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            Token::Number(chars[start..i].iter().collect())
+        } else if c.is_alphabetic() || c == '_' || (c == '$' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Name(chars[start..i].iter().collect())
+        } else {
+            i += 1;
+            match c {
+                '+' | '-' | '*' | '/' | '^' | '!' => Token::Op(c),
+                '(' => Token::Open,
+                ')' => Token::Close,
+                ',' => Token::Comma,
+                _ => return Err(format!("Unexpected '{}' at position {}", c, start + 1)),
+            }
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// `12.5e-3` as the exact rational it spells.
+pub(crate) fn parse_literal(literal: &str) -> Result<BigRational, String> {
+    let invalid = || format!("Invalid number '{}'", literal);
+    let (mantissa, exponent) = match literal.find(['e', 'E']) {
+        Some(at) => (&literal[..at], literal[at + 1..].parse::<i32>().map_err(|_| invalid())?),
+        None => (literal, 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if fraction.contains('.') || (whole.is_empty() && fraction.is_empty()) {
+        return Err(invalid());
+    }
+    let digits: BigInt = format!("{}{}", whole, fraction).parse().map_err(|_| invalid())?;
+    let exponent = exponent - fraction.len() as i32;
+    if exponent.unsigned_abs() > 10_000 {
+        return Err(format!("Number '{}' is out of range", literal));
+    }
+    let scale = BigRational::from_integer(ten_pow(exponent.unsigned_abs() as usize));
+    let value = BigRational::from_integer(digits);
+    Ok(if exponent >= 0 { value * scale } else { value / scale })
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    variables: &'a HashMap<String, Number>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, t)| t)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).map(|(_, t)| t.clone());
+        self.position += 1;
+        token
+    }
+
+    fn unexpected(&self) -> String {
+        match self.tokens.get(self.position) {
+            Some((at, token)) => format!("Unexpected {} at position {}", describe(token), at + 1),
+            None => "Unexpected end of expression".to_string(),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Number, String> {
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.next();
+            let rhs = self.term()?;
+            value = if op == '+' { add(value, rhs)? } else { add(value, negate(rhs))? };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Number, String> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.next();
+            let rhs = self.unary()?;
+            value = if op == '*' { multiply(value, rhs)? } else { divide(value, rhs)? };
+        }
+        Ok(value)
+    }
+
+    /// Binds looser than `^`, so `-2^2` is -4.
+    fn unary(&mut self) -> Result<Number, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.next();
+                Ok(negate(self.unary()?))
+            }
+            Some(Token::Op('+')) => {
+                self.next();
+                self.unary()
+            }
+            _ => self.power(),
+        }
+    }
+
+    /// Right-associative: `2^3^2` is 2^9.
+    fn power(&mut self) -> Result<Number, String> {
+        let base = self.postfix()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.next();
+            let exponent = self.unary()?;
+            return power(base, exponent);
+        }
+        Ok(base)
+    }
+
+    fn postfix(&mut self) -> Result<Number, String> {
+        let mut value = self.primary()?;
+        while let Some(Token::Op('!')) = self.peek() {
+            self.next();
+            value = factorial(value)?;
+        }
+        Ok(value)
+    }
+
+    fn primary(&mut self) -> Result<Number, String> {
+        match self.peek().cloned() {
+            Some(Token::Number(literal)) => {
+                self.next();
+                parse_literal(&literal).map(Number::Exact)
+            }
+            Some(Token::Open) => {
+                self.next();
+                let value = self.expression()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(value),
+                    _ => Err("Missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Name(name)) => {
+                self.next();
+                if let Some(Token::Open) = self.peek() {
+                    self.next();
+                    let args = self.arguments()?;
+                    return call(&name, args);
+                }
+                match self.variables.get(&name) {
+                    Some(value) => Ok(value.clone()),
+                    None if name.starts_with('$') || name == "ans" => Err(format!("There is no earlier result for '{}'", name)),
+                    None => constant(&name),
+                }
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Number>, String> {
+        let mut args = Vec::new();
+        if let Some(Token::Close) = self.peek() {
+            self.next();
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expression()?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::Close) => return Ok(args),
+                _ => return Err("Missing closing parenthesis".to_string()),
+            }
+        }
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format!("number {}", n),
+        Token::Name(n) => format!("'{}'", n),
+        Token::Op(c) => format!("'{}'", c),
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+        Token::Comma => "','".to_string(),
+    }
+}
+
+fn negate(value: Number) -> Number {
+    match value {
+        Number::Exact(r) => Number::Exact(-r),
+        Number::Approx(f) => Number::Approx(-f),
+    }
+}
+
+fn add(lhs: Number, rhs: Number) -> Result<Number, String> {
+    match (lhs, rhs) {
+        (Number::Exact(a), Number::Exact(b)) => Ok(Number::Exact(a + b)),
+        (a, b) => Number::approx(a.to_f64() + b.to_f64()),
+    }
+}
+
+fn multiply(lhs: Number, rhs: Number) -> Result<Number, String> {
+    match (lhs, rhs) {
+        (Number::Exact(a), Number::Exact(b)) => Ok(Number::Exact(a * b)),
+        (a, b) => Number::approx(a.to_f64() * b.to_f64()),
+    }
+}
+
+fn divide(lhs: Number, rhs: Number) -> Result<Number, String> {
+    if rhs.to_f64() == 0.0 && !matches!(&rhs, Number::Exact(r) if !r.is_zero()) {
+        return Err("Division by zero".to_string());
+    }
+    match (lhs, rhs) {
+        (Number::Exact(a), Number::Exact(b)) => Ok(Number::Exact(a / b)),
+        (a, b) => Number::approx(a.to_f64() / b.to_f64()),
+    }
+}
+
+fn bits(value: &BigRational) -> u64 {
+    value.numer().bits().max(value.denom().bits())
+}
+
+fn power(base: Number, exponent: Number) -> Result<Number, String> {
+    if let (Number::Exact(b), Some(e)) = (&base, exponent.as_integer()) {
+        if b.is_zero() && e.is_negative() {
+            return Err("Division by zero".to_string());
+        }
+        let trivial = b.is_zero() || b.abs().is_one();
+        let small = e.abs().to_u64().is_some_and(|e| trivial || e.saturating_mul(bits(b)) <= MAX_EXACT_BITS);
+        if small && let Some(e) = e.to_i32() {
+            return Ok(Number::Exact(Pow::pow(b, e)));
+        }
+    }
+    Number::approx(base.to_f64().powf(exponent.to_f64()))
+}
+
+fn factorial(value: Number) -> Result<Number, String> {
+    let n = value.as_integer()
+        .filter(|n| !n.is_negative())
+        .ok_or_else(|| format!("Factorial needs a whole number of 0 or more, not {}", value))?;
+    let n = n.to_u64().filter(|n| *n <= MAX_FACTORIAL)
+        .ok_or_else(|| format!("Factorial of {} is too large (the limit is {}!)", n, MAX_FACTORIAL))?;
+    let product = (2..=n).fold(BigInt::one(), |acc, k| acc * k);
+    Ok(Number::Exact(BigRational::from_integer(product)))
+}
+
+fn constant(name: &str) -> Result<Number, String> {
+    match name {
+        "pi" => Ok(Number::Approx(std::f64::consts::PI)),
+        "e" => Ok(Number::Approx(std::f64::consts::E)),
+        _ => Err(format!("Unknown name '{}'", name)),
+    }
+}
+
+/// The square root when both parts of the fraction are perfect squares.
+fn exact_sqrt(value: &BigRational) -> Option<BigRational> {
+    if value.is_negative() {
+        return None;
+    }
+    let numer = value.numer().sqrt();
+    let denom = value.denom().sqrt();
+    (&numer * &numer == *value.numer() && &denom * &denom == *value.denom())
+        .then(|| BigRational::new(numer, denom))
+}
+
+fn call(name: &str, args: Vec<Number>) -> Result<Number, String> {
+    let arity = |expected: usize| -> Result<(), String> {
+        if args.len() == expected {
+            Ok(())
+        } else {
+            Err(format!("{}() takes {} argument{}, got {}", name, expected, if expected == 1 { "" } else { "s" }, args.len()))
+        }
+    };
+    let float = |f: fn(f64) -> f64| -> Result<Number, String> {
+        arity(1)?;
+        Number::approx(f(args[0].to_f64()))
+    };
+    match name {
+        "abs" => {
+            arity(1)?;
+            Ok(match &args[0] {
+                Number::Exact(r) => Number::Exact(r.abs()),
+                Number::Approx(f) => Number::Approx(f.abs()),
+            })
+        }
+        "floor" | "ceil" | "round" => {
+            arity(1)?;
+            Ok(match &args[0] {
+                Number::Exact(r) => Number::Exact(match name {
+                    "floor" => r.floor(),
+                    "ceil" => r.ceil(),
+                    _ => r.round(),
+                }),
+                Number::Approx(f) => Number::Approx(match name {
+                    "floor" => f.floor(),
+                    "ceil" => f.ceil(),
+                    _ => f.round(),
+                }),
+            })
+        }
+        "sqrt" => {
+            arity(1)?;
+            if args[0].to_f64() < 0.0 {
+                return Err("sqrt() of a negative number".to_string());
+            }
+            match &args[0] {
+                Number::Exact(r) if let Some(root) = exact_sqrt(r) => Ok(Number::Exact(root)),
+                other => Number::approx(other.to_f64().sqrt()),
+            }
+        }
+        "min" | "max" => {
+            if args.is_empty() {
+                return Err(format!("{}() needs at least one argument", name));
+            }
+            let pick = |a: Number, b: Number| {
+                let b_wins = if name == "min" { b.to_f64() < a.to_f64() } else { b.to_f64() > a.to_f64() };
+                if b_wins { b } else { a }
+            };
+            Ok(args.into_iter().reduce(pick).unwrap_or_else(|| unreachable!()))
+        }
+        "log" => {
+            if args.len() == 1 {
+                return Err("log() needs a base: use ln(x), log10(x) or log(x, base)".to_string());
+            }
+            arity(2)?;
+            Number::approx(args[0].to_f64().ln() / args[1].to_f64().ln())
+        }
+        "exp" => float(f64::exp),
+        "ln" => float(f64::ln),
+        "log10" => float(f64::log10),
+        "log2" => float(f64::log2),
+        "sin" => float(f64::sin),
+        "cos" => float(f64::cos),
+        "tan" => float(f64::tan),
+        "asin" => float(f64::asin),
+        "acos" => float(f64::acos),
+        "atan" => float(f64::atan),
+        _ => Err(format!("Unknown function '{}'", name)),
+    }
+}
+
+/// Evaluates with exact rational arithmetic: `/` is true division and whole
+/// numbers never overflow.
+pub fn evaluate(expression: &str) -> Result<Number, String> {
+    evaluate_with(expression, &HashMap::new())
+}
+
+/// Like [`evaluate`], with `variables` (including `ans` and `$1`, `$2`, ...)
+/// readable by name.
+pub fn evaluate_with(expression: &str, variables: &HashMap<String, Number>) -> Result<Number, String> {
+    let mut parser = Parser { tokens: tokenize(expression)?, position: 0, variables };
+    if parser.tokens.is_empty() {
+        return Err("Expression is empty".to_string());
+    }
+    let value = parser.expression()?;
+    if parser.peek().is_some() {
+        return Err(parser.unexpected());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(expression: &str) -> String {
+        match evaluate(expression) {
+            Ok(value) => value.to_string(),
+            Err(e) => format!("error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_true_division() {
+        assert_eq!(eval("4561 / 7"), "651.5714285714 (rounded from 4561/7)");
+        assert_eq!(eval("7 / 2"), "3.5");
+        assert_eq!(eval("1 / 8"), "0.125");
+        assert_eq!(eval("0.1 + 0.2"), "0.3");
+        assert_eq!(eval("10 / 4 * 2"), "5");
+        assert_eq!(eval("-2 / 3"), "-0.6666666667 (rounded from -2/3)");
+        assert_eq!(eval("1 / 0"), "error: Division by zero");
+    }
+
+    #[test]
+    fn test_big_integers() {
+        assert_eq!(eval("2^100"), "1267650600228229401496703205376");
+        assert_eq!(eval("9223372036854775807 + 1"), "9223372036854775808");
+        assert_eq!(eval("25!"), "15511210043330985984000000");
+        assert_eq!(eval("52! / (5! * 47!)"), "2598960");
+        assert_eq!(evaluate("1000!").unwrap().to_string().len(), 2568);
+        assert_eq!(eval("(-3)!"), "error: Factorial needs a whole number of 0 or more, not -3");
+        assert_eq!(eval("20000!"), "error: Factorial of 20000 is too large (the limit is 10000!)");
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(eval("2 + 3 * 4"), "14");
+        assert_eq!(eval("-2^2"), "-4");
+        assert_eq!(eval("2^3^2"), "512");
+        assert_eq!(eval("2^-2"), "0.25");
+        assert_eq!(eval("(1 + 2) * (3 - 4)"), "-3");
+        assert_eq!(eval("1.5e3 + 2E-1"), "1500.2");
+    }
+
+    #[test]
+    fn test_functions_and_constants() {
+        assert_eq!(eval("sqrt(144)"), "12");
+        assert_eq!(eval("sqrt(9/4)"), "1.5");
+        assert_eq!(eval("sqrt(2)"), "1.4142135624");
+        assert_eq!(eval("pi * 7.85^2"), "193.5927932958");
+        assert_eq!(eval("ln(e)"), "1");
+        assert_eq!(eval("log10(1000)"), "3");
+        assert_eq!(eval("log(8, 2)"), "3");
+        assert_eq!(eval("round(2.5) + floor(-1.5) + ceil(0.2)"), "2");
+        assert_eq!(eval("max(3, 7/2, 1)"), "3.5");
+        assert_eq!(eval("2^0.5"), "1.4142135624");
+        assert_eq!(eval("log(8)"), "error: log() needs a base: use ln(x), log10(x) or log(x, base)");
+        assert_eq!(eval("sqrt(-1)"), "error: sqrt() of a negative number");
+        assert_eq!(eval("sqrt(1, 2)"), "error: sqrt() takes 1 argument, got 2");
+    }
+
+    #[test]
+    fn test_function_list_matches_calls() {
+        for name in FUNCTIONS {
+            let result = call(name, vec![Number::Approx(0.5), Number::Approx(2.0)]);
+            assert!(!result.is_err_and(|e| e.starts_with("Unknown function")), "{}", name);
+        }
+        for name in CONSTANTS {
+            assert!(constant(name).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_precision() {
+        let third = evaluate("1/3").unwrap();
+        assert_eq!(third.format(2), "0.33 (rounded from 1/3)");
+        assert_eq!(third.format(30), "0.333333333333333333333333333333 (rounded from 1/3)");
+        assert_eq!(evaluate("1/1024").unwrap().format(10), "0.0009765625");
+        assert_eq!(evaluate("2/3").unwrap().format(0), "1 (rounded from 2/3)");
+        assert_eq!(evaluate("pi").unwrap().format(4), "3.1416");
+    }
+
+    #[test]
+    fn test_variables() {
+        let variables: HashMap<String, Number> = [
+            ("total".to_string(), evaluate("1847 * 923").unwrap()),
+            ("$1".to_string(), evaluate("1 / 3").unwrap()),
+        ].into();
+        assert_eq!(evaluate_with("total / 7", &variables).unwrap().to_string(), "243540.1428571429 (rounded from 1704781/7)");
+        assert_eq!(evaluate_with("$1 * 3", &variables).unwrap().to_string(), "1");
+        assert_eq!(evaluate_with("$2 + 1", &variables).unwrap_err(), "There is no earlier result for '$2'");
+        assert_eq!(evaluate_with("ans", &variables).unwrap_err(), "There is no earlier result for 'ans'");
+        assert_eq!(evaluate_with("totl", &variables).unwrap_err(), "Unknown name 'totl'");
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        assert_eq!(eval("delete * from users"), "error: Unknown name 'delete'");
+        assert_eq!(eval("2 +"), "error: Unexpected end of expression");
+        assert_eq!(eval("(2 + 3"), "error: Missing closing parenthesis");
+        assert_eq!(eval("2 3"), "error: Unexpected number 3 at position 3");
+        assert_eq!(eval("5 % 2"), "error: Unexpected '%' at position 3");
+        assert_eq!(eval("1.2.3"), "error: Invalid number '1.2.3'");
+        assert_eq!(eval("1e99999"), "error: Number '1e99999' is out of range");
+        assert_eq!(eval("foo(1)"), "error: Unknown function 'foo'");
+    }
+}
@@ -0,0 +1,669 @@
+//! Moon phases, equinoxes and solstices, and sunrise/sunset, computed
+//! offline (Meeus, "Astronomical Algorithms", 2nd ed., and the NOAA solar
+//! calculator). Good to a minute or two for 1900-2100.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use serde_json::{json, Value};
+use crate::clock::{system_clock, Clock};
+use crate::error::AgentError;
+use crate::tools::date_math::parse_date;
+use crate::tools::Tool;
+
+const J2000: f64 = 2451545.0;
+const SYNODIC_MONTH: f64 = 29.530588861;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoonPhase {
+    New,
+    FirstQuarter,
+    Full,
+    LastQuarter,
+}
+
+impl MoonPhase {
+    pub const ALL: [MoonPhase; 4] = [MoonPhase::New, MoonPhase::FirstQuarter, MoonPhase::Full, MoonPhase::LastQuarter];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace([' ', '-'], "_").as_str() {
+            "new" | "new_moon" => Some(MoonPhase::New),
+            "first_quarter" => Some(MoonPhase::FirstQuarter),
+            "full" | "full_moon" => Some(MoonPhase::Full),
+            "last_quarter" | "third_quarter" => Some(MoonPhase::LastQuarter),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MoonPhase::New => "New Moon",
+            MoonPhase::FirstQuarter => "First Quarter",
+            MoonPhase::Full => "Full Moon",
+            MoonPhase::LastQuarter => "Last Quarter",
+        }
+    }
+
+    /// Fraction of a lunation after new moon
+    fn offset(self) -> f64 {
+        match self {
+            MoonPhase::New => 0.0,
+            MoonPhase::FirstQuarter => 0.25,
+            MoonPhase::Full => 0.5,
+            MoonPhase::LastQuarter => 0.75,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    MarchEquinox,
+    JuneSolstice,
+    SeptemberEquinox,
+    DecemberSolstice,
+}
+
+impl Season {
+    /// Accepts the northern-hemisphere names too ("summer_solstice").
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().replace([' ', '-'], "_").as_str() {
+            "march_equinox" | "spring_equinox" | "vernal_equinox" => Some(Season::MarchEquinox),
+            "june_solstice" | "summer_solstice" => Some(Season::JuneSolstice),
+            "september_equinox" | "autumn_equinox" | "autumnal_equinox" | "fall_equinox" => Some(Season::SeptemberEquinox),
+            "december_solstice" | "winter_solstice" => Some(Season::DecemberSolstice),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Season::MarchEquinox => "March equinox",
+            Season::JuneSolstice => "June solstice",
+            Season::SeptemberEquinox => "September equinox",
+            Season::DecemberSolstice => "December solstice",
+        }
+    }
+
+    /// Table 27.C, for 1000-3000 AD
+    fn coefficients(self) -> [f64; 5] {
+        match self {
+            Season::MarchEquinox => [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+            Season::JuneSolstice => [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+            Season::SeptemberEquinox => [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078],
+            Season::DecemberSolstice => [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032],
+        }
+    }
+}
+
+fn julian_day_to_utc(jde: f64) -> DateTime<Utc> {
+    // JDE is dynamical time; subtract an approximate delta T
+    let year = 2000.0 + (jde - J2000) / 365.25;
+    let t = year - 2000.0;
+    let delta_t = 62.92 + 0.32217 * t + 0.005589 * t * t;
+    let unix_seconds = (jde - 2440587.5) * 86400.0 - delta_t;
+    DateTime::from_timestamp(unix_seconds.round() as i64, 0).unwrap_or_default()
+}
+
+fn utc_to_julian_day(instant: DateTime<Utc>) -> f64 {
+    instant.timestamp() as f64 / 86400.0 + 2440587.5
+}
+
+fn sin_deg(deg: f64) -> f64 {
+    deg.to_radians().sin()
+}
+
+fn cos_deg(deg: f64) -> f64 {
+    deg.to_radians().cos()
+}
+
+/// Chapter 27: instant of a solstice or equinox.
+pub fn season_start(year: i32, season: Season) -> DateTime<Utc> {
+    const TERMS: [(f64, f64, f64); 24] = [
+        (485.0, 324.96, 1934.136), (203.0, 337.23, 32964.467), (199.0, 342.08, 20.186),
+        (182.0, 27.85, 445267.112), (156.0, 73.14, 45036.886), (136.0, 171.52, 22518.443),
+        (77.0, 222.54, 65928.934), (74.0, 296.72, 3034.906), (70.0, 243.58, 9037.513),
+        (58.0, 119.81, 33718.147), (52.0, 297.17, 150.678), (50.0, 21.02, 2281.226),
+        (45.0, 247.54, 29929.562), (44.0, 325.15, 31555.956), (29.0, 60.93, 4443.417),
+        (18.0, 155.12, 67555.328), (17.0, 288.79, 4562.452), (16.0, 198.04, 62894.029),
+        (14.0, 199.76, 31436.921), (12.0, 95.39, 14577.848), (12.0, 287.11, 31931.756),
+        (12.0, 320.81, 34777.259), (9.0, 227.73, 1222.114), (8.0, 15.45, 16859.074),
+    ];
+
+    let coefficients = season.coefficients();
+    let y = (year as f64 - 2000.0) / 1000.0;
+    let jde0 = coefficients[0]
+        + coefficients[1] * y
+        + coefficients[2] * y * y
+        + coefficients[3] * y.powi(3)
+        + coefficients[4] * y.powi(4);
+    let t = (jde0 - J2000) / 36525.0;
+    let w = 35999.373 * t - 2.47;
+    let delta_lambda = 1.0 + 0.0334 * cos_deg(w) + 0.0007 * cos_deg(2.0 * w);
+    let s: f64 = TERMS.iter().map(|(a, b, c)| a * cos_deg(b + c * t)).sum();
+
+    julian_day_to_utc(jde0 + 0.00001 * s / delta_lambda)
+}
+
+/// First `season` at or after `from`.
+pub fn next_season(season: Season, from: DateTime<Utc>) -> DateTime<Utc> {
+    let this_year = season_start(from.year(), season);
+    if this_year >= from { this_year } else { season_start(from.year() + 1, season) }
+}
+
+/// A periodic term: coefficient, power of E, then multiples of M, M', F and Ω.
+type PhaseTerm = (f64, i32, f64, f64, f64, f64);
+
+const NEW_MOON_TERMS: [PhaseTerm; 25] = [
+    (-0.40720, 0, 0.0, 1.0, 0.0, 0.0), (0.17241, 1, 1.0, 0.0, 0.0, 0.0), (0.01608, 0, 0.0, 2.0, 0.0, 0.0),
+    (0.01039, 0, 0.0, 0.0, 2.0, 0.0), (0.00739, 1, -1.0, 1.0, 0.0, 0.0), (-0.00514, 1, 1.0, 1.0, 0.0, 0.0),
+    (0.00208, 2, 2.0, 0.0, 0.0, 0.0), (-0.00111, 0, 0.0, 1.0, -2.0, 0.0), (-0.00057, 0, 0.0, 1.0, 2.0, 0.0),
+    (0.00056, 1, 1.0, 2.0, 0.0, 0.0), (-0.00042, 0, 0.0, 3.0, 0.0, 0.0), (0.00042, 1, 1.0, 0.0, 2.0, 0.0),
+    (0.00038, 1, 1.0, 0.0, -2.0, 0.0), (-0.00024, 1, -1.0, 2.0, 0.0, 0.0), (-0.00017, 0, 0.0, 0.0, 0.0, 1.0),
+    (-0.00007, 0, 2.0, 1.0, 0.0, 0.0), (0.00004, 0, 0.0, 2.0, -2.0, 0.0), (0.00004, 0, 3.0, 0.0, 0.0, 0.0),
+    (0.00003, 0, 1.0, 1.0, -2.0, 0.0), (0.00003, 0, 0.0, 2.0, 2.0, 0.0), (-0.00003, 0, 1.0, 1.0, 2.0, 0.0),
+    (0.00003, 0, -1.0, 1.0, 2.0, 0.0), (-0.00002, 0, -1.0, 1.0, -2.0, 0.0), (-0.00002, 0, 1.0, 3.0, 0.0, 0.0),
+    (0.00002, 0, 0.0, 4.0, 0.0, 0.0),
+];
+
+const FULL_MOON_TERMS: [PhaseTerm; 25] = [
+    (-0.40614, 0, 0.0, 1.0, 0.0, 0.0), (0.17302, 1, 1.0, 0.0, 0.0, 0.0), (0.01614, 0, 0.0, 2.0, 0.0, 0.0),
+    (0.01043, 0, 0.0, 0.0, 2.0, 0.0), (0.00734, 1, -1.0, 1.0, 0.0, 0.0), (-0.00515, 1, 1.0, 1.0, 0.0, 0.0),
+    (0.00209, 2, 2.0, 0.0, 0.0, 0.0), (-0.00111, 0, 0.0, 1.0, -2.0, 0.0), (-0.00057, 0, 0.0, 1.0, 2.0, 0.0),
+    (0.00056, 1, 1.0, 2.0, 0.0, 0.0), (-0.00042, 0, 0.0, 3.0, 0.0, 0.0), (0.00042, 1, 1.0, 0.0, 2.0, 0.0),
+    (0.00038, 1, 1.0, 0.0, -2.0, 0.0), (-0.00024, 1, -1.0, 2.0, 0.0, 0.0), (-0.00017, 0, 0.0, 0.0, 0.0, 1.0),
+    (-0.00007, 0, 2.0, 1.0, 0.0, 0.0), (0.00004, 0, 0.0, 2.0, -2.0, 0.0), (0.00004, 0, 3.0, 0.0, 0.0, 0.0),
+    (0.00003, 0, 1.0, 1.0, -2.0, 0.0), (0.00003, 0, 0.0, 2.0, 2.0, 0.0), (-0.00003, 0, 1.0, 1.0, 2.0, 0.0),
+    (0.00003, 0, -1.0, 1.0, 2.0, 0.0), (-0.00002, 0, -1.0, 1.0, -2.0, 0.0), (-0.00002, 0, 1.0, 3.0, 0.0, 0.0),
+    (0.00002, 0, 0.0, 4.0, 0.0, 0.0),
+];
+
+const QUARTER_TERMS: [PhaseTerm; 25] = [
+    (-0.62801, 0, 0.0, 1.0, 0.0, 0.0), (0.17172, 1, 1.0, 0.0, 0.0, 0.0), (-0.01183, 1, 1.0, 1.0, 0.0, 0.0),
+    (0.00862, 0, 0.0, 2.0, 0.0, 0.0), (0.00804, 0, 0.0, 0.0, 2.0, 0.0), (0.00454, 1, -1.0, 1.0, 0.0, 0.0),
+    (0.00204, 2, 2.0, 0.0, 0.0, 0.0), (-0.00180, 0, 0.0, 1.0, -2.0, 0.0), (-0.00070, 0, 0.0, 1.0, 2.0, 0.0),
+    (-0.00040, 0, 0.0, 3.0, 0.0, 0.0), (-0.00034, 1, -1.0, 2.0, 0.0, 0.0), (0.00032, 1, 1.0, 0.0, 2.0, 0.0),
+    (0.00032, 1, 1.0, 0.0, -2.0, 0.0), (-0.00028, 2, 2.0, 1.0, 0.0, 0.0), (0.00027, 1, 1.0, 2.0, 0.0, 0.0),
+    (-0.00017, 0, 0.0, 0.0, 0.0, 1.0), (-0.00005, 0, -1.0, 1.0, -2.0, 0.0), (0.00004, 0, 0.0, 2.0, 2.0, 0.0),
+    (-0.00004, 0, 1.0, 1.0, 2.0, 0.0), (0.00004, 0, -2.0, 1.0, 0.0, 0.0), (0.00003, 0, 1.0, 1.0, -2.0, 0.0),
+    (0.00003, 0, 3.0, 0.0, 0.0, 0.0), (0.00002, 0, 0.0, 2.0, -2.0, 0.0), (0.00002, 0, -1.0, 1.0, 2.0, 0.0),
+    (-0.00002, 0, 1.0, 3.0, 0.0, 0.0),
+];
+
+/// Chapter 49: instant of the phase of lunation `k` (k's fraction picks the
+/// phase: .0 new, .25 first quarter, .5 full, .75 last quarter).
+fn phase_instant(k: f64, phase: MoonPhase) -> DateTime<Utc> {
+    let t = k / 1236.85;
+    let jde = 2451550.09766 + SYNODIC_MONTH * k + 0.00015437 * t * t
+        - 0.000000150 * t.powi(3) + 0.00000000073 * t.powi(4);
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t * t;
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t * t - 0.00000011 * t.powi(3);
+    let mp = 201.5643 + 385.81693528 * k + 0.0107582 * t * t + 0.00001238 * t.powi(3) - 0.000000058 * t.powi(4);
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t * t - 0.00000227 * t.powi(3) + 0.000000011 * t.powi(4);
+    let omega = 124.7746 - 1.56375588 * k + 0.0020672 * t * t + 0.00000215 * t.powi(3);
+
+    let terms: &[PhaseTerm] = match phase {
+        MoonPhase::New => &NEW_MOON_TERMS,
+        MoonPhase::Full => &FULL_MOON_TERMS,
+        MoonPhase::FirstQuarter | MoonPhase::LastQuarter => &QUARTER_TERMS,
+    };
+    let mut correction: f64 = terms.iter()
+        .map(|(coefficient, e_power, a, b, c, d)| {
+            coefficient * e.powi(*e_power) * sin_deg(a * m + b * mp + c * f + d * omega)
+        })
+        .sum();
+    if matches!(phase, MoonPhase::FirstQuarter | MoonPhase::LastQuarter) {
+        let w = 0.00306 - 0.00038 * e * cos_deg(m) + 0.00026 * cos_deg(mp) - 0.00002 * cos_deg(mp - m)
+            + 0.00002 * cos_deg(mp + m) + 0.00002 * cos_deg(2.0 * f);
+        correction += if phase == MoonPhase::FirstQuarter { w } else { -w };
+    }
+
+    const PLANETARY: [(f64, f64, f64); 14] = [
+        (0.000325, 299.77, 0.107408), (0.000165, 251.88, 0.016321), (0.000164, 251.83, 26.651886),
+        (0.000126, 349.42, 36.412478), (0.000110, 84.66, 18.206239), (0.000062, 141.74, 53.303771),
+        (0.000060, 207.14, 2.453732), (0.000056, 154.84, 7.306860), (0.000047, 34.52, 27.261239),
+        (0.000042, 207.19, 0.121824), (0.000040, 291.34, 1.844379), (0.000037, 161.72, 24.198154),
+        (0.000035, 239.56, 25.513099), (0.000023, 331.55, 3.592518),
+    ];
+    let planetary: f64 = PLANETARY.iter().enumerate().map(|(i, (coefficient, base, rate))| {
+        // A1 carries an extra T^2 term
+        let arg = base + rate * k - if i == 0 { 0.009173 * t * t } else { 0.0 };
+        coefficient * sin_deg(arg)
+    }).sum();
+
+    julian_day_to_utc(jde + correction + planetary)
+}
+
+/// Lunation number just before `instant`, so searches start behind it.
+fn lunation_before(instant: DateTime<Utc>) -> f64 {
+    let years_since_2000 = (utc_to_julian_day(instant) - J2000) / 365.25;
+    (years_since_2000 * 12.3685).floor() - 2.0
+}
+
+/// First `phase` at or after `from`.
+pub fn next_phase(phase: MoonPhase, from: DateTime<Utc>) -> DateTime<Utc> {
+    let mut k = lunation_before(from) + phase.offset();
+    loop {
+        let instant = phase_instant(k, phase);
+        if instant >= from {
+            return instant;
+        }
+        k += 1.0;
+    }
+}
+
+/// Last `phase` at or before `instant`.
+fn previous_phase(phase: MoonPhase, instant: DateTime<Utc>) -> DateTime<Utc> {
+    let mut k = lunation_before(instant) - 1.0 + phase.offset();
+    let mut latest = phase_instant(k, phase);
+    loop {
+        k += 1.0;
+        let at = phase_instant(k, phase);
+        if at > instant {
+            return latest;
+        }
+        latest = at;
+    }
+}
+
+/// The principal phase most recently at or before `instant`, and when.
+fn previous_principal_phase(instant: DateTime<Utc>) -> (MoonPhase, DateTime<Utc>) {
+    let mut k = lunation_before(instant);
+    let mut latest = (MoonPhase::New, phase_instant(k, MoonPhase::New));
+    loop {
+        for phase in MoonPhase::ALL {
+            let at = phase_instant(k + phase.offset(), phase);
+            if at > instant {
+                return latest;
+            }
+            latest = (phase, at);
+        }
+        k += 1.0;
+    }
+}
+
+/// Illuminated fraction of the disk (chapter 48, low precision).
+pub fn illuminated_fraction(instant: DateTime<Utc>) -> f64 {
+    let t = (utc_to_julian_day(instant) - J2000) / 36525.0;
+    let d = 297.8501921 + 445267.1114034 * t;
+    let m = 357.5291092 + 35999.0502909 * t;
+    let mp = 134.9633964 + 477198.8675055 * t;
+    let phase_angle = 180.0 - d - 6.289 * sin_deg(mp) + 2.100 * sin_deg(m) - 1.274 * sin_deg(2.0 * d - mp)
+        - 0.658 * sin_deg(2.0 * d) - 0.214 * sin_deg(2.0 * mp) - 0.110 * sin_deg(d);
+    (1.0 + cos_deg(phase_angle)) / 2.0
+}
+
+/// Phase name at `instant`: a principal phase on its UTC day, otherwise the
+/// crescent or gibbous between two of them.
+pub fn phase_name(instant: DateTime<Utc>) -> &'static str {
+    let (previous, at) = previous_principal_phase(instant);
+    let next = MoonPhase::ALL[(MoonPhase::ALL.iter().position(|p| *p == previous).unwrap_or(0) + 1) % 4];
+    let next_at = next_phase(next, at + Duration::seconds(1));
+    if at.date_naive() == instant.date_naive() {
+        return previous.label();
+    }
+    if next_at.date_naive() == instant.date_naive() {
+        return next.label();
+    }
+    match previous {
+        MoonPhase::New => "Waxing Crescent",
+        MoonPhase::FirstQuarter => "Waxing Gibbous",
+        MoonPhase::Full => "Waning Gibbous",
+        MoonPhase::LastQuarter => "Waning Crescent",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Daylight {
+    /// Sunrise and sunset (UTC) on the day
+    RiseAndSet(DateTime<Utc>, DateTime<Utc>),
+    /// The sun stays up all day
+    MidnightSun,
+    /// The sun stays down all day
+    PolarNight,
+}
+
+/// NOAA solar position at Julian day `jd`: declination (degrees) and the
+/// equation of time (minutes).
+fn solar_position(jd: f64) -> (f64, f64) {
+    let t = (jd - J2000) / 36525.0;
+    let l0 = (280.46646 + t * (36000.76983 + 0.0003032 * t)).rem_euclid(360.0);
+    let m = 357.52911 + t * (35999.05029 - 0.0001537 * t);
+    let e = 0.016708634 - t * (0.000042037 + 0.0000001267 * t);
+    let center = sin_deg(m) * (1.914602 - t * (0.004817 + 0.000014 * t))
+        + sin_deg(2.0 * m) * (0.019993 - 0.000101 * t)
+        + sin_deg(3.0 * m) * 0.000289;
+    let omega = 125.04 - 1934.136 * t;
+    let lambda = l0 + center - 0.00569 - 0.00478 * sin_deg(omega);
+    let epsilon0 = 23.0 + (26.0 + (21.448 - t * (46.815 + t * (0.00059 - t * 0.001813))) / 60.0) / 60.0;
+    let epsilon = epsilon0 + 0.00256 * cos_deg(omega);
+    let declination = (sin_deg(epsilon) * sin_deg(lambda)).asin().to_degrees();
+
+    let y = (epsilon / 2.0).to_radians().tan().powi(2);
+    let l0r = l0.to_radians();
+    let mr = m.to_radians();
+    let equation_of_time = 4.0 * (y * (2.0 * l0r).sin() - 2.0 * e * mr.sin() + 4.0 * e * y * mr.sin() * (2.0 * l0r).cos()
+        - 0.5 * y * y * (4.0 * l0r).sin() - 1.25 * e * e * (2.0 * mr).sin()).to_degrees();
+    (declination, equation_of_time)
+}
+
+/// Minutes after `date`'s UTC midnight of sunrise (`sign` -1) or sunset (+1),
+/// or the cosine of the hour angle when the sun never crosses the horizon.
+fn sun_event(date: NaiveDate, latitude: f64, longitude: f64, sign: f64) -> Result<f64, f64> {
+    let midnight = utc_to_julian_day(date.and_time(NaiveTime::MIN).and_utc());
+    // start from local noon, then refine at the event itself
+    let mut minutes = 720.0 - 4.0 * longitude;
+    for _ in 0..3 {
+        let (declination, equation_of_time) = solar_position(midnight + minutes / 1440.0);
+        // 90.833: refraction plus the sun's radius
+        let cos_hour_angle = cos_deg(90.833) / (cos_deg(latitude) * cos_deg(declination))
+            - latitude.to_radians().tan() * declination.to_radians().tan();
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return Err(cos_hour_angle);
+        }
+        let hour_angle = cos_hour_angle.acos().to_degrees();
+        minutes = 720.0 - 4.0 * longitude - equation_of_time + sign * 4.0 * hour_angle;
+    }
+    Ok(minutes)
+}
+
+/// Sunrise and sunset on `date` (local calendar day) at a latitude and
+/// longitude (degrees, east positive).
+pub fn daylight(date: NaiveDate, latitude: f64, longitude: f64) -> Daylight {
+    let at = |minutes: f64| date.and_time(NaiveTime::MIN).and_utc() + Duration::seconds((minutes * 60.0).round() as i64);
+    match (sun_event(date, latitude, longitude, -1.0), sun_event(date, latitude, longitude, 1.0)) {
+        (Ok(rise), Ok(set)) => Daylight::RiseAndSet(at(rise), at(set)),
+        (Err(cos_hour_angle), _) | (_, Err(cos_hour_angle)) if cos_hour_angle < -1.0 => Daylight::MidnightSun,
+        _ => Daylight::PolarNight,
+    }
+}
+
+pub struct AstronomyTool {
+    clock: Arc<dyn Clock>,
+}
+
+impl AstronomyTool {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn date_arg(&self, args: &HashMap<String, Value>, key: &str) -> Result<Option<NaiveDate>, AgentError> {
+        match args.get(key).and_then(|v| v.as_str()) {
+            Some(text) => parse_date(text).map(Some).map_err(|e| self.error(e)),
+            None => Ok(None),
+        }
+    }
+
+    fn degrees_arg(&self, args: &HashMap<String, Value>, key: &str, limit: f64) -> Result<f64, AgentError> {
+        let value = args.get(key)
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| self.error(format!("Missing or non-numeric '{}' parameter (decimal degrees)", key)))?;
+        if value.abs() > limit {
+            return Err(self.error(format!("'{}' must be between -{} and {} degrees (got {})", key, limit, limit, value)));
+        }
+        Ok(value)
+    }
+
+    fn year_arg(&self, args: &HashMap<String, Value>) -> Result<i32, AgentError> {
+        match args.get("year") {
+            Some(value) => value.as_i64()
+                .and_then(|y| i32::try_from(y).ok())
+                .filter(|y| (1000..=3000).contains(y))
+                .ok_or_else(|| self.error(format!("Invalid year {}: expected 1000 to 3000", value))),
+            None => Ok(self.clock.now().year()),
+        }
+    }
+
+    /// Midnight UTC of `from`, or now.
+    fn start_arg(&self, args: &HashMap<String, Value>) -> Result<DateTime<Utc>, AgentError> {
+        Ok(match self.date_arg(args, "from")? {
+            Some(date) => date.and_time(NaiveTime::MIN).and_utc(),
+            None => self.clock.now(),
+        })
+    }
+}
+
+impl Default for AstronomyTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn show(instant: DateTime<Utc>) -> String {
+    instant.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+fn days_from(instant: DateTime<Utc>, from: DateTime<Utc>) -> String {
+    format!("{} days from {}", (instant.date_naive() - from.date_naive()).num_days(), from.format("%Y-%m-%d"))
+}
+
+#[async_trait]
+impl Tool for AstronomyTool {
+    fn name(&self) -> &'static str {
+        "astronomy"
+    }
+
+    fn description(&self) -> &'static str {
+        "Moon phases, equinoxes and solstices, and sunrise/sunset, computed offline. Args: { operation: string, phase?: string, event?: string, year?: integer, date?: string, from?: string, latitude?: number, longitude?: number }"
+    }
+
+    fn json_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "description": "'moon_phase' (phase and illumination on a date), 'next_moon_phase' (next time the moon reaches a phase), 'season' (an equinox or solstice in a year), 'next_season' (its next occurrence) or 'sun' (sunrise and sunset)",
+                    "enum": ["moon_phase", "next_moon_phase", "season", "next_season", "sun"]
+                },
+                "phase": {
+                    "type": "string",
+                    "description": "For 'next_moon_phase'",
+                    "enum": ["new_moon", "first_quarter", "full_moon", "last_quarter"]
+                },
+                "event": {
+                    "type": "string",
+                    "description": "For 'season' and 'next_season'; summer/winter mean the northern hemisphere",
+                    "enum": ["march_equinox", "june_solstice", "september_equinox", "december_solstice", "spring_equinox", "summer_solstice", "autumn_equinox", "winter_solstice"]
+                },
+                "year": {
+                    "type": "integer",
+                    "description": "For 'season'; defaults to the current year"
+                },
+                "date": {
+                    "type": "string",
+                    "description": "YYYY-MM-DD for 'moon_phase' and 'sun'; defaults to today"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "YYYY-MM-DD to search from for 'next_moon_phase' and 'next_season'; defaults to now"
+                },
+                "latitude": {
+                    "type": "number",
+                    "description": "For 'sun': decimal degrees, north positive"
+                },
+                "longitude": {
+                    "type": "number",
+                    "description": "For 'sun': decimal degrees, east positive (New York is -74.0)"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
+        let operation = args.get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.error("Missing 'operation' parameter"))?;
+        let season = || {
+            let name = args.get("event").and_then(|v| v.as_str()).unwrap_or_default();
+            Season::parse(name).ok_or_else(|| self.error(format!(
+                "Invalid event '{}'. Use: march_equinox, june_solstice, september_equinox, or december_solstice", name
+            )))
+        };
+
+        match operation {
+            "moon_phase" => {
+                let instant = match self.date_arg(&args, "date")? {
+                    Some(date) => date.and_hms_opt(12, 0, 0).unwrap_or_default().and_utc(),
+                    None => self.clock.now(),
+                };
+                let age = (instant - previous_phase(MoonPhase::New, instant)).num_minutes() as f64 / 1440.0;
+                Ok(format!(
+                    "{} on {}: {:.0}% illuminated, {:.1} days since new moon; next full moon {}",
+                    phase_name(instant), instant.format("%Y-%m-%d"), illuminated_fraction(instant) * 100.0,
+                    age, show(next_phase(MoonPhase::Full, instant)),
+                ))
+            }
+            "next_moon_phase" => {
+                let name = args.get("phase").and_then(|v| v.as_str()).unwrap_or_default();
+                let phase = MoonPhase::parse(name).ok_or_else(|| self.error(format!(
+                    "Invalid phase '{}'. Use: new_moon, first_quarter, full_moon, or last_quarter", name
+                )))?;
+                let from = self.start_arg(&args)?;
+                let at = next_phase(phase, from);
+                Ok(format!("{}: {} ({})", phase.label(), show(at), days_from(at, from)))
+            }
+            "season" => {
+                let season = season()?;
+                let year = self.year_arg(&args)?;
+                Ok(format!("{} {}: {}", season.label(), year, show(season_start(year, season))))
+            }
+            "next_season" => {
+                let season = season()?;
+                let from = self.start_arg(&args)?;
+                let at = next_season(season, from);
+                Ok(format!("{}: {} ({})", season.label(), show(at), days_from(at, from)))
+            }
+            "sun" => {
+                let latitude = self.degrees_arg(&args, "latitude", 90.0)?;
+                let longitude = self.degrees_arg(&args, "longitude", 180.0)?;
+                let date = self.date_arg(&args, "date")?.unwrap_or_else(|| self.clock.now().date_naive());
+                Ok(match daylight(date, latitude, longitude) {
+                    Daylight::RiseAndSet(rise, set) => {
+                        let length = (set - rise).num_minutes();
+                        format!("Sunrise {}, sunset {}, daylight {}h {:02}m", show(rise), show(set), length / 60, length % 60)
+                    }
+                    Daylight::MidnightSun => format!("The sun does not set on {} (midnight sun)", date),
+                    Daylight::PolarNight => format!("The sun does not rise on {} (polar night)", date),
+                })
+            }
+            _ => Err(self.error(format!(
+                "Invalid operation '{}'. Use: moon_phase, next_moon_phase, season, next_season, or sun", operation
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{parse_instant, FixedClock};
+
+    fn instant(text: &str) -> DateTime<Utc> {
+        parse_instant(text).unwrap()
+    }
+
+    fn assert_within(actual: DateTime<Utc>, published: &str, minutes: i64) {
+        let difference = (actual - instant(published)).num_minutes().abs();
+        assert!(difference <= minutes, "{} is {} minutes from {}", actual, difference, published);
+    }
+
+    #[test]
+    fn test_seasons_against_published_times() {
+        // USNO
+        for (year, season, published) in [
+            (2025, Season::MarchEquinox, "2025-03-20T09:01:00Z"),
+            (2025, Season::JuneSolstice, "2025-06-21T02:42:00Z"),
+            (2025, Season::SeptemberEquinox, "2025-09-22T18:19:00Z"),
+            (2025, Season::DecemberSolstice, "2025-12-21T15:03:00Z"),
+            (2026, Season::JuneSolstice, "2026-06-21T08:24:00Z"),
+            (2026, Season::DecemberSolstice, "2026-12-21T20:50:00Z"),
+        ] {
+            assert_within(season_start(year, season), published, 2);
+        }
+        assert_eq!(next_season(Season::JuneSolstice, instant("2025-08-21T12:50:00Z")).date_naive(), parse_date("2026-06-21").unwrap());
+    }
+
+    #[test]
+    fn test_moon_phases_against_published_times() {
+        // USNO, September 2025
+        let from = instant("2025-08-21T12:50:00Z");
+        assert_within(next_phase(MoonPhase::Full, from), "2025-09-07T18:09:00Z", 2);
+        assert_within(next_phase(MoonPhase::LastQuarter, from), "2025-09-14T10:33:00Z", 2);
+        assert_within(next_phase(MoonPhase::New, from), "2025-08-23T06:06:00Z", 2);
+        assert_within(next_phase(MoonPhase::New, instant("2025-09-01T00:00:00Z")), "2025-09-21T19:54:00Z", 2);
+        assert_within(next_phase(MoonPhase::FirstQuarter, instant("2025-09-22T00:00:00Z")), "2025-09-29T23:54:00Z", 2);
+        // the March 2025 eclipses
+        assert_within(next_phase(MoonPhase::Full, instant("2025-03-01T00:00:00Z")), "2025-03-14T06:55:00Z", 2);
+        assert_within(next_phase(MoonPhase::New, instant("2025-03-15T00:00:00Z")), "2025-03-29T10:58:00Z", 2);
+    }
+
+    #[test]
+    fn test_phase_names_and_illumination() {
+        assert_eq!(phase_name(instant("2025-09-07T03:00:00Z")), "Full Moon");
+        assert_eq!(phase_name(instant("2025-09-10T12:00:00Z")), "Waning Gibbous");
+        assert_eq!(phase_name(instant("2025-09-17T12:00:00Z")), "Waning Crescent");
+        assert_eq!(phase_name(instant("2025-09-25T12:00:00Z")), "Waxing Crescent");
+        assert_eq!(phase_name(instant("2025-10-03T12:00:00Z")), "Waxing Gibbous");
+        assert!(illuminated_fraction(instant("2025-09-07T18:09:00Z")) > 0.99);
+        assert!(illuminated_fraction(instant("2025-09-21T19:54:00Z")) < 0.01);
+        let quarter = illuminated_fraction(instant("2025-09-14T10:33:00Z"));
+        assert!((quarter - 0.5).abs() < 0.03, "{}", quarter);
+    }
+
+    #[test]
+    fn test_sunrise_sunset_against_published_times() {
+        let date = |text| parse_date(text).unwrap();
+        // NOAA solar calculator; New York (40.7128, -74.0060), London (51.5074, -0.1278)
+        for (day, latitude, longitude, rise, set) in [
+            ("2025-06-21", 40.7128, -74.0060, "2025-06-21T09:25:00Z", "2025-06-22T00:31:00Z"),
+            ("2025-12-21", 40.7128, -74.0060, "2025-12-21T12:17:00Z", "2025-12-21T21:32:00Z"),
+            ("2025-06-21", 51.5074, -0.1278, "2025-06-21T03:43:00Z", "2025-06-21T20:21:00Z"),
+            ("2025-12-21", 51.5074, -0.1278, "2025-12-21T08:04:00Z", "2025-12-21T15:53:00Z"),
+        ] {
+            let Daylight::RiseAndSet(sunrise, sunset) = daylight(date(day), latitude, longitude) else {
+                panic!("no sunrise on {}", day);
+            };
+            assert_within(sunrise, rise, 2);
+            assert_within(sunset, set, 2);
+        }
+        // Tromsø
+        assert_eq!(daylight(date("2025-06-21"), 69.6492, 18.9553), Daylight::MidnightSun);
+        assert_eq!(daylight(date("2025-12-21"), 69.6492, 18.9553), Daylight::PolarNight);
+    }
+
+    #[tokio::test]
+    async fn test_tool_operations() {
+        let tool = AstronomyTool::with_clock(Arc::new(FixedClock::new(instant("2025-08-21T12:50:00Z"))));
+        let run = |args: Value| tool.run(serde_json::from_value(args).unwrap());
+
+        let full = run(json!({"operation": "next_moon_phase", "phase": "full_moon"})).await.unwrap();
+        assert!(full.starts_with("Full Moon: 2025-09-07 18:") && full.ends_with("(17 days from 2025-08-21)"), "{}", full);
+        let solstice = run(json!({"operation": "next_season", "event": "summer_solstice"})).await.unwrap();
+        assert!(solstice.starts_with("June solstice: 2026-06-21 08:") && solstice.ends_with("(304 days from 2025-08-21)"), "{}", solstice);
+        let equinox = run(json!({"operation": "season", "event": "march_equinox", "year": 2026})).await.unwrap();
+        assert!(equinox.starts_with("March equinox 2026: 2026-03-20 14:"), "{}", equinox);
+        let phase = run(json!({"operation": "moon_phase", "date": "2025-09-10"})).await.unwrap();
+        assert!(phase.starts_with("Waning Gibbous on 2025-09-10: 9"), "{}", phase);
+        let sun = run(json!({"operation": "sun", "latitude": 51.5074, "longitude": -0.1278, "date": "2025-12-21"})).await.unwrap();
+        assert!(sun.starts_with("Sunrise 2025-12-21 08:0") && sun.contains("daylight 7h 4"), "{}", sun);
+
+        let error = |result: Result<String, AgentError>| result.unwrap_err().to_string();
+        assert!(error(run(json!({"operation": "next_moon_phase", "phase": "blue"})).await).contains("Invalid phase 'blue'"));
+        assert!(error(run(json!({"operation": "season", "event": "equinox"})).await).contains("Invalid event"));
+        assert!(error(run(json!({"operation": "sun", "latitude": 95, "longitude": 0})).await).contains("between -90 and 90"));
+        assert!(error(run(json!({"operation": "sun", "latitude": 40})).await).contains("'longitude'"));
+    }
+}
@@ -1,9 +1,58 @@
 use std::collections::HashMap;
+use std::sync::LazyLock;
 use async_trait::async_trait;
-use evalexpr::eval;
+use regex::Regex;
 use serde_json::{json, Value};
 use crate::error::AgentError;
-use crate::tools::Tool;
+use crate::tools::arithmetic::{evaluate_with, Number, CONSTANTS, FUNCTIONS};
+use crate::tools::normalize::normalize_with;
+use crate::tools::{RunContext, Tool};
+
+/// Decimal places shown for results that aren't whole numbers.
+const DEFAULT_PRECISION: usize = 10;
+const MAX_PRECISION: usize = 1000;
+const MAX_BATCH: usize = 50;
+
+static ASSIGNMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)^\s*([A-Za-z_][A-Za-z0-9_]*)\s*=([^=].*)$").unwrap()
+});
+
+/// Variables and earlier results, kept in the agent run's `RunContext` so they
+/// last for one run. Results are exact, so reusing one loses nothing.
+#[derive(Debug, Clone, Default)]
+pub struct CalculatorScope {
+    /// Assigned variables, `ans` and `$1`, `$2`, ... for each result
+    values: HashMap<String, Number>,
+    results: usize,
+}
+
+impl CalculatorScope {
+    fn record(&mut self, name: Option<&str>, value: &Number) {
+        self.results += 1;
+        self.values.insert(format!("${}", self.results), value.clone());
+        self.values.insert("ans".to_string(), value.clone());
+        if let Some(name) = name {
+            self.values.insert(name.to_string(), value.clone());
+        }
+    }
+}
+
+/// One evaluated line: `label = normalized = value`, leaving out the label
+/// for a lone expression and the normalized form when nothing was rewritten.
+struct Evaluated {
+    name: Option<String>,
+    normalized: Option<String>,
+    value: Number,
+}
+
+impl Evaluated {
+    fn render(&self, label: Option<&str>, precision: usize) -> String {
+        let label = self.name.as_deref().or(label);
+        let value = self.value.format(precision);
+        let normalized = self.normalized.as_deref().filter(|n| *n != value);
+        [label, normalized, Some(&value)].into_iter().flatten().collect::<Vec<_>>().join(" = ")
+    }
+}
 
 pub struct CalculatorTool;
 
@@ -11,6 +60,45 @@ impl CalculatorTool {
     pub fn new() -> Self {
         Self
     }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Evaluates `expression`, or the right side of `name = expression`, and
+    /// records the result in `scope`.
+    fn evaluate(&self, scope: &mut CalculatorScope, expression: &str) -> Result<Evaluated, String> {
+        let (name, body) = match ASSIGNMENT.captures(expression) {
+            Some(caps) => (Some(caps[1].to_string()), caps.get(2).map_or("", |m| m.as_str())),
+            None => (None, expression),
+        };
+        if let Some(name) = &name {
+            let lower = name.to_lowercase();
+            if name == "ans" || FUNCTIONS.contains(&lower.as_str()) || CONSTANTS.contains(&lower.as_str()) {
+                return Err(format!("Can't assign to '{}', a built-in name", name));
+            }
+        }
+
+        // what was actually evaluated is shown whenever it differs from what was sent
+        let normalized = normalize_with(body, |n| scope.values.contains_key(n));
+        let rewritten = normalized != body.trim();
+        let value = evaluate_with(&normalized, &scope.values).map_err(|e| if rewritten {
+            format!("Failed to evaluate expression '{}' (normalized to '{}'): {}", expression, normalized, e)
+        } else {
+            format!("Failed to evaluate expression '{}': {}", expression, e)
+        })?;
+        scope.record(name.as_deref(), &value);
+        Ok(Evaluated { name, normalized: rewritten.then_some(normalized), value })
+    }
+}
+
+impl Default for CalculatorTool {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[async_trait]
@@ -20,7 +108,7 @@ impl Tool for CalculatorTool {
     }
 
     fn description(&self) -> &'static str {
-        "Evaluate arithmetic expressions, e.g., '2 + 2 * (3 - 1)'. Args: { expression: string }"
+        "Evaluate arithmetic expressions exactly, e.g., '2 + 2 * (3 - 1)', '4561 / 7', '30!'. Supports + - * / ^ !, parentheses, sqrt, abs, round, floor, ceil, min, max, exp, ln, log10, log2, log(x, base), sin, cos, tan, asin, acos, atan, pi and e. Assign with 'total = 1847 * 923' and reuse 'total' in later calls; 'ans' is the last result and '$1', '$2', ... are results in order. Send several steps at once as 'expressions'. Args: { expression?: string, expressions?: string[], precision?: integer }"
     }
 
     fn json_schema(&self) -> serde_json::Value {
@@ -29,45 +117,74 @@ impl Tool for CalculatorTool {
             "properties": {
                 "expression": {
                     "type": "string",
-                    "description": "The mathematical expression to evaluate"
+                    "description": "The mathematical expression to evaluate, optionally as 'name = expression'"
+                },
+                "expressions": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Several expressions evaluated in order, each able to use the ones before"
+                },
+                "precision": {
+                    "type": "integer",
+                    "description": "Decimal places for results that aren't whole numbers (default 10)"
                 }
             },
-            "required": ["expression"]
+            "required": []
         })
     }
 
+    /// Outside an agent run there are no earlier results to use.
     async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
-        
-        let expression = args.get("expression")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| AgentError::InvalidInputError("Missing 'expression' parameter".to_string()))?;
+        self.run_in(args, &RunContext::default()).await
+    }
+
+    async fn run_in(&self, args: HashMap<String, Value>, context: &RunContext) -> Result<String, AgentError> {
+        let batch = match (args.get("expression"), args.get("expressions")) {
+            (Some(_), Some(_)) => return Err(AgentError::InvalidInputError("Use either 'expression' or 'expressions', not both".to_string())),
+            (None, Some(list)) => {
+                let list: Vec<&str> = list.as_array()
+                    .and_then(|items| items.iter().map(|v| v.as_str()).collect())
+                    .ok_or_else(|| AgentError::InvalidInputError("'expressions' must be an array of strings".to_string()))?;
+                if list.is_empty() || list.len() > MAX_BATCH {
+                    return Err(AgentError::InvalidInputError(format!("'expressions' must hold 1 to {} expressions", MAX_BATCH)));
+                }
+                Some(list)
+            }
+            _ => None,
+        };
+        let expressions = match &batch {
+            Some(list) => list.clone(),
+            None => vec![args.get("expression")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| AgentError::InvalidInputError("Missing 'expression' parameter".to_string()))?],
+        };
 
-        if expression.trim().is_empty() {
+        if expressions.iter().any(|e| e.trim().is_empty()) {
             return Err(AgentError::InvalidInputError("Expression cannot be empty".to_string()));
         }
 
-        match eval(&expression) {
-            Ok(result) => {
-                let result_str = match result {
-                    evalexpr::Value::Int(i) => i.to_string(),
-                    evalexpr::Value::Float(f) => {
-                        if f.fract() == 0.0 {
-                            (f as i64).to_string()
-                        } else {
-                            // more friendly format for LLM
-                            format!("{:.6}", f).trim_end_matches('0').trim_end_matches('.').to_string()
-                        }
-                    },
-                    evalexpr::Value::Boolean(b) => b.to_string(),
-                    _ => result.to_string(),
-                };
-                Ok(result_str)
-            },
-            Err(e) => Err(AgentError::ToolError {
-                tool_name: self.name().to_string(),
-                message: format!("Failed to evaluate expression '{}': {}", expression, e)
-            })
+        let precision = match args.get("precision") {
+            None => DEFAULT_PRECISION,
+            Some(value) => value.as_u64()
+                .filter(|p| *p as usize <= MAX_PRECISION)
+                .ok_or_else(|| AgentError::InvalidInputError(format!("'precision' must be a whole number from 0 to {}", MAX_PRECISION)))? as usize,
+        };
+
+        // a batch is all or nothing: the scope only changes once every line has evaluated
+        let mut shared = context.calculator.lock()
+            .map_err(|_| self.error("Variables from this run are unavailable after an earlier calculator failure"))?;
+        let mut scope = shared.clone();
+        let mut lines = Vec::new();
+        for (i, expression) in expressions.iter().enumerate() {
+            let evaluated = self.evaluate(&mut scope, expression).map_err(|e| match &batch {
+                Some(list) => self.error(format!("Expression {} of {}: {}. No results from this call were kept", i + 1, list.len(), e)),
+                None => self.error(e),
+            })?;
+            let label = batch.is_some().then(|| format!("${}", scope.results));
+            lines.push(evaluated.render(label.as_deref(), precision));
         }
+        *shared = scope;
+        Ok(lines.join("\n"))
     }
 }
 
@@ -95,6 +212,78 @@ mod tests {
         assert_eq!(result, "128");
     }
 
+    #[tokio::test]
+    async fn test_calculator_true_division() {
+        let calc = CalculatorTool::new();
+        let mut args = HashMap::new();
+        args.insert("expression".to_string(), Value::String("4561 / 7".to_string()));
+        args.insert("precision".to_string(), json!(3));
+
+        let result = calc.run(args).await.unwrap();
+        assert_eq!(result, "651.571 (rounded from 4561/7)");
+    }
+
+    #[tokio::test]
+    async fn test_calculator_shows_normalized_expression() {
+        let calc = CalculatorTool::new();
+        let mut args = HashMap::new();
+        args.insert("expression".to_string(), Value::String("23% of 2,847".to_string()));
+
+        let result = calc.run(args).await.unwrap();
+        assert_eq!(result, "(23/100) * 2847 = 654.81");
+
+        let mut args = HashMap::new();
+        args.insert("expression".to_string(), Value::String("2(3 + 4) mod 5".to_string()));
+        let err = calc.run(args).await.unwrap_err().to_string();
+        assert!(err.contains("(normalized to '2*(3 + 4) mod 5'): Unexpected 'mod'"), "{}", err);
+    }
+
+    fn args(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_calculator_variables_persist_between_calls() {
+        let calc = CalculatorTool::new();
+        let run = RunContext::default();
+        assert_eq!(calc.run_in(args(json!({"expression": "total = 1,847 * 923"})), &run).await.unwrap(), "total = 1847 * 923 = 1704781");
+        assert_eq!(calc.run_in(args(json!({"expression": "total / 7"})), &run).await.unwrap(), "243540.1428571429 (rounded from 1704781/7)");
+        assert_eq!(calc.run_in(args(json!({"expression": "ans * 7 - $1"})), &run).await.unwrap(), "0");
+        assert_eq!(calc.run_in(args(json!({"expression": "2total"})), &run).await.unwrap(), "2*total = 3409562");
+
+        let err = calc.run_in(args(json!({"expression": "pi = 3"})), &run).await.unwrap_err().to_string();
+        assert!(err.contains("Can't assign to 'pi', a built-in name"), "{}", err);
+
+        // a new run starts empty, even with the same tool
+        let err = calc.run_in(args(json!({"expression": "total"})), &RunContext::default()).await.unwrap_err().to_string();
+        assert!(err.contains("Unknown name 'total'"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_calculator_poisoned_scope_is_an_error() {
+        let run = RunContext::default();
+        let _ = std::panic::catch_unwind(|| {
+            let _guard = run.calculator.lock().unwrap();
+            panic!("poison");
+        });
+        let err = CalculatorTool::new().run_in(args(json!({"expression": "1 + 1"})), &run).await.unwrap_err().to_string();
+        assert!(err.contains("unavailable after an earlier calculator failure"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_calculator_batch() {
+        let calc = CalculatorTool::new();
+        let run = RunContext::default();
+        let result = calc.run_in(args(json!({"expressions": ["salary = 67,500", "salary / 52 / 40", "round(ans * 100) / 100"], "precision": 4})), &run).await.unwrap();
+        assert_eq!(result, "salary = 67500\n$2 = 32.4519 (rounded from 3375/104)\n$3 = 32.45");
+
+        let err = calc.run_in(args(json!({"expressions": ["bonus = 5000", "bonus / 0"]})), &run).await.unwrap_err().to_string();
+        assert!(err.contains("Expression 2 of 2: Failed to evaluate expression 'bonus / 0': Division by zero. No results from this call were kept"), "{}", err);
+        let err = calc.run_in(args(json!({"expression": "bonus"})), &run).await.unwrap_err().to_string();
+        assert!(err.contains("Unknown name 'bonus'"), "{}", err);
+        assert_eq!(calc.run_in(args(json!({"expression": "$3"})), &run).await.unwrap(), "32.45");
+    }
+
     #[tokio::test]
     async fn test_calculator_validation_fails() {
         let calc = CalculatorTool::new();
@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde_json::{json, Value};
+use crate::error::AgentError;
+use crate::tools::Tool;
+
+/// Calendar arithmetic on plain `YYYY-MM-DD` dates, so the model never
+/// has to count days itself.
+pub struct DateMathTool;
+
+impl DateMathTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn date_arg(&self, args: &HashMap<String, Value>, key: &str) -> Result<NaiveDate, AgentError> {
+        let text = args.get(key)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.error(format!("Missing '{}' parameter (a date as YYYY-MM-DD)", key)))?;
+        parse_date(text).map_err(|message| self.error(message))
+    }
+
+    fn unit_arg<'a>(&self, args: &'a HashMap<String, Value>, allowed: &[&str]) -> Result<&'a str, AgentError> {
+        let unit = args.get("unit").and_then(|v| v.as_str()).unwrap_or("days");
+        if allowed.contains(&unit) {
+            Ok(unit)
+        } else {
+            Err(self.error(format!("Invalid unit '{}'. Use: {}", unit, allowed.join(", "))))
+        }
+    }
+
+    fn shift(&self, args: &HashMap<String, Value>, sign: i64) -> Result<String, AgentError> {
+        let date = self.date_arg(args, "date")?;
+        let amount = args.get("amount")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| self.error("Missing or non-integer 'amount' parameter"))?;
+        let unit = self.unit_arg(args, &["days", "weeks", "months", "years", "business_days"])?;
+        let amount = amount.checked_mul(sign).ok_or_else(|| self.error("'amount' is out of range"))?;
+        let shifted = match unit {
+            "days" => add_days(date, amount),
+            "weeks" => amount.checked_mul(7).and_then(|days| add_days(date, days)),
+            "months" => add_months(date, amount),
+            "years" => amount.checked_mul(12).and_then(|months| add_months(date, months)),
+            _ => add_business_days(date, amount),
+        };
+        shifted
+            .map(|d| d.format("%Y-%m-%d").to_string())
+            .ok_or_else(|| self.error(format!("{} {} from {} is out of range", amount, unit, date)))
+    }
+
+    fn difference(&self, args: &HashMap<String, Value>) -> Result<String, AgentError> {
+        let start = self.date_arg(args, "date")?;
+        let end = self.date_arg(args, "end_date")?;
+        let unit = self.unit_arg(args, &["days", "weeks", "months", "business_days"])?;
+        let days = (end - start).num_days();
+        Ok(match unit {
+            "days" => days.to_string(),
+            "weeks" => format!("{} weeks, {} days", days / 7, days % 7),
+            "months" => {
+                let (months, rest) = months_between(start, end);
+                format!("{} months, {} days", months, rest)
+            }
+            _ => business_days_between(start, end).to_string(),
+        })
+    }
+}
+
+impl Default for DateMathTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for DateMathTool {
+    fn name(&self) -> &'static str {
+        "date_math"
+    }
+
+    fn description(&self) -> &'static str {
+        "Date arithmetic on YYYY-MM-DD dates: difference between two dates, add/subtract durations, day of week, day of year, ISO week, leap year. Args: { operation: string, date?: string, end_date?: string, amount?: integer, unit?: string, year?: integer }"
+    }
+
+    fn json_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "description": "'difference' (end_date minus date), 'add', 'subtract', 'day_of_week', 'day_of_year', 'iso_week' or 'is_leap_year'",
+                    "enum": ["difference", "add", "subtract", "day_of_week", "day_of_year", "iso_week", "is_leap_year"]
+                },
+                "date": {
+                    "type": "string",
+                    "description": "Date as YYYY-MM-DD (the start date for 'difference')"
+                },
+                "end_date": {
+                    "type": "string",
+                    "description": "End date as YYYY-MM-DD, for 'difference'"
+                },
+                "amount": {
+                    "type": "integer",
+                    "description": "How many units to add or subtract"
+                },
+                "unit": {
+                    "type": "string",
+                    "description": "For 'difference': days, weeks, months or business_days (Mon-Fri, counting from the day after date through end_date). For 'add'/'subtract': days, weeks, months, years or business_days. Adding months clamps to the end of the month.",
+                    "default": "days",
+                    "enum": ["days", "weeks", "months", "years", "business_days"]
+                },
+                "year": {
+                    "type": "integer",
+                    "description": "Year for 'is_leap_year' (or pass date)"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
+        let operation = args.get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.error("Missing 'operation' parameter"))?;
+
+        match operation {
+            "difference" => self.difference(&args),
+            "add" => self.shift(&args, 1),
+            "subtract" => self.shift(&args, -1),
+            "day_of_week" => Ok(weekday_name(self.date_arg(&args, "date")?.weekday()).to_string()),
+            "day_of_year" => Ok(self.date_arg(&args, "date")?.ordinal().to_string()),
+            "iso_week" => {
+                let week = self.date_arg(&args, "date")?.iso_week();
+                Ok(format!("{}-W{:02}", week.year(), week.week()))
+            }
+            "is_leap_year" => {
+                let year = match args.get("year") {
+                    Some(value) => value.as_i64()
+                        .and_then(|y| i32::try_from(y).ok())
+                        .ok_or_else(|| self.error(format!("Invalid year {}", value)))?,
+                    None => self.date_arg(&args, "date")?.year(),
+                };
+                Ok(is_leap_year(year).to_string())
+            }
+            _ => Err(self.error(format!(
+                "Invalid operation '{}'. Use: difference, add, subtract, day_of_week, day_of_year, iso_week, or is_leap_year",
+                operation
+            ))),
+        }
+    }
+}
+
+/// Exactly `YYYY-MM-DD`, and a real calendar day.
+pub fn parse_date(text: &str) -> Result<NaiveDate, String> {
+    let parts: Vec<&str> = text.split('-').collect();
+    let well_formed = parts.len() == 3
+        && [4, 2, 2].iter().zip(&parts).all(|(len, part)| part.len() == *len && part.bytes().all(|b| b.is_ascii_digit()));
+    if !well_formed {
+        return Err(format!("Invalid date '{}': expected YYYY-MM-DD, e.g. 2025-08-22", text));
+    }
+    let number = |part: &str| part.parse::<u32>().unwrap_or_default();
+    NaiveDate::from_ymd_opt(number(parts[0]) as i32, number(parts[1]), number(parts[2]))
+        .ok_or_else(|| format!("Invalid date '{}': no such day in the calendar", text))
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub(crate) fn weekday_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+fn is_business_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    if days >= 0 {
+        date.checked_add_days(Days::new(days as u64))
+    } else {
+        date.checked_sub_days(Days::new(days.unsigned_abs()))
+    }
+}
+
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let count = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 { date.checked_add_months(count) } else { date.checked_sub_months(count) }
+}
+
+fn add_business_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    if days == 0 {
+        return Some(date);
+    }
+    let step = if days > 0 { 1 } else { -1 };
+    // from a weekend, count as if from the business day behind it
+    let mut current = date;
+    while !is_business_day(current) {
+        current = add_days(current, -step)?;
+    }
+    // whole weeks first, so large amounts don't walk day by day
+    current = add_days(current, days.checked_div(5)?.checked_mul(7)?)?;
+    let mut left = days % 5;
+    while left != 0 {
+        current = add_days(current, step)?;
+        if is_business_day(current) {
+            left -= step;
+        }
+    }
+    Some(current)
+}
+
+/// Whole months from `start` towards `end`, then the days left over; both
+/// negative when `end` is earlier.
+fn months_between(start: NaiveDate, end: NaiveDate) -> (i64, i64) {
+    if end < start {
+        let (months, days) = months_between(end, start);
+        return (-months, -days);
+    }
+    let mut months = (end.year() - start.year()) as i64 * 12 + end.month() as i64 - start.month() as i64;
+    while months > 0 && add_months(start, months).is_none_or(|d| d > end) {
+        months -= 1;
+    }
+    let anchor = add_months(start, months).unwrap_or(start);
+    (months, (end - anchor).num_days())
+}
+
+/// Weekdays after `start` up to and including `end`; negative when `end` is
+/// earlier.
+fn business_days_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    if end < start {
+        return -business_days_between(end, start);
+    }
+    let days = (end - start).num_days();
+    let whole_weeks = days / 7;
+    let mut count = whole_weeks * 5;
+    let mut current = add_days(start, whole_weeks * 7).unwrap_or(end);
+    while current < end {
+        current = current.succ_opt().unwrap_or(end);
+        if is_business_day(current) {
+            count += 1;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run(args: Value) -> Result<String, AgentError> {
+        let args: HashMap<String, Value> = serde_json::from_value(args).unwrap();
+        DateMathTool::new().run(args).await
+    }
+
+    #[tokio::test]
+    async fn test_difference_in_days() {
+        let days = run(json!({"operation": "difference", "date": "2025-03-17", "end_date": "2025-11-27"})).await.unwrap();
+        assert_eq!(days, "255");
+        let back = run(json!({"operation": "difference", "date": "2025-12-25", "end_date": "2025-08-22"})).await.unwrap();
+        assert_eq!(back, "-125");
+    }
+
+    #[tokio::test]
+    async fn test_difference_in_weeks_and_months() {
+        let weeks = run(json!({"operation": "difference", "date": "2025-08-22", "end_date": "2025-12-25", "unit": "weeks"})).await.unwrap();
+        assert_eq!(weeks, "17 weeks, 6 days");
+        let months = run(json!({"operation": "difference", "date": "2025-01-31", "end_date": "2025-03-30", "unit": "months"})).await.unwrap();
+        assert_eq!(months, "1 months, 30 days");
+        let back = run(json!({"operation": "difference", "date": "2025-12-25", "end_date": "2025-08-22", "unit": "months"})).await.unwrap();
+        assert_eq!(back, "-4 months, -3 days");
+    }
+
+    #[test]
+    fn test_business_days_between() {
+        let date = |s| parse_date(s).unwrap();
+        // Fri -> Mon
+        assert_eq!(business_days_between(date("2025-08-22"), date("2025-08-25")), 1);
+        // Sat -> Sun
+        assert_eq!(business_days_between(date("2025-08-23"), date("2025-08-24")), 0);
+        assert_eq!(business_days_between(date("2025-08-01"), date("2025-08-31")), 20);
+        assert_eq!(business_days_between(date("2025-08-31"), date("2025-08-01")), -20);
+        for (start, end) in [("2025-08-23", "2025-10-04"), ("2025-08-20", "2026-01-01")] {
+            let (start, end) = (date(start), date(end));
+            let walked = start.iter_days().skip(1).take_while(|d| *d <= end).filter(|d| is_business_day(*d)).count();
+            assert_eq!(business_days_between(start, end), walked as i64);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_and_subtract() {
+        let add = |amount: i64, unit: &str| run(json!({"operation": "add", "date": "2025-01-31", "amount": amount, "unit": unit}));
+        assert_eq!(add(30, "days").await.unwrap(), "2025-03-02");
+        assert_eq!(add(2, "weeks").await.unwrap(), "2025-02-14");
+        assert_eq!(add(1, "months").await.unwrap(), "2025-02-28");
+        assert_eq!(add(-1, "years").await.unwrap(), "2024-01-31");
+        let leap = run(json!({"operation": "subtract", "date": "2024-02-29", "amount": 1, "unit": "years"})).await.unwrap();
+        assert_eq!(leap, "2023-02-28");
+    }
+
+    #[tokio::test]
+    async fn test_add_business_days() {
+        let add = |date: &str, amount: i64| run(json!({"operation": "add", "date": date, "amount": amount, "unit": "business_days"}));
+        // Fri + 1 -> Mon
+        assert_eq!(add("2025-08-22", 1).await.unwrap(), "2025-08-25");
+        assert_eq!(add("2025-08-22", 5).await.unwrap(), "2025-08-29");
+        // Sat + 5 -> next Fri
+        assert_eq!(add("2025-08-23", 5).await.unwrap(), "2025-08-29");
+        assert_eq!(add("2025-08-25", -1).await.unwrap(), "2025-08-22");
+        assert_eq!(add("2025-08-24", -5).await.unwrap(), "2025-08-18");
+        let start = parse_date("2025-08-23").unwrap();
+        for amount in [3, 10, 23, -7, -23] {
+            let end = parse_date(&add("2025-08-23", amount).await.unwrap()).unwrap();
+            let counted = business_days_between(start, end);
+            assert_eq!(counted, if amount < 0 { amount + 1 } else { amount }, "{}", amount);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_calendar_facts() {
+        let ask = |operation: &str, date: &str| run(json!({"operation": operation, "date": date}));
+        assert_eq!(ask("day_of_week", "2025-12-25").await.unwrap(), "Thursday");
+        assert_eq!(ask("day_of_year", "2024-12-31").await.unwrap(), "366");
+        assert_eq!(ask("iso_week", "2025-08-22").await.unwrap(), "2025-W34");
+        // belongs to the last ISO week of the previous year
+        assert_eq!(ask("iso_week", "2027-01-01").await.unwrap(), "2026-W53");
+        assert_eq!(ask("is_leap_year", "2024-06-01").await.unwrap(), "true");
+        for (year, leap) in [(1900, false), (2000, true), (2025, false)] {
+            assert_eq!(run(json!({"operation": "is_leap_year", "year": year})).await.unwrap(), leap.to_string());
+        }
+    }
+
+    #[test]
+    fn test_parse_date_is_strict() {
+        assert!(parse_date("2025-08-22").is_ok());
+        for bad in ["2025-8-22", "22/08/2025", "2025-08-22T00:00:00", " 2025-08-22", "2025-08", "+025-08-22", "today"] {
+            let err = parse_date(bad).unwrap_err();
+            assert!(err.contains("expected YYYY-MM-DD"), "{}: {}", bad, err);
+        }
+        assert!(parse_date("2025-02-29").unwrap_err().contains("no such day"));
+        assert!(parse_date("2025-13-01").unwrap_err().contains("no such day"));
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_clear() {
+        let message = |result: Result<String, AgentError>| result.unwrap_err().to_string();
+        assert!(message(run(json!({"operation": "difference", "date": "2025-08-22"})).await).contains("'end_date'"));
+        assert!(message(run(json!({"operation": "add", "date": "2025-08-22", "amount": 1.5})).await).contains("'amount'"));
+        assert!(message(run(json!({"operation": "add", "date": "2025-08-22", "amount": 1, "unit": "fortnights"})).await).contains("Invalid unit"));
+        assert!(message(run(json!({"operation": "difference", "date": "2025-08-22", "end_date": "2025-09-01", "unit": "years"})).await).contains("Invalid unit"));
+        assert!(message(run(json!({"operation": "age"})).await).contains("Invalid operation"));
+        assert!(message(run(json!({"operation": "add", "date": "2025-08-22", "amount": i64::MAX, "unit": "days"})).await).contains("out of range"));
+    }
+}
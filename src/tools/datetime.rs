@@ -1,15 +1,30 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc, Local};
+use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
+use crate::clock::{system_clock, Clock};
 use crate::error::AgentError;
+use crate::tools::timezone::resolve_zone;
 use crate::tools::Tool;
 
-pub struct DatetimeTool;
+pub struct DatetimeTool {
+    clock: Arc<dyn Clock>,
+}
 
 impl DatetimeTool {
     pub fn new() -> Self {
-        Self
+        Self::with_clock(system_clock())
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+}
+
+impl Default for DatetimeTool {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -20,7 +35,7 @@ impl Tool for DatetimeTool {
     }
 
     fn description(&self) -> &'static str {
-        "Get the current date and time in various formats. Args: { format: string }"
+        "Get the current date and time in various formats, in UTC or an IANA time zone. Args: { format: string, timezone?: string }"
     }
 
     fn json_schema(&self) -> serde_json::Value {
@@ -29,9 +44,13 @@ impl Tool for DatetimeTool {
             "properties": {
                 "format": {
                     "type": "string",
-                    "description": "Output format: 'timestamp' (Unix timestamp), 'iso' (ISO 8601), 'human' (human readable), or 'local' (local timezone)",
+                    "description": "Output format: 'timestamp' (Unix timestamp), 'iso' (ISO 8601), 'human' (human readable), or 'local' (the given timezone, which it requires)",
                     "default": "iso",
                     "enum": ["timestamp", "iso", "human", "local"]
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA zone such as 'America/Chicago' for 'iso', 'human' and 'local'. Required for 'local'; the others default to UTC"
                 }
             },
             "required": []
@@ -44,15 +63,27 @@ impl Tool for DatetimeTool {
             .and_then(|v| v.as_str())
             .unwrap_or("iso");
 
-        let now_utc: DateTime<Utc> = Utc::now();
+        let now_utc: DateTime<Utc> = self.clock.now();
+        let zone = match args.get("timezone").and_then(|v| v.as_str()) {
+            Some(name) => Some(resolve_zone(name).map_err(|message| AgentError::ToolError {
+                tool_name: self.name().to_string(),
+                message,
+            })?),
+            None => None,
+        };
 
-        let result = match format {
-            "timestamp" => now_utc.timestamp().to_string(),
-            "iso" => now_utc.to_rfc3339(),
-            "human" => now_utc.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
-            "local" => {
-                let local_time = now_utc.with_timezone(&Local);
-                local_time.format("%Y-%m-%d %H:%M:%S %Z").to_string()
+        let result = match (format, zone) {
+            ("timestamp", _) => now_utc.timestamp().to_string(),
+            ("iso", Some(zone)) => now_utc.with_timezone(&zone).to_rfc3339(),
+            ("human" | "local", Some(zone)) => now_utc.with_timezone(&zone).format("%Y-%m-%d %H:%M:%S %Z").to_string(),
+            ("iso", None) => now_utc.to_rfc3339(),
+            ("human", None) => now_utc.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            // the server's own zone says nothing about the user's
+            ("local", None) => {
+                return Err(AgentError::ToolError {
+                    tool_name: self.name().to_string(),
+                    message: "Format 'local' needs a 'timezone', e.g. 'America/Chicago'".to_string(),
+                });
             },
             _ => {
                 return Err(AgentError::ToolError {
@@ -110,4 +141,42 @@ mod tests {
         let result = dt.run(args).await.unwrap();
         assert!(result.contains("T")); // Should be ISO format by default
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_datetime_uses_clock() {
+        let instant = crate::clock::parse_instant("2025-08-22T12:00:00Z").unwrap();
+        let dt = DatetimeTool::with_clock(Arc::new(crate::clock::FixedClock::new(instant)));
+
+        let mut args = HashMap::new();
+        args.insert("format".to_string(), Value::String("human".to_string()));
+        assert_eq!(dt.run(args).await.unwrap(), "2025-08-22 12:00:00 UTC");
+
+        let mut args = HashMap::new();
+        args.insert("format".to_string(), Value::String("timestamp".to_string()));
+        assert_eq!(dt.run(args).await.unwrap(), "1755864000");
+    }
+
+    #[tokio::test]
+    async fn test_datetime_in_timezone() {
+        let instant = crate::clock::parse_instant("2025-08-22T12:00:00Z").unwrap();
+        let dt = DatetimeTool::with_clock(Arc::new(crate::clock::FixedClock::new(instant)));
+
+        let mut args = HashMap::new();
+        args.insert("format".to_string(), Value::String("iso".to_string()));
+        args.insert("timezone".to_string(), Value::String("Asia/Tokyo".to_string()));
+        assert_eq!(dt.run(args).await.unwrap(), "2025-08-22T21:00:00+09:00");
+
+        let mut args = HashMap::new();
+        args.insert("format".to_string(), Value::String("local".to_string()));
+        args.insert("timezone".to_string(), Value::String("America/Chicago".to_string()));
+        assert_eq!(dt.run(args).await.unwrap(), "2025-08-22 07:00:00 CDT");
+
+        let mut args = HashMap::new();
+        args.insert("timezone".to_string(), Value::String("Atlantis".to_string()));
+        assert!(dt.run(args).await.is_err());
+
+        let mut args = HashMap::new();
+        args.insert("format".to_string(), Value::String("local".to_string()));
+        assert!(dt.run(args).await.unwrap_err().to_string().contains("needs a 'timezone'"));
+    }
+}
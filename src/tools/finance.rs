@@ -0,0 +1,614 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use serde_json::{json, Value};
+use crate::error::AgentError;
+use crate::tools::arithmetic::{parse_literal, ten_pow};
+use crate::tools::Tool;
+
+/// More periods than this (about 55 years of daily compounding) is a mistake.
+const MAX_PERIODS: u64 = 20_000;
+/// Decimal places a growth factor is carried to: far past cents on any
+/// realistic amount, and its size stays fixed however many periods there are.
+const GROWTH_PLACES: usize = 30;
+/// Beyond about this many bits of whole part a growth factor is astronomical,
+/// and carrying it stops being quick.
+const MAX_GROWTH_BITS: f64 = 20_000.0;
+const MAX_SCHEDULE_ROWS: u64 = 600;
+
+/// How a result is rounded to its decimal places.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Half away from zero: 2.345 -> 2.35, -2.345 -> -2.35
+    HalfUp,
+    /// Banker's rounding: 2.345 -> 2.34, 2.355 -> 2.36
+    HalfEven,
+    /// Toward zero
+    Down,
+    /// Away from zero
+    Up,
+    Floor,
+    Ceiling,
+}
+
+impl Rounding {
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "half_up" => Rounding::HalfUp,
+            "half_even" | "bankers" => Rounding::HalfEven,
+            "down" | "truncate" => Rounding::Down,
+            "up" => Rounding::Up,
+            "floor" => Rounding::Floor,
+            "ceiling" => Rounding::Ceiling,
+            _ => return None,
+        })
+    }
+
+    /// `value` rounded to `places` decimal places.
+    pub fn round(self, value: &BigRational, places: usize) -> BigRational {
+        let scale = BigRational::from_integer(ten_pow(places));
+        let scaled = value * &scale;
+        let rounded = match self {
+            Rounding::HalfUp => scaled.round(),
+            Rounding::HalfEven => {
+                let floor = scaled.floor();
+                let half = BigRational::new(BigInt::one(), BigInt::from(2));
+                if &scaled - &floor == half {
+                    if floor.to_integer() % 2 == BigInt::zero() { floor } else { floor + BigRational::one() }
+                } else {
+                    scaled.round()
+                }
+            }
+            Rounding::Down => scaled.trunc(),
+            Rounding::Up => {
+                let truncated = scaled.trunc();
+                if truncated == scaled { truncated } else { truncated + BigRational::from_integer(scaled.signum().to_integer()) }
+            }
+            Rounding::Floor => scaled.floor(),
+            Rounding::Ceiling => scaled.ceil(),
+        };
+        rounded / scale
+    }
+}
+
+/// Every digit to `places`, e.g. `1668.70`; `value` should already be rounded.
+pub fn fixed(value: &BigRational, places: usize) -> String {
+    let scaled = (value * BigRational::from_integer(ten_pow(places))).round().to_integer();
+    let digits = format!("{:0>width$}", scaled.abs().to_string(), width = places + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - places);
+    let sign = if scaled.is_negative() { "-" } else { "" };
+    if places == 0 { format!("{}{}", sign, whole) } else { format!("{}{}.{}", sign, whole, fraction) }
+}
+
+fn percent(rate: f64) -> String {
+    let text = format!("{:.4}", rate * 100.0);
+    format!("{}%", text.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// `(1 + rate)^periods` by repeated squaring, with every step rounded
+/// half-even to `GROWTH_PLACES`; the caller's rounding is for money only.
+fn growth(rate: &BigRational, periods: i64) -> BigRational {
+    let step = |value: BigRational| Rounding::HalfEven.round(&value, GROWTH_PLACES);
+    let one_plus_rate = BigRational::one() + rate;
+    let mut base = if periods < 0 { step(one_plus_rate.recip()) } else { one_plus_rate };
+    let mut factor = BigRational::one();
+    let mut exponent = periods.unsigned_abs();
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            factor = step(&factor * &base);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base = step(&base * &base);
+        }
+    }
+    factor
+}
+
+/// The level payment that repays `principal` over `periods` at `rate` per period.
+pub fn payment(principal: &BigRational, rate: &BigRational, periods: i64) -> BigRational {
+    let discount = BigRational::one() - growth(rate, -periods);
+    // a rate too small to register in the growth factor repays like no interest
+    if rate.is_zero() || discount.is_zero() {
+        return principal / BigRational::from_integer(periods.into());
+    }
+    principal * rate / discount
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleRow {
+    pub period: i64,
+    pub payment: BigRational,
+    pub interest: BigRational,
+    pub principal: BigRational,
+    pub balance: BigRational,
+}
+
+/// Amortization with the payment and each period's interest rounded like a
+/// lender would; the last payment absorbs the rounding and clears the balance.
+pub fn amortize(principal: &BigRational, rate: &BigRational, periods: i64, places: usize, rounding: Rounding) -> Vec<ScheduleRow> {
+    let level = rounding.round(&payment(principal, rate, periods), places);
+    let mut balance = principal.clone();
+    let mut rows = Vec::new();
+    for period in 1..=periods {
+        let interest = rounding.round(&(&balance * rate), places);
+        let payment = if period == periods { &balance + &interest } else { level.clone() };
+        let principal = &payment - &interest;
+        balance = &balance - &principal;
+        rows.push(ScheduleRow { period, payment, interest, principal, balance: balance.clone() });
+    }
+    rows
+}
+
+/// Net present value with the first cash flow at time 0 (undiscounted).
+/// Spreadsheet `NPV()` discounts the first flow too.
+pub fn npv(rate: &BigRational, cash_flows: &[BigRational]) -> BigRational {
+    let discount = growth(rate, -1);
+    let mut factor = BigRational::one();
+    let mut total = BigRational::zero();
+    for flow in cash_flows {
+        total += flow * &factor;
+        factor = Rounding::HalfEven.round(&(&factor * &discount), GROWTH_PLACES);
+    }
+    total
+}
+
+/// The per-period rate at which `npv` is zero, if the flows change sign.
+pub fn irr(cash_flows: &[f64]) -> Option<f64> {
+    let npv = |rate: f64| cash_flows.iter().zip(0..).map(|(flow, t)| flow / (1.0 + rate).powi(t)).sum::<f64>();
+    // scan for the first sign change above -100%, then bisect it; near
+    // -100% long series overflow, and inf - inf is no sign to go by
+    let mut low = -0.9999;
+    let mut npv_low = npv(low);
+    let mut step = 0.01;
+    while low < 100.0 {
+        let high = low + step;
+        let npv_high = npv(high);
+        if npv_low == 0.0 {
+            return Some(low);
+        }
+        if npv_low.is_finite() && npv_high.is_finite() && npv_low.signum() != npv_high.signum() {
+            let (mut a, mut b, mut npv_a) = (low, high, npv_low);
+            for _ in 0..200 {
+                let mid = (a + b) / 2.0;
+                let npv_mid = npv(mid);
+                if npv_mid.signum() == npv_a.signum() {
+                    a = mid;
+                    npv_a = npv_mid;
+                } else {
+                    b = mid;
+                }
+            }
+            return Some((a + b) / 2.0);
+        }
+        low = high;
+        npv_low = npv_high;
+        if low > 1.0 {
+            step = 0.1;
+        }
+    }
+    None
+}
+
+pub struct FinanceTool;
+
+impl FinanceTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An exact decimal from a JSON number or a string like "$2,847.50" or "4.2%".
+    fn decimal(&self, key: &str, value: &Value) -> Result<BigRational, AgentError> {
+        let text = match value {
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => s.chars().filter(|c| !matches!(c, '$' | '€' | '£' | ',' | '_' | '%') && !c.is_whitespace()).collect(),
+            _ => String::new(),
+        };
+        let (sign, digits) = match text.strip_prefix('-') {
+            Some(rest) => (-BigRational::one(), rest),
+            None => (BigRational::one(), text.as_str()),
+        };
+        parse_literal(digits)
+            .map(|value| sign * value)
+            .map_err(|_| self.error(format!("'{}' must be a number (got {})", key, value)))
+    }
+
+    fn amount_arg(&self, args: &HashMap<String, Value>, key: &str) -> Result<BigRational, AgentError> {
+        let value = args.get(key).ok_or_else(|| self.error(format!("Missing '{}' parameter", key)))?;
+        self.decimal(key, value)
+    }
+
+    fn optional_amount_arg(&self, args: &HashMap<String, Value>, key: &str) -> Result<BigRational, AgentError> {
+        match args.get(key) {
+            Some(value) => self.decimal(key, value),
+            None => Ok(BigRational::zero()),
+        }
+    }
+
+    /// An annual percentage such as 4.2 (or "4.2%"), as a fraction.
+    fn rate_arg(&self, args: &HashMap<String, Value>, key: &str) -> Result<BigRational, AgentError> {
+        let rate = self.amount_arg(args, key)? / BigRational::from_integer(100.into());
+        if rate <= -BigRational::one() {
+            return Err(self.error(format!("'{}' must be above -100%", key)));
+        }
+        Ok(rate)
+    }
+
+    fn amounts_arg(&self, args: &HashMap<String, Value>, key: &str) -> Result<Vec<BigRational>, AgentError> {
+        let items = args.get(key)
+            .and_then(|v| v.as_array())
+            .filter(|items| !items.is_empty())
+            .ok_or_else(|| self.error(format!("Missing '{}' parameter (a non-empty array of amounts)", key)))?;
+        items.iter().enumerate().map(|(i, item)| self.decimal(&format!("{}[{}]", key, i), item)).collect()
+    }
+
+    /// Periods per year from a number or a name; `None` for continuous.
+    fn frequency_arg(&self, args: &HashMap<String, Value>, default: u32) -> Result<Option<u32>, AgentError> {
+        let Some(value) = args.get("periods_per_year") else {
+            return Ok(Some(default));
+        };
+        if let Some(n) = value.as_u64().filter(|n| (1..=365).contains(n)) {
+            return Ok(Some(n as u32));
+        }
+        let name = value.as_str().unwrap_or_default();
+        Ok(Some(match name {
+            "annually" | "yearly" => 1,
+            "semiannually" | "semi-annually" => 2,
+            "quarterly" => 4,
+            "monthly" => 12,
+            "biweekly" => 26,
+            "weekly" => 52,
+            "daily" => 365,
+            "continuous" | "continuously" => return Ok(None),
+            _ => return Err(self.error(format!(
+                "Invalid periods_per_year {}. Use 1 to 365, or annually, semiannually, quarterly, monthly, biweekly, weekly, daily or continuous", value
+            ))),
+        }))
+    }
+
+    /// Whole periods in `years` at `per_year`, or `periods` when given.
+    fn periods_arg(&self, args: &HashMap<String, Value>, per_year: u32) -> Result<i64, AgentError> {
+        let periods = match args.get("periods") {
+            Some(value) => self.decimal("periods", value)?,
+            None => self.amount_arg(args, "years")? * BigRational::from_integer(per_year.into()),
+        };
+        if !periods.is_integer() || periods.is_negative() {
+            return Err(self.error(format!(
+                "The number of periods must be a whole number (got {}); give 'periods' or whole years at this frequency",
+                fixed(&periods, 4).trim_end_matches('0').trim_end_matches('.')
+            )));
+        }
+        periods.to_integer().to_u64()
+            .filter(|n| *n <= MAX_PERIODS)
+            .map(|n| n as i64)
+            .ok_or_else(|| self.error(format!("At most {} periods are supported", MAX_PERIODS)))
+    }
+
+    /// Refuses rates and periods whose growth factor, or its inverse, is
+    /// astronomically large.
+    fn check_growth(&self, rate: &BigRational, periods: i64) -> Result<(), AgentError> {
+        let bits = (1.0 + rate.to_f64().unwrap_or_default()).log2().abs() * periods as f64;
+        if bits > MAX_GROWTH_BITS {
+            return Err(self.error("The growth over this many periods at this rate is too large to work with; use fewer periods or a smaller rate"));
+        }
+        Ok(())
+    }
+
+    fn rounding_args(&self, args: &HashMap<String, Value>) -> Result<(usize, Rounding), AgentError> {
+        let places = match args.get("decimals") {
+            Some(value) => value.as_u64().filter(|d| *d <= 10)
+                .ok_or_else(|| self.error(format!("'decimals' must be a whole number from 0 to 10 (got {})", value)))? as usize,
+            None => 2,
+        };
+        let rounding = match args.get("rounding") {
+            Some(value) => {
+                let name = value.as_str().unwrap_or_default();
+                Rounding::parse(name).ok_or_else(|| self.error(format!(
+                    "Invalid rounding {}. Use: half_up, half_even, down, up, floor, or ceiling", value
+                )))?
+            }
+            None => Rounding::HalfUp,
+        };
+        Ok((places, rounding))
+    }
+}
+
+impl Default for FinanceTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for FinanceTool {
+    fn name(&self) -> &'static str {
+        "finance"
+    }
+
+    fn description(&self) -> &'static str {
+        "Financial math with exact decimal arithmetic: simple and compound interest, loan payments and amortization schedules, future and present value, NPV, IRR, CAGR and inflation adjustment. Rates are percentages (4.2 means 4.2%). Args: { operation: string, principal?: number, rate?: number, years?: number, periods?: integer, periods_per_year?: integer | string, payment?: number, future_value?: number, present_value?: number, cash_flows?: number[], start_value?: number, end_value?: number, amount?: number, decimals?: integer, rounding?: string }"
+    }
+
+    fn json_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["simple_interest", "compound_interest", "loan_payment", "amortization", "future_value", "present_value", "npv", "irr", "cagr", "inflation"],
+                    "description": "What to compute"
+                },
+                "principal": {"type": "number", "description": "Amount invested or borrowed"},
+                "rate": {"type": "number", "description": "Annual rate in percent (4.2 for 4.2%); per period for npv"},
+                "years": {"type": "number", "description": "Term in years"},
+                "periods": {"type": "integer", "description": "Number of periods, instead of years"},
+                "periods_per_year": {
+                    "type": ["integer", "string"],
+                    "description": "Compounding or payment frequency: 1 to 365, or annually, semiannually, quarterly, monthly, biweekly, weekly, daily, continuous. Defaults: 1 for interest and values, 12 for loans"
+                },
+                "payment": {"type": "number", "description": "Regular payment per period (end of period), for future_value and present_value"},
+                "future_value": {"type": "number", "description": "Amount at the end, for present_value"},
+                "present_value": {"type": "number", "description": "Amount at the start, for future_value"},
+                "cash_flows": {"type": "array", "items": {"type": "number"}, "description": "Cash flows per period starting at time 0 (investments negative), for npv and irr"},
+                "start_value": {"type": "number", "description": "Starting value, for cagr"},
+                "end_value": {"type": "number", "description": "Ending value, for cagr"},
+                "amount": {"type": "number", "description": "Amount to adjust, for inflation"},
+                "decimals": {"type": "integer", "description": "Decimal places for money (default 2)"},
+                "rounding": {
+                    "type": "string",
+                    "enum": ["half_up", "half_even", "down", "up", "floor", "ceiling"],
+                    "description": "Rounding mode for money (default half_up: half away from zero)"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
+        let operation = args.get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.error("Missing 'operation' parameter"))?;
+        let (places, rounding) = self.rounding_args(&args)?;
+        let money = |value: &BigRational| fixed(&rounding.round(value, places), places);
+        let per_period = |rate: &BigRational, per_year: u32| rate / BigRational::from_integer(per_year.into());
+
+        match operation {
+            "simple_interest" => {
+                let principal = self.amount_arg(&args, "principal")?;
+                let rate = self.rate_arg(&args, "rate")?;
+                let years = self.amount_arg(&args, "years")?;
+                let interest = &principal * &rate * &years;
+                Ok(format!("Interest: {}\nTotal: {}", money(&interest), money(&(principal + interest))))
+            }
+            "compound_interest" | "future_value" | "present_value" => {
+                let rate = self.rate_arg(&args, "rate")?;
+                let Some(per_year) = self.frequency_arg(&args, 1)? else {
+                    if operation != "compound_interest" {
+                        return Err(self.error("Continuous compounding is only supported by compound_interest"));
+                    }
+                    // e^(rt) is irrational, so this one value goes through floating point
+                    let principal = self.amount_arg(&args, "principal")?;
+                    let years = self.amount_arg(&args, "years")?;
+                    let factor = (rate.to_f64().unwrap_or_default() * years.to_f64().unwrap_or_default()).exp();
+                    let total = BigRational::from_float(principal.to_f64().unwrap_or_default() * factor)
+                        .ok_or_else(|| self.error("Result is too large"))?;
+                    let interest = &total - &principal;
+                    return Ok(format!("Future value: {}\nInterest: {}\n(compounded continuously)", money(&total), money(&interest)));
+                };
+                let periods = self.periods_arg(&args, per_year)?;
+                let i = per_period(&rate, per_year);
+                self.check_growth(&i, periods)?;
+                let factor = growth(&i, periods);
+                // value of a payment at the end of each period, at the end
+                let annuity = if i.is_zero() { BigRational::from_integer(periods.into()) } else { (&factor - BigRational::one()) / &i };
+                let payment = self.optional_amount_arg(&args, "payment")?;
+                match operation {
+                    "compound_interest" => {
+                        let principal = self.amount_arg(&args, "principal")?;
+                        let total = &principal * &factor;
+                        Ok(format!("Future value: {}\nInterest: {}\n({} periods at {} per period)",
+                            money(&total), money(&(&total - &principal)), periods, percent(i.to_f64().unwrap_or_default())))
+                    }
+                    "future_value" => {
+                        let present = self.optional_amount_arg(&args, "present_value")?;
+                        let total = present * &factor + &payment * annuity;
+                        Ok(format!("Future value: {}\n({} periods at {} per period)", money(&total), periods, percent(i.to_f64().unwrap_or_default())))
+                    }
+                    _ => {
+                        let future = self.optional_amount_arg(&args, "future_value")?;
+                        let total = (future + &payment * annuity) * growth(&i, -periods);
+                        Ok(format!("Present value: {}\n({} periods at {} per period)", money(&total), periods, percent(i.to_f64().unwrap_or_default())))
+                    }
+                }
+            }
+            "loan_payment" | "amortization" => {
+                let principal = self.amount_arg(&args, "principal")?;
+                let rate = self.rate_arg(&args, "rate")?;
+                let per_year = self.frequency_arg(&args, 12)?
+                    .ok_or_else(|| self.error("Loans need a payment frequency, not continuous"))?;
+                let periods = self.periods_arg(&args, per_year)?;
+                if periods == 0 {
+                    return Err(self.error("A loan needs at least one payment"));
+                }
+                let rate = per_period(&rate, per_year);
+                self.check_growth(&rate, periods)?;
+                let rows = amortize(&principal, &rate, periods, places, rounding);
+                let total: BigRational = rows.iter().map(|r| &r.payment).fold(BigRational::zero(), |a, b| a + b);
+                let level = &rows[0].payment;
+                let last = &rows[rows.len() - 1].payment;
+                let mut summary = format!("Payment: {} per period ({} payments", money(level), periods);
+                if last != level {
+                    summary.push_str(&format!(", the last {}", money(last)));
+                }
+                summary.push_str(&format!(")\nTotal paid: {}\nTotal interest: {}", money(&total), money(&(&total - &principal))));
+                if operation == "loan_payment" {
+                    return Ok(summary);
+                }
+                if periods as u64 > MAX_SCHEDULE_ROWS {
+                    return Err(self.error(format!("Schedules are limited to {} periods; use loan_payment for the totals", MAX_SCHEDULE_ROWS)));
+                }
+                let mut lines = vec![summary, "period | payment | interest | principal | balance".to_string()];
+                lines.extend(rows.iter().map(|r| format!(
+                    "{} | {} | {} | {} | {}", r.period, money(&r.payment), money(&r.interest), money(&r.principal), money(&r.balance)
+                )));
+                Ok(lines.join("\n"))
+            }
+            "npv" => {
+                let rate = self.rate_arg(&args, "rate")?;
+                let flows = self.amounts_arg(&args, "cash_flows")?;
+                self.check_growth(&rate, flows.len() as i64)?;
+                Ok(format!("NPV: {}\n(first cash flow at time 0, undiscounted)", money(&npv(&rate, &flows))))
+            }
+            "irr" => {
+                let flows = self.amounts_arg(&args, "cash_flows")?;
+                let floats: Vec<f64> = flows.iter().map(|f| f.to_f64().unwrap_or_default()).collect();
+                let rate = irr(&floats).ok_or_else(|| self.error(
+                    "No IRR found: the cash flows must change sign (an investment followed by returns)"
+                ))?;
+                Ok(format!("IRR: {} per period", percent(rate)))
+            }
+            "cagr" => {
+                let start = self.amount_arg(&args, "start_value")?;
+                let end = self.amount_arg(&args, "end_value")?;
+                let years = self.amount_arg(&args, "years")?;
+                if !start.is_positive() || !end.is_positive() || !years.is_positive() {
+                    return Err(self.error("CAGR needs positive start_value, end_value and years"));
+                }
+                let ratio = (end / start).to_f64().unwrap_or_default();
+                let cagr = ratio.powf(1.0 / years.to_f64().unwrap_or_default()) - 1.0;
+                Ok(format!("CAGR: {} per year", percent(cagr)))
+            }
+            "inflation" => {
+                let amount = self.amount_arg(&args, "amount")?;
+                let rate = self.rate_arg(&args, "rate")?;
+                let years = self.periods_arg(&args, 1)?;
+                self.check_growth(&rate, years)?;
+                let factor = growth(&rate, years);
+                let discount = growth(&rate, -years);
+                Ok(format!(
+                    "{} today costs {} after {} years of {} inflation\n{} then is worth {} in today's money",
+                    money(&amount), money(&(&amount * &factor)), years, percent(rate.to_f64().unwrap_or_default()),
+                    money(&amount), money(&(&amount * &discount)),
+                ))
+            }
+            _ => Err(self.error(format!(
+                "Invalid operation '{}'. Use: simple_interest, compound_interest, loan_payment, amortization, future_value, present_value, npv, irr, cagr, or inflation", operation
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(text: &str) -> BigRational {
+        let (sign, digits) = text.strip_prefix('-').map_or((1, text), |rest| (-1, rest));
+        parse_literal(digits).unwrap() * BigRational::from_integer(sign.into())
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let cases = [
+            ("2.345", ["2.35", "2.34", "2.34", "2.35", "2.34", "2.35"]),
+            ("2.355", ["2.36", "2.36", "2.35", "2.36", "2.35", "2.36"]),
+            ("-2.345", ["-2.35", "-2.34", "-2.34", "-2.35", "-2.35", "-2.34"]),
+            ("2.3401", ["2.34", "2.34", "2.34", "2.35", "2.34", "2.35"]),
+        ];
+        let modes = [Rounding::HalfUp, Rounding::HalfEven, Rounding::Down, Rounding::Up, Rounding::Floor, Rounding::Ceiling];
+        for (value, expected) in cases {
+            for (mode, expected) in modes.iter().zip(expected) {
+                assert_eq!(fixed(&mode.round(&decimal(value), 2), 2), expected, "{} {:?}", value, mode);
+            }
+        }
+        assert_eq!(fixed(&decimal("1668.7"), 2), "1668.70");
+        assert_eq!(fixed(&Rounding::HalfUp.round(&decimal("0.5"), 0), 0), "1");
+    }
+
+    #[test]
+    fn test_amortization_clears_the_balance() {
+        let rows = amortize(&decimal("200000"), &decimal("0.005"), 360, 2, Rounding::HalfUp);
+        assert_eq!(fixed(&rows[0].payment, 2), "1199.10");
+        assert_eq!(fixed(&rows[0].interest, 2), "1000.00");
+        assert_eq!(fixed(&rows[0].principal, 2), "199.10");
+        assert!(rows[359].balance.is_zero());
+        assert_eq!(fixed(&rows[359].payment, 2), "1200.14");
+
+        let rows = amortize(&decimal("1000"), &BigRational::zero(), 3, 2, Rounding::HalfUp);
+        let payments: Vec<String> = rows.iter().map(|r| fixed(&r.payment, 2)).collect();
+        assert_eq!(payments, ["333.33", "333.33", "333.34"]);
+    }
+
+    #[test]
+    fn test_npv_and_irr() {
+        let flows: Vec<BigRational> = ["-1000", "300", "400", "500"].iter().map(|f| decimal(f)).collect();
+        let value = npv(&decimal("0.1"), &flows);
+        assert_eq!(fixed(&Rounding::HalfUp.round(&value, 2), 2), "-21.04");
+
+        let rate = irr(&[-1000.0, 300.0, 400.0, 500.0]).unwrap();
+        assert!((rate - 0.0889633947).abs() < 1e-9, "{}", rate);
+        assert_eq!(irr(&[100.0, 200.0]), None);
+        // the lowest rates overflow on long series instead of changing sign
+        let mut long = vec![-1000.0];
+        long.extend([20.0; 99]);
+        long.push(-50.0);
+        let rate = irr(&long).unwrap();
+        assert!((rate + 2.0 / 7.0).abs() < 1e-9, "{}", rate);
+    }
+
+    #[tokio::test]
+    async fn test_tool_operations() {
+        let tool = FinanceTool::new();
+        let run = |args: Value| tool.run(serde_json::from_value(args).unwrap());
+
+        let compound = run(json!({"operation": "compound_interest", "principal": 5000, "rate": 4.2, "years": 7})).await.unwrap();
+        assert_eq!(compound, "Future value: 6668.74\nInterest: 1668.74\n(7 periods at 4.2% per period)");
+        let monthly = run(json!({"operation": "compound_interest", "principal": "$10,000", "rate": "5%", "years": 10, "periods_per_year": "monthly"})).await.unwrap();
+        assert!(monthly.starts_with("Future value: 16470.09\n"), "{}", monthly);
+        // daily over decades stays fast: the growth factor has a fixed number of places
+        let daily = run(json!({"operation": "compound_interest", "principal": 10000, "rate": 4.2, "years": 30, "periods_per_year": "daily"})).await.unwrap();
+        assert_eq!(daily, "Future value: 35251.66\nInterest: 25251.66\n(10950 periods at 0.0115% per period)");
+        let discounted = run(json!({"operation": "present_value", "future_value": 1000, "rate": 5, "years": 40, "periods_per_year": 365})).await.unwrap();
+        assert!(discounted.starts_with("Present value: 135.35\n"), "{}", discounted);
+        let continuous = run(json!({"operation": "compound_interest", "principal": 1000, "rate": 5, "years": 10, "periods_per_year": "continuous"})).await.unwrap();
+        assert!(continuous.starts_with("Future value: 1648.72\n"), "{}", continuous);
+
+        let simple = run(json!({"operation": "simple_interest", "principal": 2500, "rate": 3.5, "years": 1.5})).await.unwrap();
+        assert_eq!(simple, "Interest: 131.25\nTotal: 2631.25");
+
+        let loan = run(json!({"operation": "loan_payment", "principal": 200000, "rate": 6, "years": 30})).await.unwrap();
+        assert!(loan.starts_with("Payment: 1199.10 per period (360 payments, the last "), "{}", loan);
+        let schedule = run(json!({"operation": "amortization", "principal": 1000, "rate": 12, "periods": 3})).await.unwrap();
+        assert!(schedule.ends_with("period | payment | interest | principal | balance\n1 | 340.02 | 10.00 | 330.02 | 669.98\n2 | 340.02 | 6.70 | 333.32 | 336.66\n3 | 340.03 | 3.37 | 336.66 | 0.00"), "{}", schedule);
+
+        let fv = run(json!({"operation": "future_value", "payment": 100, "rate": 6, "years": 10, "periods_per_year": 12})).await.unwrap();
+        assert!(fv.starts_with("Future value: 16387.93\n"), "{}", fv);
+        let pv = run(json!({"operation": "present_value", "future_value": 10000, "rate": 5, "years": 3})).await.unwrap();
+        assert!(pv.starts_with("Present value: 8638.38\n"), "{}", pv);
+        let banker = run(json!({"operation": "simple_interest", "principal": 0.5, "rate": 1, "years": 1, "decimals": 3, "rounding": "half_even"})).await.unwrap();
+        assert_eq!(banker, "Interest: 0.005\nTotal: 0.505");
+
+        assert_eq!(run(json!({"operation": "irr", "cash_flows": [-1000, 300, 400, 500]})).await.unwrap(), "IRR: 8.8963% per period");
+        assert_eq!(run(json!({"operation": "cagr", "start_value": 1000, "end_value": 2000, "years": 5})).await.unwrap(), "CAGR: 14.8698% per year");
+        let inflation = run(json!({"operation": "inflation", "amount": 100, "rate": 3, "years": 10})).await.unwrap();
+        assert_eq!(inflation, "100.00 today costs 134.39 after 10 years of 3% inflation\n100.00 then is worth 74.41 in today's money");
+
+        let error = |result: Result<String, AgentError>| result.unwrap_err().to_string();
+        assert!(error(run(json!({"operation": "compound_interest", "principal": 100, "rate": 5, "years": 2.1, "periods_per_year": 4})).await).contains("whole number (got 8.4)"));
+        assert!(error(run(json!({"operation": "simple_interest", "principal": "lots", "rate": 5, "years": 1})).await).contains("'principal' must be a number"));
+        assert!(error(run(json!({"operation": "loan_payment", "principal": 100, "rate": 5, "years": 1, "rounding": "nearest"})).await).contains("Invalid rounding"));
+        assert!(error(run(json!({"operation": "irr", "cash_flows": [100, 200]})).await).contains("must change sign"));
+        assert!(error(run(json!({"operation": "compound_interest", "principal": 1, "rate": 5, "periods": 100000})).await).contains("At most 20000 periods"));
+        assert!(error(run(json!({"operation": "compound_interest", "principal": 1, "rate": 1000, "periods": 20000})).await).contains("too large to work with"));
+        assert!(error(run(json!({"operation": "mortgage"})).await).contains("Invalid operation"));
+    }
+}
@@ -0,0 +1,556 @@
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use async_trait::async_trait;
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::clock::{system_clock, Clock};
+use crate::error::AgentError;
+use crate::tools::date_math::{parse_date, weekday_name};
+use crate::tools::Tool;
+
+pub const DEFAULT_REGION: &str = "US";
+
+/// Leap Day can be eight years away (e.g. 2096 to 2104)
+const MAX_YEARS_AHEAD: i32 = 9;
+
+/// How a holiday falls in a given year.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HolidayRule {
+    /// Same day every year; Feb 29 only happens in leap years
+    Fixed { month: u32, day: u32 },
+    /// `nth` weekday of the month, e.g. 4 for the fourth; -1 is the last
+    NthWeekday { month: u32, weekday: Weekday, nth: i8 },
+    /// First `weekday` on or after month/day, e.g. US Election Day
+    WeekdayOnOrAfter { month: u32, day: u32, weekday: Weekday },
+    /// Last `weekday` on or before month/day, e.g. Victoria Day
+    WeekdayOnOrBefore { month: u32, day: u32, weekday: Weekday },
+    /// Days from Western (Gregorian) Easter Sunday
+    Easter {
+        #[serde(default)]
+        offset: i64,
+    },
+}
+
+impl HolidayRule {
+    /// The date in `year`, or `None` when it doesn't occur that year.
+    pub fn date_in(&self, year: i32) -> Option<NaiveDate> {
+        match *self {
+            HolidayRule::Fixed { month, day } => NaiveDate::from_ymd_opt(year, month, day),
+            HolidayRule::NthWeekday { month, weekday, nth } if nth > 0 => {
+                NaiveDate::from_weekday_of_month_opt(year, month, weekday, nth as u8)
+            }
+            HolidayRule::NthWeekday { month, weekday, nth } => {
+                let last = last_weekday(year, month, weekday)?;
+                let weeks_back = u64::from(nth.unsigned_abs()).checked_sub(1)?;
+                last.checked_sub_days(Days::new(weeks_back * 7)).filter(|d| d.month() == month)
+            }
+            HolidayRule::WeekdayOnOrAfter { month, day, weekday } => {
+                let start = NaiveDate::from_ymd_opt(year, month, day)?;
+                start.iter_days().find(|d| d.weekday() == weekday)
+            }
+            HolidayRule::WeekdayOnOrBefore { month, day, weekday } => {
+                let start = NaiveDate::from_ymd_opt(year, month, day)?;
+                start.iter_days().rev().find(|d| d.weekday() == weekday)
+            }
+            HolidayRule::Easter { offset } => {
+                let easter = easter_sunday(year)?;
+                if offset >= 0 {
+                    easter.checked_add_days(Days::new(offset as u64))
+                } else {
+                    easter.checked_sub_days(Days::new(offset.unsigned_abs()))
+                }
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let month_day = |month: u32, day: u32| {
+            // a leap year, so Feb 29 is allowed
+            NaiveDate::from_ymd_opt(2024, month, day)
+                .map(|_| ())
+                .ok_or_else(|| format!("month {} has no day {}", month, day))
+        };
+        match *self {
+            HolidayRule::Fixed { month, day }
+            | HolidayRule::WeekdayOnOrAfter { month, day, .. }
+            | HolidayRule::WeekdayOnOrBefore { month, day, .. } => month_day(month, day),
+            HolidayRule::NthWeekday { month, nth, .. } => {
+                month_day(month, 1)?;
+                if nth == 0 || !(-5..=5).contains(&nth) {
+                    return Err(format!("nth must be 1 to 5, or -1 to -5 from the end of the month (got {})", nth));
+                }
+                Ok(())
+            }
+            HolidayRule::Easter { offset } if offset.abs() > 366 => {
+                Err(format!("Easter offset {} is more than a year", offset))
+            }
+            HolidayRule::Easter { .. } => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holiday {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Region codes observing it (US, CA, GB, ...); empty means everywhere
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
+    #[serde(flatten)]
+    pub rule: HolidayRule,
+}
+
+impl Holiday {
+    fn new(name: &str, aliases: &[&str], regions: &[&str], rule: HolidayRule) -> Self {
+        Self {
+            name: name.to_string(),
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            regions: regions.iter().map(|r| r.to_string()).collect(),
+            rule,
+        }
+    }
+
+    fn observed_in(&self, region: &str) -> bool {
+        self.regions.is_empty() || self.regions.iter().any(|r| normalize_region(r) == region)
+    }
+
+    fn answers_to(&self, key: &str) -> bool {
+        std::iter::once(&self.name).chain(&self.aliases).any(|n| name_key(n) == key)
+    }
+}
+
+/// The built-in holidays plus any loaded from a data file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HolidayCalendar {
+    /// Added on top of the built-in rules; these win over a built-in holiday
+    /// with the same name
+    #[serde(default)]
+    pub holidays: Vec<Holiday>,
+}
+
+impl HolidayCalendar {
+    /// Built-in holidays only.
+    pub fn builtin() -> Self {
+        Self::default()
+    }
+
+    pub fn from_yaml_file(path: &str) -> Result<Self, AgentError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AgentError::ConfigurationError(format!("Failed to read holidays file {}: {}", path, e)))?;
+        let calendar: Self = serde_yaml::from_str(&content)
+            .map_err(|e| AgentError::ConfigurationError(format!("Failed to parse holidays file {}: {}", path, e)))?;
+        for holiday in &calendar.holidays {
+            if holiday.name.trim().is_empty() {
+                return Err(AgentError::ConfigurationError(format!("Holidays file {}: a holiday has no name", path)));
+            }
+            holiday.rule.validate().map_err(|e| {
+                AgentError::ConfigurationError(format!("Holidays file {}: {}: {}", path, holiday.name, e))
+            })?;
+        }
+        Ok(calendar)
+    }
+
+    fn all(&self) -> impl Iterator<Item = &Holiday> {
+        self.holidays.iter().chain(BUILTIN.iter())
+    }
+
+    /// The holiday called `name` (or one of its aliases) in `region`.
+    pub fn find(&self, name: &str, region: &str) -> Result<&Holiday, String> {
+        let key = name_key(name);
+        let region = normalize_region(region);
+        let named: Vec<&Holiday> = self.all().filter(|h| h.answers_to(&key)).collect();
+        if named.is_empty() {
+            return Err(format!("Unknown holiday '{}'; operation 'list' shows the holidays for a region", name));
+        }
+        named.iter().copied().find(|h| h.observed_in(&region)).ok_or_else(|| {
+            let mut regions: Vec<&str> = named.iter().flat_map(|h| h.regions.iter().map(String::as_str)).collect();
+            regions.sort_unstable();
+            regions.dedup();
+            format!("'{}' is not observed in region {} (observed in: {})", name, region, regions.join(", "))
+        })
+    }
+
+    pub fn date_in(&self, name: &str, region: &str, year: i32) -> Result<NaiveDate, String> {
+        let holiday = self.find(name, region)?;
+        holiday.rule.date_in(year)
+            .ok_or_else(|| format!("{} does not occur in {}", holiday.name, year))
+    }
+
+    /// First occurrence on or after `from`.
+    pub fn next_on_or_after(&self, name: &str, region: &str, from: NaiveDate) -> Result<NaiveDate, String> {
+        let holiday = self.find(name, region)?;
+        (from.year()..=from.year() + MAX_YEARS_AHEAD)
+            .filter_map(|year| holiday.rule.date_in(year))
+            .find(|date| *date >= from)
+            .ok_or_else(|| format!("{} does not occur within {} years of {}", holiday.name, MAX_YEARS_AHEAD, from))
+    }
+
+    /// Region codes some holiday is limited to.
+    pub fn regions(&self) -> Vec<String> {
+        let mut regions: Vec<String> = self.all().flat_map(|h| h.regions.iter().map(|r| normalize_region(r))).collect();
+        regions.sort_unstable();
+        regions.dedup();
+        regions
+    }
+
+    /// Every holiday observed in `region` during `year`, by date.
+    pub fn list(&self, region: &str, year: i32) -> Vec<(NaiveDate, &Holiday)> {
+        let region = normalize_region(region);
+        let mut seen: Vec<String> = Vec::new();
+        let mut days = Vec::new();
+        for holiday in self.all().filter(|h| h.observed_in(&region)) {
+            // a data-file rule replaces the built-in one
+            let key = name_key(&holiday.name);
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            if let Some(date) = holiday.rule.date_in(year) {
+                days.push((date, holiday));
+            }
+        }
+        days.sort_by_key(|(date, _)| *date);
+        days
+    }
+}
+
+static BUILTIN: LazyLock<Vec<Holiday>> = LazyLock::new(|| {
+    use HolidayRule::*;
+    let fixed = |month, day| Fixed { month, day };
+    let nth = |month, weekday, nth| NthWeekday { month, weekday, nth };
+    let easter = |offset| Easter { offset };
+    vec![
+        // everywhere
+        Holiday::new("New Year's Day", &["New Year"], &[], fixed(1, 1)),
+        Holiday::new("Valentine's Day", &[], &[], fixed(2, 14)),
+        Holiday::new("Leap Day", &["Leap Year"], &[], fixed(2, 29)),
+        Holiday::new("St. Patrick's Day", &["Saint Patrick's Day", "St. Paddy's Day"], &[], fixed(3, 17)),
+        Holiday::new("April Fools' Day", &[], &[], fixed(4, 1)),
+        Holiday::new("Earth Day", &[], &[], fixed(4, 22)),
+        Holiday::new("Halloween", &[], &[], fixed(10, 31)),
+        Holiday::new("Christmas Eve", &[], &[], fixed(12, 24)),
+        Holiday::new("Christmas Day", &["Christmas"], &[], fixed(12, 25)),
+        Holiday::new("New Year's Eve", &[], &[], fixed(12, 31)),
+        Holiday::new("Mardi Gras", &["Shrove Tuesday", "Pancake Day", "Fat Tuesday"], &[], easter(-47)),
+        Holiday::new("Ash Wednesday", &[], &[], easter(-46)),
+        Holiday::new("Palm Sunday", &[], &[], easter(-7)),
+        Holiday::new("Good Friday", &[], &[], easter(-2)),
+        Holiday::new("Easter Sunday", &["Easter"], &[], easter(0)),
+        Holiday::new("Easter Monday", &[], &[], easter(1)),
+        Holiday::new("Ascension Day", &[], &[], easter(39)),
+        Holiday::new("Pentecost", &["Whitsun"], &[], easter(49)),
+        // United States
+        Holiday::new("Martin Luther King Jr. Day", &["MLK Day"], &["US"], nth(1, Weekday::Mon, 3)),
+        Holiday::new("Groundhog Day", &[], &["US", "CA"], fixed(2, 2)),
+        Holiday::new("Presidents' Day", &["Washington's Birthday"], &["US"], nth(2, Weekday::Mon, 3)),
+        Holiday::new("Mother's Day", &[], &["US", "CA"], nth(5, Weekday::Sun, 2)),
+        Holiday::new("Memorial Day", &[], &["US"], nth(5, Weekday::Mon, -1)),
+        Holiday::new("Father's Day", &[], &["US", "CA", "GB", "IE"], nth(6, Weekday::Sun, 3)),
+        Holiday::new("Juneteenth", &[], &["US"], fixed(6, 19)),
+        Holiday::new("Independence Day", &["Fourth of July", "July 4th"], &["US"], fixed(7, 4)),
+        Holiday::new("Labor Day", &["Labour Day"], &["US", "CA"], nth(9, Weekday::Mon, 1)),
+        Holiday::new("Columbus Day", &["Indigenous Peoples' Day"], &["US"], nth(10, Weekday::Mon, 2)),
+        Holiday::new("Election Day", &[], &["US"], WeekdayOnOrAfter { month: 11, day: 2, weekday: Weekday::Tue }),
+        Holiday::new("Veterans Day", &[], &["US"], fixed(11, 11)),
+        Holiday::new("Thanksgiving", &[], &["US"], nth(11, Weekday::Thu, 4)),
+        // Canada
+        Holiday::new("Victoria Day", &[], &["CA"], WeekdayOnOrBefore { month: 5, day: 24, weekday: Weekday::Mon }),
+        Holiday::new("Canada Day", &[], &["CA"], fixed(7, 1)),
+        Holiday::new("Thanksgiving", &[], &["CA"], nth(10, Weekday::Mon, 2)),
+        Holiday::new("Remembrance Day", &[], &["CA", "GB"], fixed(11, 11)),
+        Holiday::new("Boxing Day", &[], &["CA", "GB", "IE"], fixed(12, 26)),
+        // United Kingdom and Ireland
+        Holiday::new("Mothering Sunday", &["Mother's Day"], &["GB", "IE"], easter(-21)),
+        Holiday::new("Early May Bank Holiday", &["May Day"], &["GB"], nth(5, Weekday::Mon, 1)),
+        Holiday::new("Spring Bank Holiday", &[], &["GB"], nth(5, Weekday::Mon, -1)),
+        Holiday::new("Summer Bank Holiday", &[], &["GB"], nth(8, Weekday::Mon, -1)),
+        Holiday::new("Guy Fawkes Night", &["Bonfire Night"], &["GB"], fixed(11, 5)),
+    ]
+});
+
+/// Western Easter Sunday (anonymous Gregorian algorithm).
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year.rem_euclid(19);
+    let (b, c) = (year.div_euclid(100), year.rem_euclid(100));
+    let (d, e) = (b / 4, b % 4);
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15).rem_euclid(30);
+    let (i, k) = (c / 4, c % 4);
+    let l = (32 + 2 * e + 2 * i - h - k).rem_euclid(7);
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+pub fn last_weekday(year: i32, month: u32, weekday: Weekday) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    let last = NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()?;
+    last.iter_days().rev().find(|d| d.weekday() == weekday)
+}
+
+/// "St. Patrick's Day", "saint patricks day" and "st patricks" all match.
+fn name_key(name: &str) -> String {
+    let cleaned: String = name.to_lowercase()
+        .chars()
+        .filter(|c| *c != '\'' && *c != '’' && *c != '.')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let mut words: Vec<&str> = cleaned.split_whitespace()
+        .filter(|w| *w != "the")
+        .map(|w| if w == "saint" { "st" } else { w })
+        .collect();
+    if words.len() > 1 && words.last() == Some(&"day") {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+fn normalize_region(region: &str) -> String {
+    match region.trim().to_uppercase().as_str() {
+        "UK" => "GB".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Resolves named days (Thanksgiving, Easter, Leap Day, ...) to dates.
+pub struct HolidaysTool {
+    calendar: HolidayCalendar,
+    clock: Arc<dyn Clock>,
+}
+
+impl HolidaysTool {
+    pub fn new() -> Self {
+        Self::with_calendar(HolidayCalendar::builtin(), system_clock())
+    }
+
+    pub fn with_calendar(calendar: HolidayCalendar, clock: Arc<dyn Clock>) -> Self {
+        Self { calendar, clock }
+    }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn year_arg(&self, args: &HashMap<String, Value>) -> Result<i32, AgentError> {
+        match args.get("year") {
+            Some(value) => value.as_i64()
+                .and_then(|y| i32::try_from(y).ok())
+                .filter(|y| (1583..=9999).contains(y))
+                .ok_or_else(|| self.error(format!("Invalid year {}: expected a Gregorian year such as 2025", value))),
+            None => Ok(self.clock.now().year()),
+        }
+    }
+}
+
+impl Default for HolidaysTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for HolidaysTool {
+    fn name(&self) -> &'static str {
+        "holidays"
+    }
+
+    fn description(&self) -> &'static str {
+        "Look up the date of a named holiday or event (Thanksgiving, Easter, Mother's Day, Leap Day, ...) for a year and region, or its next occurrence from a date. Args: { operation: string, name?: string, year?: integer, from?: string, region?: string }"
+    }
+
+    fn json_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "description": "'date' (the holiday in a year), 'next' (first occurrence on or after a date) or 'list' (every holiday in a year)",
+                    "enum": ["date", "next", "list"]
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Holiday name, e.g. 'Thanksgiving', 'Easter', 'Leap Day'"
+                },
+                "year": {
+                    "type": "integer",
+                    "description": "Year for 'date' and 'list'; defaults to the current year"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Start date as YYYY-MM-DD for 'next'; defaults to today"
+                },
+                "region": {
+                    "type": "string",
+                    "description": "Country code: US, CA, GB or IE",
+                    "default": DEFAULT_REGION
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
+        let operation = args.get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.error("Missing 'operation' parameter"))?;
+        let region = args.get("region").and_then(|v| v.as_str()).unwrap_or(DEFAULT_REGION);
+        let regions = self.calendar.regions();
+        if !regions.contains(&normalize_region(region)) {
+            return Err(self.error(format!("Unknown region '{}'. Use: {}", region, regions.join(", "))));
+        }
+        let name = || args.get("name")
+            .and_then(|v| v.as_str())
+            .filter(|n| !n.trim().is_empty())
+            .ok_or_else(|| self.error("Missing 'name' parameter"));
+        let show = |date: NaiveDate| format!("{} ({})", date.format("%Y-%m-%d"), weekday_name(date.weekday()));
+
+        match operation {
+            "date" => {
+                let date = self.calendar.date_in(name()?, region, self.year_arg(&args)?).map_err(|e| self.error(e))?;
+                Ok(show(date))
+            }
+            "next" => {
+                let from = match args.get("from").and_then(|v| v.as_str()) {
+                    Some(text) => parse_date(text).map_err(|e| self.error(e))?,
+                    None => self.clock.now().date_naive(),
+                };
+                let date = self.calendar.next_on_or_after(name()?, region, from).map_err(|e| self.error(e))?;
+                Ok(format!("{}, {} days from {}", show(date), (date - from).num_days(), from.format("%Y-%m-%d")))
+            }
+            "list" => {
+                Ok(self.calendar.list(region, self.year_arg(&args)?).iter().map(|(date, holiday)| format!("{} {}", show(*date), holiday.name)).collect::<Vec<_>>().join("\n"))
+            }
+            _ => Err(self.error(format!("Invalid operation '{}'. Use: date, next, or list", operation))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{parse_instant, FixedClock};
+
+    fn date(text: &str) -> NaiveDate {
+        parse_date(text).unwrap()
+    }
+
+    fn tool() -> HolidaysTool {
+        let clock = Arc::new(FixedClock::new(parse_instant("2025-08-21T12:50:00Z").unwrap()));
+        HolidaysTool::with_calendar(HolidayCalendar::builtin(), clock)
+    }
+
+    async fn run(args: Value) -> Result<String, AgentError> {
+        tool().run(serde_json::from_value(args).unwrap()).await
+    }
+
+    #[test]
+    fn test_easter() {
+        for (year, expected) in [(2024, "2024-03-31"), (2025, "2025-04-20"), (2026, "2026-04-05"), (2038, "2038-04-25"), (1818, "1818-03-22")] {
+            assert_eq!(easter_sunday(year), Some(date(expected)), "{}", year);
+        }
+    }
+
+    #[test]
+    fn test_rules() {
+        let calendar = HolidayCalendar::builtin();
+        let on = |name: &str, region: &str, year: i32| calendar.date_in(name, region, year).unwrap();
+        assert_eq!(on("Thanksgiving", "US", 2025), date("2025-11-27"));
+        assert_eq!(on("Thanksgiving", "CA", 2025), date("2025-10-13"));
+        assert_eq!(on("Memorial Day", "US", 2026), date("2026-05-25"));
+        assert_eq!(on("mothers day", "US", 2026), date("2026-05-10"));
+        assert_eq!(on("Mother's Day", "UK", 2026), date("2026-03-15"));
+        assert_eq!(on("Good Friday", "GB", 2025), date("2025-04-18"));
+        assert_eq!(on("Victoria Day", "CA", 2025), date("2025-05-19"));
+        assert_eq!(on("Election Day", "US", 2026), date("2026-11-03"));
+        assert_eq!(on("indigenous peoples day", "us", 2025), date("2025-10-13"));
+        assert_eq!(on("saint patricks", "US", 2026), date("2026-03-17"));
+        assert_eq!(calendar.date_in("Leap Day", "US", 2025).unwrap_err(), "Leap Day does not occur in 2025");
+    }
+
+    #[test]
+    fn test_nth_from_end() {
+        let second_to_last = HolidayRule::NthWeekday { month: 8, weekday: Weekday::Fri, nth: -2 };
+        assert_eq!(second_to_last.date_in(2025), Some(date("2025-08-22")));
+        let fifth = HolidayRule::NthWeekday { month: 2, weekday: Weekday::Mon, nth: 5 };
+        assert_eq!(fifth.date_in(2025), None);
+    }
+
+    #[test]
+    fn test_next_occurrence() {
+        let calendar = HolidayCalendar::builtin();
+        assert_eq!(calendar.next_on_or_after("Christmas", "US", date("2025-12-25")).unwrap(), date("2025-12-25"));
+        assert_eq!(calendar.next_on_or_after("Christmas", "US", date("2025-12-26")).unwrap(), date("2026-12-25"));
+        assert_eq!(calendar.next_on_or_after("leap year", "US", date("2025-08-21")).unwrap(), date("2028-02-29"));
+        assert_eq!(calendar.next_on_or_after("Leap Day", "US", date("2097-01-01")).unwrap(), date("2104-02-29"));
+    }
+
+    #[test]
+    fn test_unknown_and_unobserved() {
+        let calendar = HolidayCalendar::builtin();
+        assert!(calendar.find("Festivus", "US").unwrap_err().contains("Unknown holiday 'Festivus'"));
+        assert_eq!(calendar.find("Canada Day", "US").unwrap_err(), "'Canada Day' is not observed in region US (observed in: CA)");
+    }
+
+    #[tokio::test]
+    async fn test_tool_operations() {
+        assert_eq!(run(json!({"operation": "date", "name": "Thanksgiving", "year": 2025})).await.unwrap(), "2025-11-27 (Thursday)");
+        assert_eq!(run(json!({"operation": "date", "name": "Easter"})).await.unwrap(), "2025-04-20 (Sunday)");
+        assert_eq!(
+            run(json!({"operation": "next", "name": "Thanksgiving"})).await.unwrap(),
+            "2025-11-27 (Thursday), 98 days from 2025-08-21"
+        );
+        assert_eq!(
+            run(json!({"operation": "next", "name": "Groundhog Day", "from": "2025-08-21"})).await.unwrap(),
+            "2026-02-02 (Monday), 165 days from 2025-08-21"
+        );
+        let list = run(json!({"operation": "list", "year": 2025, "region": "CA"})).await.unwrap();
+        assert!(list.starts_with("2025-01-01 (Wednesday) New Year's Day\n"), "{}", list);
+        assert!(list.contains("2025-10-13 (Monday) Thanksgiving"));
+        assert!(!list.contains("Juneteenth"));
+    }
+
+    #[tokio::test]
+    async fn test_tool_errors() {
+        let message = |result: Result<String, AgentError>| result.unwrap_err().to_string();
+        assert!(message(run(json!({"operation": "date"})).await).contains("Missing 'name'"));
+        assert!(message(run(json!({"operation": "next", "name": "Easter", "from": "next week"})).await).contains("expected YYYY-MM-DD"));
+        assert!(message(run(json!({"operation": "date", "name": "Easter", "year": 12})).await).contains("Invalid year"));
+        assert!(message(run(json!({"operation": "list", "region": "FR"})).await).contains("Unknown region 'FR'. Use: CA, GB, IE, US"));
+        assert!(message(run(json!({"operation": "when"})).await).contains("Invalid operation"));
+    }
+
+    #[test]
+    fn test_data_file_extends_and_overrides() {
+        let dir = std::env::temp_dir().join(format!("cvc_holidays_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("holidays.yaml");
+        std::fs::write(&path, r#"
+holidays:
+  - {name: Founders Day, aliases: [Founding Day], regions: [US], type: fixed, month: 3, day: 9}
+  - {name: Team Offsite, type: nth_weekday, month: 9, weekday: Wed, nth: -1}
+  - {name: Thanksgiving, regions: [US], type: weekday_on_or_after, month: 11, day: 22, weekday: Thu}
+"#).unwrap();
+        let calendar = HolidayCalendar::from_yaml_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(calendar.date_in("founding day", "US", 2026).unwrap(), date("2026-03-09"));
+        assert_eq!(calendar.date_in("Team Offsite", "GB", 2025).unwrap(), date("2025-09-24"));
+        assert_eq!(calendar.date_in("Thanksgiving", "US", 2025).unwrap(), date("2025-11-27"));
+        let thanksgivings = calendar.list("US", 2025).iter().filter(|(_, h)| h.name == "Thanksgiving").count();
+        assert_eq!(thanksgivings, 1);
+
+        std::fs::write(&path, "holidays:\n  - {name: Bad, type: nth_weekday, month: 9, weekday: Wed, nth: 0}\n").unwrap();
+        let err = HolidayCalendar::from_yaml_file(path.to_str().unwrap()).unwrap_err().to_string();
+        assert!(err.contains("Bad: nth must be"), "{}", err);
+        std::fs::write(&path, "holidays:\n  - {name: Bad, type: fixed, month: 2, day: 30}\n").unwrap();
+        let err = HolidayCalendar::from_yaml_file(path.to_str().unwrap()).unwrap_err().to_string();
+        assert!(err.contains("month 2 has no day 30"), "{}", err);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,14 +1,38 @@
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use crate::clock::{system_clock, Clock};
 use crate::error::AgentError;
-use crate::tools::calculator::CalculatorTool;
+use crate::tools::astronomy::AstronomyTool;
+use crate::tools::calculator::{CalculatorScope, CalculatorTool};
+use crate::tools::date_math::DateMathTool;
 use crate::tools::datetime::DatetimeTool;
+use crate::tools::finance::FinanceTool;
+use crate::tools::holidays::{HolidayCalendar, HolidaysTool};
+use crate::tools::statistics::StatisticsTool;
+use crate::tools::timezone::TimezoneTool;
+use crate::tools::units::{UnitTable, UnitsTool};
 use crate::types::ToolSpec;
 
+pub mod arithmetic;
+pub mod astronomy;
 pub mod calculator;
+pub mod date_math;
 pub mod datetime;
+pub mod finance;
+pub mod holidays;
+pub mod normalize;
+pub mod statistics;
+pub mod timezone;
+pub mod units;
+
+/// State that lasts for one agent run and is shared by that run's tool calls.
+#[derive(Debug, Default)]
+pub struct RunContext {
+    /// Calculator variables and earlier results
+    pub(crate) calculator: Mutex<CalculatorScope>,
+}
 
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -16,6 +40,12 @@ pub trait Tool: Send + Sync {
     fn description(&self) -> &'static str;
     fn json_schema(&self) -> serde_json::Value;
     async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError>;
+
+    /// Runs as part of the agent run owning `context`. Tools that keep state
+    /// between calls override this; the rest ignore the context.
+    async fn run_in(&self, args: HashMap<String, Value>, _context: &RunContext) -> Result<String, AgentError> {
+        self.run(args).await
+    }
 }
 
 #[derive(Clone)]
@@ -47,10 +77,20 @@ impl ToolRegistry {
         }).collect()
     }
 
+    /// Runs a tool on its own, outside any agent run.
     pub async fn execute_tool(
         &self,
         name: &str,
         args: HashMap<String, Value>,
+    ) -> Result<String, AgentError> {
+        self.execute_tool_in(name, args, &RunContext::default()).await
+    }
+
+    pub async fn execute_tool_in(
+        &self,
+        name: &str,
+        args: HashMap<String, Value>,
+        context: &RunContext,
     ) -> Result<String, AgentError> {
         let tool = self.tools.get(name).ok_or_else(|| {
             AgentError::ToolNotFoundError {
@@ -59,7 +99,7 @@ impl ToolRegistry {
             }
         })?;
         
-        tool.run(args).await.map_err(|e| match e {
+        tool.run_in(args, context).await.map_err(|e| match e {
             AgentError::ToolError { .. } => e,
             other => AgentError::ToolError {
                 tool_name: name.to_string(),
@@ -68,6 +108,19 @@ impl ToolRegistry {
         })
     }
 
+    /// A registry holding only `names`, which must all be registered here.
+    pub fn subset(&self, names: &[String]) -> Result<ToolRegistry, AgentError> {
+        let mut registry = ToolRegistry::new();
+        for name in names {
+            let tool = self.get(name).ok_or_else(|| AgentError::ToolNotFoundError {
+                tool_name: name.clone(),
+                available_tools: self.tool_names().join(", "),
+            })?;
+            registry.register(tool.clone());
+        }
+        Ok(registry)
+    }
+
     pub fn tool_names(&self) -> Vec<String> {
         self.tools.keys().map(|&k| k.to_string()).collect()
     }
@@ -77,11 +130,35 @@ impl ToolRegistry {
     }
 }
 
+impl Default for ToolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub fn create_default_registry() -> ToolRegistry {
+    create_registry_with_clock(system_clock())
+}
+
+/// Default tools, with every time-aware tool reading from `clock`.
+pub fn create_registry_with_clock(clock: Arc<dyn Clock>) -> ToolRegistry {
+    create_registry(clock, HolidayCalendar::builtin(), UnitTable::builtin())
+}
+
+/// Default tools reading from `clock`, with `holidays` and `units` behind the
+/// holidays and units tools.
+pub fn create_registry(clock: Arc<dyn Clock>, holidays: HolidayCalendar, units: UnitTable) -> ToolRegistry {
     let mut registry = ToolRegistry::new();
     registry
         .register(Arc::new(CalculatorTool::new()))
-        .register(Arc::new(DatetimeTool::new()));
+        .register(Arc::new(DatetimeTool::with_clock(clock.clone())))
+        .register(Arc::new(DateMathTool::new()))
+        .register(Arc::new(HolidaysTool::with_calendar(holidays, clock.clone())))
+        .register(Arc::new(AstronomyTool::with_clock(clock.clone())))
+        .register(Arc::new(TimezoneTool::with_clock(clock)))
+        .register(Arc::new(UnitsTool::with_table(units)))
+        .register(Arc::new(StatisticsTool::new()))
+        .register(Arc::new(FinanceTool::new()));
     registry
 }
 
@@ -101,8 +178,23 @@ mod tests {
     fn test_tool_specs_generation() {
         let registry = create_default_registry();
         let specs = registry.to_tool_specs();
-        assert_eq!(specs.len(), 2);
+        assert_eq!(specs.len(), 9);
         assert!(specs.iter().any(|s| s.name == "calculator"));
         assert!(specs.iter().any(|s| s.name == "datetime"));
+        assert!(specs.iter().any(|s| s.name == "date_math"));
+        assert!(specs.iter().any(|s| s.name == "holidays"));
+        assert!(specs.iter().any(|s| s.name == "astronomy"));
+        assert!(specs.iter().any(|s| s.name == "timezone"));
+        assert!(specs.iter().any(|s| s.name == "units"));
+        assert!(specs.iter().any(|s| s.name == "statistics"));
+        assert!(specs.iter().any(|s| s.name == "finance"));
+    }
+
+    #[test]
+    fn test_registry_subset() {
+        let registry = create_default_registry();
+        let subset = registry.subset(&["calculator".to_string()]).unwrap();
+        assert_eq!(subset.tool_names(), vec!["calculator"]);
+        assert!(registry.subset(&["abacus".to_string()]).is_err());
     }
 }
\ No newline at end of file
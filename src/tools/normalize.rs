@@ -0,0 +1,379 @@
+use crate::tools::arithmetic::{CONSTANTS, FUNCTIONS, MULTI_ARGUMENT_FUNCTIONS};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Number,
+    /// A calculator variable, or an earlier result such as `$1`
+    Variable,
+    Name,
+    Op,
+    Open,
+    Close,
+    Comma,
+    Other,
+}
+
+/// A token with the whitespace written before it, so rewriting keeps the
+/// model's spacing.
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    text: String,
+    space: String,
+}
+
+impl Token {
+    fn new(kind: Kind, text: &str) -> Self {
+        Token { kind, text: text.to_string(), space: String::new() }
+    }
+
+    fn is(&self, kind: Kind, text: &str) -> bool {
+        self.kind == kind && self.text == text
+    }
+
+    fn is_function(&self) -> bool {
+        self.kind == Kind::Name && FUNCTIONS.contains(&self.text.as_str())
+    }
+
+    fn is_constant(&self) -> bool {
+        self.kind == Kind::Name && CONSTANTS.contains(&self.text.as_str())
+    }
+
+    /// A number, constant or variable.
+    fn is_value(&self) -> bool {
+        matches!(self.kind, Kind::Number | Kind::Variable) || self.is_constant()
+    }
+
+    /// Can end an operand: `2`, `pi`, `)`, `3!`.
+    fn ends_operand(&self) -> bool {
+        self.is_value() || self.kind == Kind::Close || self.is(Kind::Op, "!")
+    }
+
+    /// Can start an operand that follows another without an operator.
+    fn starts_operand(&self) -> bool {
+        self.is_value() || self.kind == Kind::Open || self.is_function()
+    }
+}
+
+/// `SQRT`, `arcsin`, `π` and friends as the calculator's names.
+fn canonical_name(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let alias = match lower.as_str() {
+        "arcsin" => "asin",
+        "arccos" => "acos",
+        "arctan" => "atan",
+        "lg" => "log10",
+        "π" => "pi",
+        _ => &lower,
+    };
+    // `E` is left alone; it is more likely a typo for an exponent than Euler's number
+    if (FUNCTIONS.contains(&alias) || alias == "pi") && alias != name {
+        alias.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn lex(expression: &str, is_variable: &dyn Fn(&str) -> bool) -> Vec<Token> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    // whether each open parenthesis starts the arguments of a multi-argument function
+    let mut argument_lists: Vec<bool> = Vec::new();
+    let mut space = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            space.push(c);
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let (kind, text) = if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            let in_arguments = argument_lists.last().copied().unwrap_or(false);
+            let mut text = String::new();
+            while i < chars.len() {
+                if chars[i].is_ascii_digit() || chars[i] == '.' {
+                    text.push(chars[i]);
+                    i += 1;
+                } else if chars[i] == ',' && !in_arguments && is_thousands_separator(&chars, i) {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let digits_at = if matches!(chars.get(i + 1), Some('+' | '-')) { i + 2 } else { i + 1 };
+                if chars.get(digits_at).is_some_and(|d| d.is_ascii_digit()) {
+                    let mut end = digits_at;
+                    while end < chars.len() && chars[end].is_ascii_digit() {
+                        end += 1;
+                    }
+                    text.extend(&chars[i..end]);
+                    i = end;
+                }
+            }
+            (Kind::Number, text)
+        } else if c == '$' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()) {
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            (Kind::Variable, chars[start..i].iter().collect())
+        } else if c.is_alphabetic() || c == '_' {
+            let mut word_start = start;
+            loop {
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let word: String = chars[word_start..i].iter().collect();
+                let separator = match chars.get(i) {
+                    Some('.') => 1,
+                    Some(':') if chars.get(i + 1) == Some(&':') => 2,
+                    _ => 0,
+                };
+                // `math::sqrt` and `Math.sqrt` read as `sqrt`
+                if word.eq_ignore_ascii_case("math") && separator > 0 && chars.get(i + separator).is_some_and(|c| c.is_alphabetic()) {
+                    i += separator;
+                    word_start = i;
+                    continue;
+                }
+                if is_variable(&word) {
+                    break (Kind::Variable, word);
+                }
+                break (Kind::Name, canonical_name(&word));
+            }
+        } else {
+            i += 1;
+            match c {
+                '*' if chars.get(i) == Some(&'*') => {
+                    i += 1;
+                    (Kind::Op, "^".to_string())
+                }
+                '+' | '-' | '*' | '/' | '^' | '!' | '%' => (Kind::Op, c.to_string()),
+                '×' | '·' | '⋅' | '∗' => (Kind::Op, "*".to_string()),
+                '÷' | '∕' => (Kind::Op, "/".to_string()),
+                '−' | '–' => (Kind::Op, "-".to_string()),
+                '√' => (Kind::Op, "√".to_string()),
+                '(' | '[' => (Kind::Open, "(".to_string()),
+                ')' | ']' => (Kind::Close, ")".to_string()),
+                ',' => (Kind::Comma, ",".to_string()),
+                _ => (Kind::Other, c.to_string()),
+            }
+        };
+        match kind {
+            Kind::Open => {
+                let multi = tokens.last().is_some_and(|t| t.kind == Kind::Name && MULTI_ARGUMENT_FUNCTIONS.contains(&t.text.as_str()));
+                argument_lists.push(multi);
+            }
+            Kind::Close => {
+                argument_lists.pop();
+            }
+            _ => {}
+        }
+        tokens.push(Token { kind, text, space: std::mem::take(&mut space) });
+    }
+    tokens
+}
+
+/// `1,847` but not `1,84` or `1,8470`.
+fn is_thousands_separator(chars: &[char], comma: usize) -> bool {
+    let group = chars.get(comma + 1..comma + 4);
+    group.is_some_and(|g| g.iter().all(|c| c.is_ascii_digit()))
+        && !chars.get(comma + 4).is_some_and(|c| c.is_ascii_digit())
+}
+
+/// Index of the first token of the operand ending at `end`.
+fn operand_start(tokens: &[Token], end: usize) -> Option<usize> {
+    let last = tokens.get(end)?;
+    if last.is_value() {
+        return Some(end);
+    }
+    if last.kind != Kind::Close {
+        return None;
+    }
+    let mut depth = 0;
+    for i in (0..=end).rev() {
+        match tokens[i].kind {
+            Kind::Close => depth += 1,
+            Kind::Open => {
+                depth -= 1;
+                if depth == 0 {
+                    let call = i > 0 && tokens[i - 1].is_function();
+                    return Some(if call { i - 1 } else { i });
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `√2` and `√(x + 1)` as `sqrt(...)`.
+fn rewrite_roots(tokens: Vec<Token>) -> Vec<Token> {
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(token) = iter.next() {
+        if !token.is(Kind::Op, "√") {
+            out.push(token);
+            continue;
+        }
+        let sqrt = Token { kind: Kind::Name, text: "sqrt".to_string(), space: token.space };
+        out.push(sqrt);
+        if let Some(next) = iter.peek()
+            && next.is_value()
+        {
+            let mut operand = iter.next().unwrap_or_else(|| unreachable!());
+            operand.space.clear();
+            out.extend([Token::new(Kind::Open, "("), operand, Token::new(Kind::Close, ")")]);
+        }
+    }
+    out
+}
+
+/// `15%` as `(15/100)`, and `15% of 80` as a product. A `%` followed by an
+/// operand is more likely modulo, so it is left for the calculator to reject.
+fn rewrite_percentages(tokens: Vec<Token>) -> Vec<Token> {
+    let mut out: Vec<Token> = Vec::with_capacity(tokens.len());
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(token) = iter.next() {
+        let binary = iter.peek().is_some_and(|next| next.starts_operand());
+        let start = if token.is(Kind::Op, "%") && !binary && !out.is_empty() { operand_start(&out, out.len() - 1) } else { None };
+        let Some(start) = start else {
+            out.push(token);
+            continue;
+        };
+        let mut tail = out.split_off(start);
+        let space = std::mem::take(&mut tail[0].space);
+        out.push(Token { kind: Kind::Open, text: "(".to_string(), space });
+        out.extend(tail);
+        out.extend([Token::new(Kind::Op, "/"), Token::new(Kind::Number, "100"), Token::new(Kind::Close, ")")]);
+        if let Some(next) = iter.peek_mut()
+            && next.kind == Kind::Name
+            && next.text.eq_ignore_ascii_case("of")
+        {
+            next.kind = Kind::Op;
+            next.text = "*".to_string();
+        }
+    }
+    out
+}
+
+/// `3 x 4` as `3 * 4`.
+fn rewrite_times(tokens: &mut [Token]) {
+    for i in 1..tokens.len().saturating_sub(1) {
+        if tokens[i].kind == Kind::Name
+            && (tokens[i].text == "x" || tokens[i].text == "X")
+            && tokens[i - 1].ends_operand()
+            && tokens[i + 1].starts_operand()
+        {
+            tokens[i].kind = Kind::Op;
+            tokens[i].text = "*".to_string();
+        }
+    }
+}
+
+/// `2(3 + 4)`, `(1 + 2)(3 + 4)`, `2pi` and `3sqrt(2)` as products.
+fn insert_multiplication(tokens: Vec<Token>) -> Vec<Token> {
+    let mut out: Vec<Token> = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let implicit = out.last().is_some_and(|prev| {
+            // `2 3` and `x y` are more likely a missing operator than a product
+            let spaced_values = prev.is_value() && token.is_value() && !token.space.is_empty();
+            prev.ends_operand() && token.starts_operand() && !spaced_values
+        });
+        if implicit {
+            out.push(Token::new(Kind::Op, "*"));
+        }
+        out.push(token);
+    }
+    out
+}
+
+/// Rewrites the ways models write math into the calculator's syntax:
+/// thousands separators, `×`/`÷`/`−`, `%`, implicit multiplication, `**`,
+/// qualified or aliased function names and `π`. Anything it doesn't
+/// recognize is passed through for the calculator to report.
+pub fn normalize(expression: &str) -> String {
+    normalize_with(expression, |_| false)
+}
+
+/// Like [`normalize`], treating the names `is_variable` accepts as values,
+/// so `2x` is a product and `x` in `3 x 4` isn't read as times.
+pub fn normalize_with(expression: &str, is_variable: impl Fn(&str) -> bool) -> String {
+    let tokens = rewrite_roots(lex(expression.trim(), &is_variable));
+    let mut tokens = rewrite_percentages(tokens);
+    rewrite_times(&mut tokens);
+    insert_multiplication(tokens)
+        .iter()
+        .map(|t| format!("{}{}", t.space, t.text))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thousands_separators() {
+        assert_eq!(normalize("1,847 * 923"), "1847 * 923");
+        assert_eq!(normalize("1,000,000 / 3"), "1000000 / 3");
+        assert_eq!(normalize("max(1,847, 2)"), "max(1,847, 2)");
+        assert_eq!(normalize("log(1000,10)"), "log(1000,10)");
+        assert_eq!(normalize("sqrt(1,000,000)"), "sqrt(1000000)");
+    }
+
+    #[test]
+    fn test_operator_symbols() {
+        assert_eq!(normalize("12 × 4 ÷ 3 − 1"), "12 * 4 / 3 - 1");
+        assert_eq!(normalize("2**10"), "2^10");
+        assert_eq!(normalize("3 x 4"), "3 * 4");
+        assert_eq!(normalize("[1 + 2] * 3"), "(1 + 2) * 3");
+    }
+
+    #[test]
+    fn test_percentages() {
+        assert_eq!(normalize("23% * 2847"), "(23/100) * 2847");
+        assert_eq!(normalize("23% of 2,847"), "(23/100) * 2847");
+        assert_eq!(normalize("200 * (1 + 4.2%)^7"), "200 * (1 + (4.2/100))^7");
+        assert_eq!(normalize("(1 + 2)%"), "((1 + 2)/100)");
+        // modulo, not a percentage
+        assert_eq!(normalize("17 % 5"), "17 % 5");
+    }
+
+    #[test]
+    fn test_implicit_multiplication() {
+        assert_eq!(normalize("2(3+4)"), "2*(3+4)");
+        assert_eq!(normalize("(1 + 2)(3 + 4)"), "(1 + 2)*(3 + 4)");
+        assert_eq!(normalize("2pi * 3"), "2*pi * 3");
+        assert_eq!(normalize("3sqrt(2)"), "3*sqrt(2)");
+        assert_eq!(normalize("2 3"), "2 3");
+        assert_eq!(normalize("1.5e3 + 2e"), "1.5e3 + 2*e");
+    }
+
+    #[test]
+    fn test_function_names() {
+        assert_eq!(normalize("math::sqrt(16) + Math.PI"), "sqrt(16) + pi");
+        assert_eq!(normalize("SQRT(2) * π"), "sqrt(2) * pi");
+        assert_eq!(normalize("√2 + √(9)"), "sqrt(2) + sqrt(9)");
+        assert_eq!(normalize("arctan(1) + lg(100)"), "atan(1) + log10(100)");
+        assert_eq!(normalize("ln(e) + sin(0)"), "ln(e) + sin(0)");
+    }
+
+    #[test]
+    fn test_variables() {
+        let is_variable = |name: &str| ["x", "total", "ans"].contains(&name);
+        assert_eq!(normalize_with("2x + 3(total)", is_variable), "2*x + 3*(total)");
+        assert_eq!(normalize_with("x x 2", is_variable), "x x 2");
+        assert_eq!(normalize_with("ans * 15%", is_variable), "ans * (15/100)");
+        assert_eq!(normalize_with("$1 × 2$2", is_variable), "$1 * 2*$2");
+        assert_eq!(normalize_with("total%", is_variable), "(total/100)");
+    }
+
+    #[test]
+    fn test_leaves_calculator_syntax_alone() {
+        for expression in ["2 + 2", "15 * 7 + 23", "4561 / 7", "52! / (5! * 47!)", "-2^2", "delete * from users"] {
+            assert_eq!(normalize(expression), expression);
+        }
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use crate::error::AgentError;
+use crate::tools::units::format_number;
+use crate::tools::Tool;
+
+const MEASURES: &[&str] = &[
+    "count", "sum", "mean", "median", "mode", "min", "max", "range",
+    "variance", "population_variance", "std_dev", "population_std_dev",
+];
+
+/// Neumaier's compensated sum, so large inputs don't drift. A sum beyond the
+/// f64 range is infinite (the correction term would make it NaN).
+pub fn sum(values: &[f64]) -> f64 {
+    let mut total = 0.0;
+    let mut compensation = 0.0;
+    for &value in values {
+        let next = total + value;
+        if !next.is_finite() {
+            return next;
+        }
+        compensation += if total.abs() >= value.abs() { (total - next) + value } else { (value - next) + total };
+        total = next;
+    }
+    total + compensation
+}
+
+/// Count, mean and sum of squared deviations in one pass (Welford).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Moments {
+    pub count: usize,
+    pub mean: f64,
+    squared_deviations: f64,
+}
+
+impl Moments {
+    pub fn of(values: &[f64]) -> Self {
+        let mut moments = Self::default();
+        for &value in values {
+            moments.count += 1;
+            let delta = value - moments.mean;
+            moments.mean += delta / moments.count as f64;
+            moments.squared_deviations += delta * (value - moments.mean);
+        }
+        moments
+    }
+
+    /// Divides by n - 1 when `sample`, by n otherwise; `None` without enough values.
+    pub fn variance(&self, sample: bool) -> Option<f64> {
+        let divisor = if sample { self.count.checked_sub(1)? } else { self.count };
+        (divisor > 0).then(|| self.squared_deviations / divisor as f64)
+    }
+
+    pub fn std_dev(&self, sample: bool) -> Option<f64> {
+        self.variance(sample).map(f64::sqrt)
+    }
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// Linear interpolation between closest ranks (Excel's PERCENTILE.INC,
+/// NumPy's default); `percent` is 0 to 100.
+pub fn percentile(sorted: &[f64], percent: f64) -> Option<f64> {
+    if sorted.is_empty() || !(0.0..=100.0).contains(&percent) {
+        return None;
+    }
+    let rank = percent / 100.0 * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64))
+}
+
+/// Every most frequent value, or nothing when no value repeats.
+pub fn modes(sorted: &[f64]) -> Vec<f64> {
+    let mut runs: Vec<(f64, usize)> = Vec::new();
+    for &value in sorted {
+        match runs.last_mut() {
+            Some((last, count)) if *last == value => *count += 1,
+            _ => runs.push((value, 1)),
+        }
+    }
+    let most = runs.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    if most < 2 {
+        return Vec::new();
+    }
+    runs.into_iter().filter(|(_, count)| *count == most).map(|(value, _)| value).collect()
+}
+
+/// Least-squares line and Pearson correlation of paired values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regression {
+    pub slope: f64,
+    pub intercept: f64,
+    /// `None` when every y is the same
+    pub r: Option<f64>,
+}
+
+pub fn regression(x: &[f64], y: &[f64]) -> Result<Regression, String> {
+    if x.len() != y.len() {
+        return Err(format!("'x' and 'y' must be the same length (got {} and {})", x.len(), y.len()));
+    }
+    if x.len() < 2 {
+        return Err("Regression needs at least 2 pairs".to_string());
+    }
+    // one pass of Welford's updates over both series and their co-moment
+    let (mut mean_x, mut mean_y, mut m2_x, mut m2_y, mut co_moment) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for (i, (&xi, &yi)) in x.iter().zip(y).enumerate() {
+        let n = (i + 1) as f64;
+        let dx = xi - mean_x;
+        let dy = yi - mean_y;
+        mean_x += dx / n;
+        mean_y += dy / n;
+        m2_x += dx * (xi - mean_x);
+        m2_y += dy * (yi - mean_y);
+        co_moment += dx * (yi - mean_y);
+    }
+    if m2_x == 0.0 {
+        return Err("Every x is the same, so there is no line through the points".to_string());
+    }
+    let slope = co_moment / m2_x;
+    let r = (m2_y != 0.0).then(|| (co_moment / (m2_x * m2_y).sqrt()).clamp(-1.0, 1.0));
+    Ok(Regression { slope, intercept: mean_y - slope * mean_x, r })
+}
+
+pub struct StatisticsTool;
+
+impl StatisticsTool {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn numbers_arg(&self, args: &HashMap<String, Value>, key: &str) -> Result<Vec<f64>, AgentError> {
+        let items = args.get(key)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| self.error(format!("Missing '{}' parameter (an array of numbers)", key)))?;
+        if items.is_empty() {
+            return Err(self.error(format!("'{}' is empty", key)));
+        }
+        items.iter().enumerate().map(|(i, item)| {
+            item.as_f64().ok_or_else(|| self.error(format!("{}[{}] is not a number (got {})", key, i, item)))
+        }).collect()
+    }
+
+    fn strings_arg<'a>(&self, args: &'a HashMap<String, Value>, key: &str) -> Result<Option<Vec<&'a str>>, AgentError> {
+        match args.get(key) {
+            None => Ok(None),
+            Some(value) => value.as_array()
+                .and_then(|items| items.iter().map(|v| v.as_str()).collect())
+                .map(Some)
+                .ok_or_else(|| self.error(format!("'{}' must be an array of strings", key))),
+        }
+    }
+
+    fn describe(&self, values: &[f64], measures: &[&str]) -> Result<String, AgentError> {
+        let moments = Moments::of(values);
+        let sorted = sorted(values);
+        let (min, max) = (sorted[0], sorted[sorted.len() - 1]);
+        let number = |measure: &str, value: f64| if value.is_finite() {
+            Ok(format_number(value))
+        } else {
+            Err(self.error(format!("The {} is too large to represent (beyond ±1.8e308)", measure)))
+        };
+        let spread = |measure: &str, value: Option<f64>| value.map_or_else(|| Ok("undefined (needs at least 2 values)".to_string()), |v| number(measure, v));
+
+        let mut lines = Vec::new();
+        for &measure in measures {
+            let value = match measure {
+                "count" => moments.count.to_string(),
+                "sum" => number(measure, sum(values))?,
+                "mean" => number(measure, moments.mean)?,
+                "median" => format_number(percentile(&sorted, 50.0).unwrap_or_default()),
+                "mode" => {
+                    let modes = modes(&sorted);
+                    if modes.is_empty() {
+                        "none (no value appears more than once)".to_string()
+                    } else {
+                        modes.into_iter().map(format_number).collect::<Vec<_>>().join(", ")
+                    }
+                }
+                "min" => format_number(min),
+                "max" => format_number(max),
+                "range" => number(measure, max - min)?,
+                "variance" => spread(measure, moments.variance(true))?,
+                "population_variance" => spread(measure, moments.variance(false))?,
+                "std_dev" => spread(measure, moments.std_dev(true))?,
+                "population_std_dev" => spread(measure, moments.std_dev(false))?,
+                _ => return Err(self.error(format!("Unknown measure '{}'. Use: {}", measure, MEASURES.join(", ")))),
+            };
+            lines.push(format!("{}: {}", measure, value));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+impl Default for StatisticsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for StatisticsTool {
+    fn name(&self) -> &'static str {
+        "statistics"
+    }
+
+    fn description(&self) -> &'static str {
+        "Statistics of a list of numbers: count, sum, mean, median, mode, min, max, range, sample and population variance and standard deviation, percentiles and z-scores; linear regression and correlation of paired lists. Args: { operation: string, values?: number[], measures?: string[], percentiles?: number[], value?: number, x?: number[], y?: number[], predict?: number }"
+    }
+
+    fn json_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["describe", "percentiles", "z_scores", "regression", "correlation"],
+                    "description": "describe: summary measures of values; percentiles: values at the given percentiles; z_scores: standard scores (population standard deviation); regression/correlation: least-squares line and Pearson r of x and y"
+                },
+                "values": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "description": "The numbers, for describe, percentiles and z_scores"
+                },
+                "measures": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": MEASURES },
+                    "description": "Measures for describe (default: all). variance and std_dev are the sample (n - 1) versions"
+                },
+                "percentiles": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "description": "Percentiles from 0 to 100, interpolated between closest ranks (default: 25, 50, 75)"
+                },
+                "value": {
+                    "type": "number",
+                    "description": "For z_scores: score only this value instead of every value"
+                },
+                "x": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "description": "Independent values, paired with y"
+                },
+                "y": {
+                    "type": "array",
+                    "items": { "type": "number" },
+                    "description": "Dependent values, paired with x"
+                },
+                "predict": {
+                    "type": "number",
+                    "description": "For regression: an x to predict y at"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
+        let operation = args.get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.error("Missing 'operation' parameter"))?;
+
+        match operation {
+            "describe" => {
+                let values = self.numbers_arg(&args, "values")?;
+                let measures = self.strings_arg(&args, "measures")?.unwrap_or_else(|| MEASURES.to_vec());
+                self.describe(&values, &measures)
+            }
+            "percentiles" => {
+                let values = sorted(&self.numbers_arg(&args, "values")?);
+                let percents = match args.get("percentiles") {
+                    Some(_) => self.numbers_arg(&args, "percentiles")?,
+                    None => vec![25.0, 50.0, 75.0],
+                };
+                let lines: Result<Vec<String>, AgentError> = percents.iter().map(|&p| {
+                    percentile(&values, p)
+                        .map(|value| format!("p{}: {}", format_number(p), format_number(value)))
+                        .ok_or_else(|| self.error(format!("Percentile {} is outside 0 to 100", format_number(p))))
+                }).collect();
+                Ok(lines?.join("\n"))
+            }
+            "z_scores" => {
+                let values = self.numbers_arg(&args, "values")?;
+                let moments = Moments::of(&values);
+                let std_dev = moments.std_dev(false).filter(|s| *s > 0.0)
+                    .ok_or_else(|| self.error("Every value is the same, so z-scores are undefined"))?;
+                let z = |value: f64| format_number((value - moments.mean) / std_dev);
+                let header = format!("mean {}, population std_dev {}", format_number(moments.mean), format_number(std_dev));
+                Ok(match args.get("value") {
+                    Some(value) => {
+                        let value = value.as_f64().ok_or_else(|| self.error(format!("'value' must be a number (got {})", value)))?;
+                        format!("z({}) = {} ({})", format_number(value), z(value), header)
+                    }
+                    None => {
+                        let scores: Vec<String> = values.iter().map(|&v| format!("{}: {}", format_number(v), z(v))).collect();
+                        format!("{}\n{}", header, scores.join("\n"))
+                    }
+                })
+            }
+            "regression" | "correlation" => {
+                let x = self.numbers_arg(&args, "x")?;
+                let y = self.numbers_arg(&args, "y")?;
+                let fit = regression(&x, &y).map_err(|e| self.error(e))?;
+                if operation == "correlation" {
+                    let r = fit.r.ok_or_else(|| self.error("Every y is the same, so the correlation is undefined"))?;
+                    return Ok(format!("r: {}\nr_squared: {}\nn: {}", format_number(r), format_number(r * r), x.len()));
+                }
+                let mut lines = vec![
+                    format!("slope: {}", format_number(fit.slope)),
+                    format!("intercept: {}", format_number(fit.intercept)),
+                    format!("equation: y = {} * x + {}", format_number(fit.slope), format_number(fit.intercept)),
+                ];
+                if let Some(r) = fit.r {
+                    lines.push(format!("r: {}", format_number(r)));
+                    lines.push(format!("r_squared: {}", format_number(r * r)));
+                }
+                lines.push(format!("n: {}", x.len()));
+                if let Some(at) = args.get("predict") {
+                    let at = at.as_f64().ok_or_else(|| self.error(format!("'predict' must be a number (got {})", at)))?;
+                    lines.push(format!("prediction at x = {}: {}", format_number(at), format_number(fit.slope * at + fit.intercept)));
+                }
+                Ok(lines.join("\n"))
+            }
+            _ => Err(self.error(format!(
+                "Invalid operation '{}'. Use: describe, percentiles, z_scores, regression, or correlation", operation
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_moments() {
+        // a large offset ruins the naive sum-of-squares formula but not Welford's
+        let values: Vec<f64> = [4.0, 7.0, 13.0, 16.0].iter().map(|v| v + 1e9).collect();
+        let moments = Moments::of(&values);
+        assert_eq!(moments.mean, 1e9 + 10.0);
+        assert_eq!(moments.variance(true), Some(30.0));
+        assert_eq!(moments.variance(false), Some(22.5));
+        assert_eq!(Moments::of(&[5.0]).variance(true), None);
+
+        assert_eq!(sum(&[1e16, 1.0, -1e16]), 1.0);
+        assert_eq!(sum(&[0.1; 10]), 1.0);
+        assert_eq!(sum(&[1e308, 1e308]), f64::INFINITY);
+        assert_eq!(sum(&[-1e308, -1e308, 1.0]), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_percentiles_and_modes() {
+        let values = sorted(&[15.0, 20.0, 35.0, 40.0, 50.0]);
+        assert_eq!(percentile(&values, 50.0), Some(35.0));
+        assert_eq!(percentile(&values, 40.0), Some(29.0));
+        assert_eq!(percentile(&values, 100.0), Some(50.0));
+        assert_eq!(percentile(&values, 101.0), None);
+        assert_eq!(percentile(&sorted(&[1.0, 2.0, 3.0, 4.0]), 50.0), Some(2.5));
+
+        assert_eq!(modes(&sorted(&[3.0, 1.0, 3.0, 2.0, 1.0])), vec![1.0, 3.0]);
+        assert!(modes(&[1.0, 2.0, 3.0]).is_empty());
+    }
+
+    #[test]
+    fn test_regression() {
+        let fit = regression(&[1.0, 2.0, 3.0, 4.0, 5.0], &[3.0, 5.0, 7.0, 9.0, 11.0]).unwrap();
+        assert_eq!((fit.slope, fit.intercept, fit.r), (2.0, 1.0, Some(1.0)));
+
+        let fit = regression(&[1.0, 2.0, 3.0, 4.0, 5.0], &[2.0, 4.0, 5.0, 4.0, 5.0]).unwrap();
+        assert!((fit.slope - 0.6).abs() < 1e-12);
+        assert!((fit.intercept - 2.2).abs() < 1e-12);
+        assert!((fit.r.unwrap() - 0.7745966692).abs() < 1e-9);
+
+        assert!(regression(&[1.0, 2.0], &[1.0]).unwrap_err().contains("same length"));
+        assert!(regression(&[2.0, 2.0], &[1.0, 3.0]).unwrap_err().contains("Every x is the same"));
+        assert_eq!(regression(&[1.0, 2.0], &[4.0, 4.0]).unwrap().r, None);
+    }
+
+    #[tokio::test]
+    async fn test_tool_operations() {
+        let tool = StatisticsTool::new();
+        let run = |args: Value| tool.run(serde_json::from_value(args).unwrap());
+
+        let summary = run(json!({"operation": "describe", "values": [2, 4, 4, 4, 5, 5, 7, 9]})).await.unwrap();
+        assert_eq!(summary, [
+            "count: 8", "sum: 40", "mean: 5", "median: 4.5", "mode: 4", "min: 2", "max: 9", "range: 7",
+            "variance: 4.571428571", "population_variance: 4", "std_dev: 2.138089935", "population_std_dev: 2",
+        ].join("\n"));
+        let some = run(json!({"operation": "describe", "values": [7], "measures": ["mean", "std_dev"]})).await.unwrap();
+        assert_eq!(some, "mean: 7\nstd_dev: undefined (needs at least 2 values)");
+        let huge = run(json!({"operation": "describe", "values": [1e308, 1e308], "measures": ["max", "sum"]})).await.unwrap_err().to_string();
+        assert!(huge.contains("The sum is too large to represent"), "{}", huge);
+        let mean = run(json!({"operation": "describe", "values": [1e308, 1e308], "measures": ["mean"]})).await.unwrap();
+        assert_eq!(mean, "mean: 1e308");
+
+        let quartiles = run(json!({"operation": "percentiles", "values": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]})).await.unwrap();
+        assert_eq!(quartiles, "p25: 3.25\np50: 5.5\np75: 7.75");
+
+        let z = run(json!({"operation": "z_scores", "values": [2, 4, 4, 4, 5, 5, 7, 9], "value": 9})).await.unwrap();
+        assert_eq!(z, "z(9) = 2 (mean 5, population std_dev 2)");
+
+        let fit = run(json!({"operation": "regression", "x": [1, 2, 3], "y": [2, 4, 6], "predict": 10})).await.unwrap();
+        assert!(fit.contains("equation: y = 2 * x + 0") && fit.ends_with("prediction at x = 10: 20"), "{}", fit);
+        let r = run(json!({"operation": "correlation", "x": [1, 2, 3], "y": [3, 2, 1]})).await.unwrap();
+        assert_eq!(r, "r: -1\nr_squared: 1\nn: 3");
+
+        let error = |result: Result<String, AgentError>| result.unwrap_err().to_string();
+        assert!(error(run(json!({"operation": "describe", "values": [1, "two"]})).await).contains("values[1] is not a number"));
+        assert!(error(run(json!({"operation": "describe", "values": []})).await).contains("'values' is empty"));
+        assert!(error(run(json!({"operation": "describe", "values": [1], "measures": ["average"]})).await).contains("Unknown measure 'average'"));
+        assert!(error(run(json!({"operation": "z_scores", "values": [3, 3]})).await).contains("z-scores are undefined"));
+        assert!(error(run(json!({"operation": "histogram", "values": [3]})).await).contains("Invalid operation"));
+    }
+}
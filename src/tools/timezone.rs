@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::{OffsetComponents, Tz, TZ_VARIANTS};
+use serde_json::{json, Value};
+use crate::clock::{system_clock, Clock};
+use crate::error::AgentError;
+use crate::tools::date_math::parse_date;
+use crate::tools::Tool;
+
+/// Time zone conversions on the IANA database compiled into the binary, so
+/// answers don't depend on the host's zone or files.
+pub struct TimezoneTool {
+    clock: Arc<dyn Clock>,
+}
+
+impl TimezoneTool {
+    pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self { clock }
+    }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn zone_arg(&self, args: &HashMap<String, Value>, key: &str, default: Option<Tz>) -> Result<Tz, AgentError> {
+        match args.get(key).and_then(|v| v.as_str()) {
+            Some(name) => resolve_zone(name).map_err(|e| self.error(e)),
+            None => default.ok_or_else(|| self.error(format!("Missing '{}' parameter (an IANA zone such as America/Chicago)", key))),
+        }
+    }
+
+    /// `time` read as a wall-clock time in `zone`; a bare time of day is today
+    /// there. The flag is set when the time happens twice.
+    fn local_time_arg(&self, args: &HashMap<String, Value>, zone: Tz) -> Result<Option<(DateTime<Tz>, bool)>, AgentError> {
+        let Some(text) = args.get("time").and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        let today = self.clock.now().with_timezone(&zone).date_naive();
+        let local = parse_local_time(text, today).map_err(|e| self.error(e))?;
+        match zone.from_local_datetime(&local) {
+            LocalResult::Single(at) => Ok(Some((at, false))),
+            LocalResult::Ambiguous(earlier, _) => Ok(Some((earlier, true))),
+            LocalResult::None => Err(self.error(format!(
+                "{} does not exist in {}: clocks skip over it when daylight saving time starts",
+                local.format("%Y-%m-%d %H:%M"), zone.name()
+            ))),
+        }
+    }
+}
+
+impl Default for TimezoneTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An IANA name (any case), or just its city, e.g. "tokyo" or "New York".
+pub fn resolve_zone(name: &str) -> Result<Tz, String> {
+    let name = name.trim();
+    if let Ok(zone) = Tz::from_str(name) {
+        return Ok(zone);
+    }
+    let city = name.replace(' ', "_");
+    TZ_VARIANTS.iter()
+        .find(|zone| zone.name().eq_ignore_ascii_case(name))
+        .or_else(|| TZ_VARIANTS.iter().find(|zone| {
+            zone.name().rsplit('/').next().is_some_and(|last| last.eq_ignore_ascii_case(&city))
+        }))
+        .copied()
+        .ok_or_else(|| format!("Unknown time zone '{}': use an IANA name such as America/Chicago or Asia/Tokyo", name))
+}
+
+/// `YYYY-MM-DD HH:MM[:SS]` (or with a `T`), or a time of day on `today`:
+/// `HH:MM[:SS]`, `9am`, `9:30 pm`.
+fn parse_local_time(text: &str, today: NaiveDate) -> Result<NaiveDateTime, String> {
+    let invalid = || format!("Invalid time '{}': expected YYYY-MM-DD HH:MM, HH:MM or a time like 9am", text);
+    let trimmed = text.trim();
+    if let Some((date, time)) = trimmed.split_once(['T', ' ']).filter(|(date, _)| date.len() == 10 && date.contains('-')) {
+        let date = parse_date(date)?;
+        return parse_time_of_day(time).map(|t| date.and_time(t)).ok_or_else(invalid);
+    }
+    parse_time_of_day(trimmed).map(|t| today.and_time(t)).ok_or_else(invalid)
+}
+
+fn parse_time_of_day(text: &str) -> Option<NaiveTime> {
+    let lower = text.trim().to_lowercase();
+    let (clock, meridiem) = match lower.strip_suffix("am").or_else(|| lower.strip_suffix("a.m.")) {
+        Some(rest) => (rest.trim(), Some(0)),
+        None => match lower.strip_suffix("pm").or_else(|| lower.strip_suffix("p.m.")) {
+            Some(rest) => (rest.trim(), Some(12)),
+            None => (lower.as_str(), None),
+        },
+    };
+    let parts: Vec<&str> = clock.split(':').collect();
+    let number = |part: &str| (!part.is_empty() && part.len() <= 2 && part.bytes().all(|b| b.is_ascii_digit()))
+        .then(|| part.parse::<u32>().ok())
+        .flatten();
+    let (hour, minute, second) = match (parts.as_slice(), meridiem) {
+        ([hour], Some(_)) => (number(hour)?, 0, 0),
+        ([hour, minute], _) if minute.len() == 2 => (number(hour)?, number(minute)?, 0),
+        ([hour, minute, second], _) if minute.len() == 2 && second.len() == 2 => (number(hour)?, number(minute)?, number(second)?),
+        _ => return None,
+    };
+    let hour = match meridiem {
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, second)
+}
+
+/// `2025-08-22 09:00 CDT (UTC-05:00)`
+fn show(at: DateTime<Tz>) -> String {
+    at.format("%Y-%m-%d %H:%M %Z (UTC%:z)").to_string()
+}
+
+fn show_offset(offset: chrono::Duration) -> String {
+    let minutes = offset.num_minutes();
+    let sign = if minutes < 0 { '-' } else { '+' };
+    format!("UTC{}{:02}:{:02}", sign, minutes.abs() / 60, minutes.abs() % 60)
+}
+
+#[async_trait]
+impl Tool for TimezoneTool {
+    fn name(&self) -> &'static str {
+        "timezone"
+    }
+
+    fn description(&self) -> &'static str {
+        "Time zones (IANA database, offline): current time in a zone, converting a time between zones, UTC offset and daylight saving status. Args: { operation: string, timezone?: string, time?: string, from?: string, to?: string }"
+    }
+
+    fn json_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "description": "'now' (current time in timezone), 'convert' (time in zone 'from' to zone 'to') or 'offset' (UTC offset and DST in timezone, at time or now)",
+                    "enum": ["now", "convert", "offset"]
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA zone for 'now' and 'offset', e.g. 'Asia/Tokyo'; a city such as 'Chicago' also works"
+                },
+                "time": {
+                    "type": "string",
+                    "description": "Wall-clock time in the source zone: 'YYYY-MM-DD HH:MM', or 'HH:MM' / '9am' for today there. Required for 'convert'"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Source zone for 'convert'",
+                    "default": "UTC"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Target zone for 'convert'"
+                }
+            },
+            "required": ["operation"]
+        })
+    }
+
+    async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
+        let operation = args.get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| self.error("Missing 'operation' parameter"))?;
+
+        match operation {
+            "now" => {
+                let zone = self.zone_arg(&args, "timezone", None)?;
+                let now = self.clock.now().with_timezone(&zone);
+                Ok(format!("{}, {} in {}", show(now), now.format("%A"), zone.name()))
+            }
+            "convert" => {
+                let from = self.zone_arg(&args, "from", Some(Tz::UTC))?;
+                let to = self.zone_arg(&args, "to", None)?;
+                let (at, ambiguous) = self.local_time_arg(&args, from)?
+                    .ok_or_else(|| self.error("Missing 'time' parameter"))?;
+                let mut answer = format!("{} in {} is {} in {}", show(at), from.name(), show(at.with_timezone(&to)), to.name());
+                if ambiguous {
+                    answer.push_str(&format!(" ({} happens twice as clocks fall back; using the first)", at.format("%H:%M")));
+                }
+                Ok(answer)
+            }
+            "offset" => {
+                let zone = self.zone_arg(&args, "timezone", None)?;
+                let at = match self.local_time_arg(&args, zone)? {
+                    Some((at, _)) => at,
+                    None => self.clock.now().with_timezone(&zone),
+                };
+                let offset = at.offset();
+                let dst = offset.dst_offset();
+                let status = if dst.is_zero() {
+                    "standard time".to_string()
+                } else {
+                    format!("daylight saving time (+{} min over standard {})", dst.num_minutes(), show_offset(offset.base_utc_offset()))
+                };
+                Ok(format!("{} at {}: {}, {}", zone.name(), show(at), show_offset(offset.base_utc_offset() + dst), status))
+            }
+            _ => Err(self.error(format!("Invalid operation '{}'. Use: now, convert, or offset", operation))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{parse_instant, FixedClock};
+
+    fn tool() -> TimezoneTool {
+        TimezoneTool::with_clock(Arc::new(FixedClock::new(parse_instant("2025-08-22T12:00:00Z").unwrap())))
+    }
+
+    async fn run(args: Value) -> Result<String, AgentError> {
+        tool().run(serde_json::from_value(args).unwrap()).await
+    }
+
+    #[test]
+    fn test_resolve_zone() {
+        assert_eq!(resolve_zone("Asia/Tokyo"), Ok(Tz::Asia__Tokyo));
+        assert_eq!(resolve_zone("america/chicago"), Ok(Tz::America__Chicago));
+        assert_eq!(resolve_zone("New York"), Ok(Tz::America__New_York));
+        assert_eq!(resolve_zone("tokyo"), Ok(Tz::Asia__Tokyo));
+        assert_eq!(resolve_zone("UTC"), Ok(Tz::UTC));
+        assert!(resolve_zone("Mars/Olympus").unwrap_err().contains("Unknown time zone 'Mars/Olympus'"));
+    }
+
+    #[test]
+    fn test_parse_local_time() {
+        let today = parse_date("2025-08-22").unwrap();
+        let at = |text| parse_local_time(text, today).map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+        assert_eq!(at("09:00").unwrap(), "2025-08-22 09:00:00");
+        assert_eq!(at("9am").unwrap(), "2025-08-22 09:00:00");
+        assert_eq!(at("12am").unwrap(), "2025-08-22 00:00:00");
+        assert_eq!(at("9:30 PM").unwrap(), "2025-08-22 21:30:00");
+        assert_eq!(at("2025-03-09T01:59:30").unwrap(), "2025-03-09 01:59:30");
+        assert_eq!(at("2025-12-31 23:15").unwrap(), "2025-12-31 23:15:00");
+        for bad in ["25:00", "13pm", "9", "9.30", "noon", "2025-13-01 10:00"] {
+            assert!(at(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_now_in_zone() {
+        let now = run(json!({"operation": "now", "timezone": "America/Chicago"})).await.unwrap();
+        assert_eq!(now, "2025-08-22 07:00 CDT (UTC-05:00), Friday in America/Chicago");
+    }
+
+    #[tokio::test]
+    async fn test_convert() {
+        let tokyo = run(json!({"operation": "convert", "time": "9am", "from": "America/Chicago", "to": "Asia/Tokyo"})).await.unwrap();
+        assert_eq!(tokyo, "2025-08-22 09:00 CDT (UTC-05:00) in America/Chicago is 2025-08-22 23:00 JST (UTC+09:00) in Asia/Tokyo");
+        // Chicago is on standard time in January
+        let winter = run(json!({"operation": "convert", "time": "2026-01-15 09:00", "from": "Chicago", "to": "Asia/Kolkata"})).await.unwrap();
+        assert!(winter.ends_with("is 2026-01-15 20:30 IST (UTC+05:30) in Asia/Kolkata"), "{}", winter);
+        let utc = run(json!({"operation": "convert", "time": "2025-08-22 23:30", "to": "Europe/London"})).await.unwrap();
+        assert!(utc.ends_with("is 2025-08-23 00:30 BST (UTC+01:00) in Europe/London"), "{}", utc);
+    }
+
+    #[tokio::test]
+    async fn test_dst_transitions() {
+        let gap = run(json!({"operation": "convert", "time": "2025-03-09 02:30", "from": "America/Chicago", "to": "UTC"})).await;
+        assert!(gap.unwrap_err().to_string().contains("does not exist in America/Chicago"));
+        let twice = run(json!({"operation": "convert", "time": "2025-11-02 01:30", "from": "America/Chicago", "to": "UTC"})).await.unwrap();
+        assert!(twice.contains("is 2025-11-02 06:30 UTC (UTC+00:00)") && twice.ends_with("happens twice as clocks fall back; using the first)"), "{}", twice);
+    }
+
+    #[tokio::test]
+    async fn test_offset_and_dst() {
+        let summer = run(json!({"operation": "offset", "timezone": "Europe/London"})).await.unwrap();
+        assert_eq!(summer, "Europe/London at 2025-08-22 13:00 BST (UTC+01:00): UTC+01:00, daylight saving time (+60 min over standard UTC+00:00)");
+        let winter = run(json!({"operation": "offset", "timezone": "Australia/Adelaide", "time": "2025-07-01 12:00"})).await.unwrap();
+        assert!(winter.ends_with(": UTC+09:30, standard time"), "{}", winter);
+        let southern_summer = run(json!({"operation": "offset", "timezone": "Australia/Adelaide", "time": "2026-01-01 12:00"})).await.unwrap();
+        assert!(southern_summer.contains(": UTC+10:30, daylight saving time"), "{}", southern_summer);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let message = |result: Result<String, AgentError>| result.unwrap_err().to_string();
+        assert!(message(run(json!({"operation": "now"})).await).contains("Missing 'timezone'"));
+        assert!(message(run(json!({"operation": "convert", "to": "Asia/Tokyo"})).await).contains("Missing 'time'"));
+        assert!(message(run(json!({"operation": "convert", "time": "half past nine", "to": "UTC"})).await).contains("Invalid time 'half past nine'"));
+        assert!(message(run(json!({"operation": "now", "timezone": "PST"})).await).contains("IANA name"));
+        assert!(message(run(json!({"operation": "local"})).await).contains("Invalid operation"));
+    }
+}
@@ -0,0 +1,560 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
+use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::error::AgentError;
+use crate::tools::Tool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Temperature,
+    Area,
+    Speed,
+    Data,
+    Energy,
+    Time,
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Dimension::Length => "length",
+            Dimension::Mass => "mass",
+            Dimension::Volume => "volume",
+            Dimension::Temperature => "temperature",
+            Dimension::Area => "area",
+            Dimension::Speed => "speed",
+            Dimension::Data => "data size",
+            Dimension::Energy => "energy",
+            Dimension::Time => "time",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A unit as `base = value * factor + offset`; the first name is its symbol.
+struct BuiltinUnit {
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+    names: &'static [&'static str],
+}
+
+const fn unit(dimension: Dimension, factor: f64, names: &'static [&'static str]) -> BuiltinUnit {
+    BuiltinUnit { dimension, factor, offset: 0.0, names }
+}
+
+use Dimension::*;
+
+/// Bases: m, kg, L, K, m², m/s, B, J, s. The first unit of each dimension is
+/// its canonical unit.
+const BUILTIN: &[BuiltinUnit] = &[
+    unit(Length, 1.0, &["m", "meter", "meters", "metre", "metres"]),
+    unit(Length, 1e3, &["km", "kilometer", "kilometers", "kilometre", "kilometres"]),
+    unit(Length, 1e-2, &["cm", "centimeter", "centimeters", "centimetre", "centimetres"]),
+    unit(Length, 1e-3, &["mm", "millimeter", "millimeters", "millimetre", "millimetres"]),
+    unit(Length, 1e-6, &["µm", "um", "micrometer", "micrometers", "micron", "microns"]),
+    unit(Length, 1e-9, &["nm", "nanometer", "nanometers"]),
+    unit(Length, 0.0254, &["in", "inch", "inches", "\""]),
+    unit(Length, 0.3048, &["ft", "foot", "feet", "'"]),
+    unit(Length, 0.9144, &["yd", "yard", "yards"]),
+    unit(Length, 1609.344, &["mi", "mile", "miles"]),
+    unit(Length, 1852.0, &["nmi", "nautical mile", "nautical miles"]),
+    unit(Length, 149_597_870_700.0, &["au", "astronomical unit", "astronomical units"]),
+    unit(Length, 9_460_730_472_580_800.0, &["ly", "light year", "light years", "light-year", "light-years"]),
+
+    unit(Mass, 1.0, &["kg", "kilogram", "kilograms"]),
+    unit(Mass, 1e-3, &["g", "gram", "grams"]),
+    unit(Mass, 1e-6, &["mg", "milligram", "milligrams"]),
+    unit(Mass, 1e-9, &["µg", "ug", "mcg", "microgram", "micrograms"]),
+    unit(Mass, 1e3, &["t", "tonne", "tonnes", "metric ton", "metric tons"]),
+    unit(Mass, 0.45359237, &["lb", "lbs", "pound", "pounds"]),
+    unit(Mass, 0.028349523125, &["oz", "ounce", "ounces"]),
+    unit(Mass, 6.35029318, &["st", "stone", "stones"]),
+    unit(Mass, 907.18474, &["ton", "tons", "short ton", "short tons"]),
+    unit(Mass, 1016.0469088, &["long ton", "long tons"]),
+
+    unit(Volume, 1.0, &["L", "l", "liter", "liters", "litre", "litres"]),
+    unit(Volume, 1e-3, &["mL", "ml", "milliliter", "milliliters", "millilitre", "millilitres"]),
+    unit(Volume, 1e3, &["m³", "m3", "cubic meter", "cubic meters", "cubic metre", "cubic metres"]),
+    unit(Volume, 1e-3, &["cm³", "cm3", "cc", "cubic centimeter", "cubic centimeters"]),
+    unit(Volume, 3.785411784, &["gal", "gallon", "gallons", "us gallon", "us gallons"]),
+    unit(Volume, 4.54609, &["imp gal", "imperial gallon", "imperial gallons"]),
+    unit(Volume, 0.946352946, &["qt", "quart", "quarts"]),
+    unit(Volume, 0.473176473, &["pt", "pint", "pints"]),
+    unit(Volume, 0.2365882365, &["cup", "cups"]),
+    unit(Volume, 0.0295735295625, &["fl oz", "fluid ounce", "fluid ounces"]),
+    unit(Volume, 0.01478676478125, &["tbsp", "tablespoon", "tablespoons"]),
+    unit(Volume, 0.00492892159375, &["tsp", "teaspoon", "teaspoons"]),
+    unit(Volume, 28.316846592, &["ft³", "ft3", "cubic foot", "cubic feet"]),
+    unit(Volume, 0.016387064, &["in³", "in3", "cubic inch", "cubic inches"]),
+
+    unit(Temperature, 1.0, &["K", "kelvin", "kelvins"]),
+    BuiltinUnit { dimension: Temperature, factor: 1.0, offset: 273.15, names: &["°C", "C", "degC", "celsius", "degrees celsius", "centigrade"] },
+    BuiltinUnit { dimension: Temperature, factor: 5.0 / 9.0, offset: 459.67 * 5.0 / 9.0, names: &["°F", "F", "degF", "fahrenheit", "degrees fahrenheit"] },
+    unit(Temperature, 5.0 / 9.0, &["°R", "R", "degR", "rankine"]),
+
+    unit(Area, 1.0, &["m²", "m2", "sq m", "square meter", "square meters", "square metre", "square metres"]),
+    unit(Area, 1e6, &["km²", "km2", "sq km", "square kilometer", "square kilometers", "square kilometre", "square kilometres"]),
+    unit(Area, 1e-4, &["cm²", "cm2", "sq cm", "square centimeter", "square centimeters"]),
+    unit(Area, 0.09290304, &["ft²", "ft2", "sq ft", "square foot", "square feet"]),
+    unit(Area, 0.00064516, &["in²", "in2", "sq in", "square inch", "square inches"]),
+    unit(Area, 0.83612736, &["yd²", "yd2", "sq yd", "square yard", "square yards"]),
+    unit(Area, 2_589_988.110336, &["mi²", "mi2", "sq mi", "square mile", "square miles"]),
+    unit(Area, 4046.8564224, &["acre", "acres"]),
+    unit(Area, 1e4, &["ha", "hectare", "hectares"]),
+
+    unit(Speed, 1.0, &["m/s", "mps", "meters per second", "metres per second"]),
+    unit(Speed, 1.0 / 3.6, &["km/h", "kph", "kmh", "kilometers per hour", "kilometres per hour"]),
+    unit(Speed, 0.44704, &["mph", "mi/h", "miles per hour"]),
+    unit(Speed, 1852.0 / 3600.0, &["kn", "kt", "knot", "knots"]),
+    unit(Speed, 0.3048, &["ft/s", "fps", "feet per second"]),
+
+    unit(Data, 1.0, &["B", "byte", "bytes"]),
+    unit(Data, 0.125, &["bit", "bits", "b"]),
+    unit(Data, 1e3 / 8.0, &["kbit", "kb", "kilobit", "kilobits"]),
+    unit(Data, 1e6 / 8.0, &["Mbit", "Mb", "megabit", "megabits"]),
+    unit(Data, 1e9 / 8.0, &["Gbit", "Gb", "gigabit", "gigabits"]),
+    unit(Data, 1e12 / 8.0, &["Tbit", "Tb", "terabit", "terabits"]),
+    unit(Data, 1e3, &["kB", "KB", "kilobyte", "kilobytes"]),
+    unit(Data, 1e6, &["MB", "megabyte", "megabytes"]),
+    unit(Data, 1e9, &["GB", "gigabyte", "gigabytes"]),
+    unit(Data, 1e12, &["TB", "terabyte", "terabytes"]),
+    unit(Data, 1e15, &["PB", "petabyte", "petabytes"]),
+    unit(Data, 1024.0, &["KiB", "kibibyte", "kibibytes"]),
+    unit(Data, 1_048_576.0, &["MiB", "mebibyte", "mebibytes"]),
+    unit(Data, 1_073_741_824.0, &["GiB", "gibibyte", "gibibytes"]),
+    unit(Data, 1_099_511_627_776.0, &["TiB", "tebibyte", "tebibytes"]),
+    unit(Data, 1_125_899_906_842_624.0, &["PiB", "pebibyte", "pebibytes"]),
+
+    unit(Energy, 1.0, &["J", "joule", "joules"]),
+    unit(Energy, 1e3, &["kJ", "kilojoule", "kilojoules"]),
+    unit(Energy, 1e6, &["MJ", "megajoule", "megajoules"]),
+    unit(Energy, 4.184, &["cal", "calorie", "calories"]),
+    unit(Energy, 4184.0, &["kcal", "Cal", "kilocalorie", "kilocalories", "food calorie", "food calories"]),
+    unit(Energy, 3600.0, &["Wh", "watt hour", "watt hours"]),
+    unit(Energy, 3.6e6, &["kWh", "kilowatt hour", "kilowatt hours"]),
+    unit(Energy, 3.6e9, &["MWh", "megawatt hour", "megawatt hours"]),
+    unit(Energy, 1.602176634e-19, &["eV", "electronvolt", "electronvolts"]),
+    unit(Energy, 1055.05585262, &["BTU", "Btu", "british thermal unit", "british thermal units"]),
+
+    unit(Time, 1.0, &["s", "sec", "secs", "second", "seconds"]),
+    unit(Time, 1e-3, &["ms", "millisecond", "milliseconds"]),
+    unit(Time, 1e-6, &["µs", "us", "microsecond", "microseconds"]),
+    unit(Time, 1e-9, &["ns", "nanosecond", "nanoseconds"]),
+    unit(Time, 60.0, &["min", "mins", "minute", "minutes"]),
+    unit(Time, 3600.0, &["h", "hr", "hrs", "hour", "hours"]),
+    unit(Time, 86_400.0, &["d", "day", "days"]),
+    unit(Time, 604_800.0, &["wk", "week", "weeks"]),
+    unit(Time, 1_209_600.0, &["fortnight", "fortnights"]),
+    // Gregorian averages
+    unit(Time, 2_629_746.0, &["mo", "month", "months"]),
+    unit(Time, 31_556_952.0, &["yr", "year", "years"]),
+    unit(Time, 315_569_520.0, &["decade", "decades"]),
+    unit(Time, 3_155_695_200.0, &["century", "centuries"]),
+];
+
+/// A unit ready to convert with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub symbol: String,
+    pub dimension: Dimension,
+    factor: f64,
+    offset: f64,
+}
+
+impl Unit {
+    fn base_value(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    fn value_of(&self, value: f64) -> f64 {
+        (value - self.offset) / self.factor
+    }
+}
+
+impl From<&BuiltinUnit> for Unit {
+    fn from(builtin: &BuiltinUnit) -> Self {
+        Unit { symbol: builtin.names[0].to_string(), dimension: builtin.dimension, factor: builtin.factor, offset: builtin.offset }
+    }
+}
+
+/// A user unit defined as a multiple of another, e.g. `furlong = 220 yd`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomUnit {
+    pub names: Vec<String>,
+    pub equals: String,
+}
+
+/// The built-in units plus any loaded from a data file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnitTable {
+    /// Checked before the built-in units, so they can shadow one
+    #[serde(default)]
+    pub units: Vec<CustomUnit>,
+}
+
+/// Custom units may refer to each other, but not this deeply.
+const MAX_DEPTH: usize = 8;
+
+static QUANTITY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*([-+]?(?:\d[\d,_]*(?:\.\d*)?|\.\d+)(?:[eE][-+]?\d+)?)\s*(.*?)\s*$").unwrap()
+});
+/// Words that may separate a quantity from its target unit.
+static KEYWORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(?:to|into|in|as)\b|->").unwrap());
+/// Bit and byte symbols (`b`, `MB`, `GiB`), where the case of the b matters.
+static BIT_OR_BYTE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:[kKMGTP]i?)?[bB]$").unwrap());
+
+impl UnitTable {
+    /// Built-in units only.
+    pub fn builtin() -> Self {
+        Self::default()
+    }
+
+    pub fn from_yaml_file(path: &str) -> Result<Self, AgentError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AgentError::ConfigurationError(format!("Failed to read units file {}: {}", path, e)))?;
+        let table: Self = serde_yaml::from_str(&content)
+            .map_err(|e| AgentError::ConfigurationError(format!("Failed to parse units file {}: {}", path, e)))?;
+        for custom in &table.units {
+            let name = custom.names.first()
+                .ok_or_else(|| AgentError::ConfigurationError(format!("Units file {}: a unit has no names", path)))?;
+            table.unit(name).map_err(|e| AgentError::ConfigurationError(format!("Units file {}: {}: {}", path, name, e)))?;
+        }
+        Ok(table)
+    }
+
+    pub fn unit(&self, name: &str) -> Result<Unit, String> {
+        self.resolve(name.trim(), 0)
+    }
+
+    fn resolve(&self, name: &str, depth: usize) -> Result<Unit, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("unit '{}' is defined in terms of itself", name));
+        }
+        if let Some(custom) = find(&self.units, name, |c| c.names.iter().map(String::as_str)) {
+            let (amount, base) = split_quantity(&custom.equals)
+                .ok_or_else(|| format!("'{}' must be a number and a unit, e.g. '220 yd'", custom.equals))?;
+            let base = self.resolve(base, depth + 1)?;
+            if base.offset != 0.0 {
+                return Err(format!("cannot be defined from {}, an offset scale", base.symbol));
+            }
+            return Ok(Unit { symbol: custom.names[0].clone(), dimension: base.dimension, factor: amount * base.factor, offset: 0.0 });
+        }
+        find(BUILTIN, name, |u| u.names.iter().copied())
+            .map(Unit::from)
+            .ok_or_else(|| format!("Unknown unit '{}'", name))
+    }
+
+    /// Parses `3.5 ft to cm` (or `3.5 ft`, to the canonical unit) and converts.
+    pub fn convert_expression(&self, expression: &str) -> Result<Conversion, String> {
+        // `in` is also the inch, so try each keyword from the right and keep
+        // the first split where both sides are units
+        let splits: Vec<(&str, &str)> = KEYWORD.find_iter(expression)
+            .filter(|m| expression[..m.start()].ends_with(char::is_whitespace) && expression[m.end()..].starts_with(char::is_whitespace))
+            .map(|m| (&expression[..m.start()], expression[m.end()..].trim()))
+            .collect();
+        let is_quantity = |text: &str| split_quantity(text).is_some_and(|(_, unit)| self.unit(unit).is_ok());
+        let valid = splits.iter().rev().find(|(quantity, target)| is_quantity(quantity) && self.unit(target).is_ok());
+        // otherwise a bare quantity, or the last split so its error shows
+        let (quantity, target) = match valid.or(splits.last()) {
+            Some((quantity, target)) if valid.is_some() || !is_quantity(expression) => (*quantity, Some(*target)),
+            _ => (expression, None),
+        };
+        let (value, from) = split_quantity(quantity)
+            .ok_or_else(|| format!("Invalid expression '{}': expected a number, a unit and optionally 'to <unit>', e.g. '3.5 ft to cm'", expression))?;
+        self.convert(value, from, target)
+    }
+
+    /// Converts to `to`, or to the dimension's canonical unit.
+    pub fn convert(&self, value: f64, from: &str, to: Option<&str>) -> Result<Conversion, String> {
+        if !value.is_finite() {
+            return Err(format!("Invalid value {}", value));
+        }
+        let from = self.unit(from)?;
+        let to = match to {
+            Some(name) => self.unit(name)?,
+            None => canonical(from.dimension),
+        };
+        if from.dimension != to.dimension {
+            return Err(format!(
+                "Cannot convert {} ({}) to {} ({})",
+                from.symbol, from.dimension, to.symbol, to.dimension
+            ));
+        }
+        let base = from.base_value(value);
+        let mut result = to.value_of(base);
+        // offset scales leave float noise where a value lands on zero
+        if result.abs() * to.factor < 1e-12 * (base.abs() + to.offset.abs()) {
+            result = 0.0;
+        }
+        if from.dimension == Temperature && base < -1e-9 {
+            return Err(format!("{} {} is below absolute zero", format_number(value), from.symbol));
+        }
+        Ok(Conversion { value, from, result, to })
+    }
+}
+
+/// Exact name first, then ignoring case when that picks out a single unit;
+/// bit and byte symbols keep the case of their b, so `Gb` never becomes `GB`.
+fn find<'a, T, N: Iterator<Item = &'a str>>(units: &'a [T], name: &str, names: impl Fn(&'a T) -> N) -> Option<&'a T> {
+    if let Some(exact) = units.iter().find(|u| names(u).any(|n| n == name)) {
+        return Some(exact);
+    }
+    let mut folded = units.iter().filter(|u| names(u).any(|n| n.eq_ignore_ascii_case(name) && !(BIT_OR_BYTE.is_match(n) && n.ends_with('b') != name.ends_with('b'))));
+    let first = folded.next()?;
+    folded.next().is_none().then_some(first)
+}
+
+fn canonical(dimension: Dimension) -> Unit {
+    BUILTIN.iter().find(|u| u.dimension == dimension).map(Unit::from).unwrap_or_else(|| unreachable!())
+}
+
+/// `3.5 ft` into 3.5 and "ft"; thousands separators are allowed.
+fn split_quantity(text: &str) -> Option<(f64, &str)> {
+    let caps = QUANTITY.captures(text)?;
+    let number: String = caps.get(1)?.as_str().chars().filter(|c| *c != ',' && *c != '_').collect();
+    let unit = caps.get(2)?.as_str();
+    if unit.is_empty() {
+        return None;
+    }
+    Some((number.parse().ok()?, unit))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub value: f64,
+    pub from: Unit,
+    pub result: f64,
+    pub to: Unit,
+}
+
+impl fmt::Display for Conversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} = {} {}", format_number(self.value), self.from.symbol, format_number(self.result), self.to.symbol)
+    }
+}
+
+/// Ten significant digits, so float noise (0.30000000000000004) disappears.
+pub fn format_number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    if !(-4..15).contains(&magnitude) {
+        let scientific = format!("{:.9e}", value);
+        let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+        let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+        return format!("{}e{}", mantissa, exponent);
+    }
+    let decimals = (9 - magnitude).max(0) as usize;
+    let fixed = format!("{:.*}", decimals, value);
+    let trimmed = if fixed.contains('.') { fixed.trim_end_matches('0').trim_end_matches('.') } else { &fixed };
+    if trimmed == "-0" { "0".to_string() } else { trimmed.to_string() }
+}
+
+pub struct UnitsTool {
+    table: UnitTable,
+}
+
+impl UnitsTool {
+    pub fn new() -> Self {
+        Self::with_table(UnitTable::builtin())
+    }
+
+    pub fn with_table(table: UnitTable) -> Self {
+        Self { table }
+    }
+
+    fn error(&self, message: impl Into<String>) -> AgentError {
+        AgentError::ToolError {
+            tool_name: self.name().to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl Default for UnitsTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Tool for UnitsTool {
+    fn name(&self) -> &'static str {
+        "units"
+    }
+
+    fn description(&self) -> &'static str {
+        "Convert between units of length, mass, volume, temperature, area, speed, data size (SI and IEC), energy and time. Args: { expression?: string } or { value: number, from: string, to?: string }"
+    }
+
+    fn json_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "expression": {
+                    "type": "string",
+                    "description": "A conversion such as '3.5 ft to cm' or '98.6 °F in °C'"
+                },
+                "value": {
+                    "type": "number",
+                    "description": "Amount to convert, when not using expression"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Unit of value, e.g. 'mi', 'lb', 'GiB'"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Target unit; defaults to the canonical (SI) unit"
+                }
+            },
+            "required": []
+        })
+    }
+
+    async fn run(&self, args: HashMap<String, Value>) -> Result<String, AgentError> {
+        let expression = args.get("expression").and_then(|v| v.as_str());
+        let conversion = match (expression, args.get("value")) {
+            (Some(expression), _) => self.table.convert_expression(expression),
+            (None, Some(value)) => {
+                let value = value.as_f64().ok_or_else(|| self.error(format!("'value' must be a number (got {})", value)))?;
+                let from = args.get("from")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| self.error("Missing 'from' parameter"))?;
+                self.table.convert(value, from, args.get("to").and_then(|v| v.as_str()))
+            }
+            (None, None) => return Err(self.error("Provide 'expression' (e.g. '3.5 ft to cm') or 'value' and 'from'")),
+        };
+        conversion.map(|c| c.to_string()).map_err(|e| self.error(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(expression: &str) -> Result<String, String> {
+        UnitTable::builtin().convert_expression(expression).map(|c| c.to_string())
+    }
+
+    #[test]
+    fn test_conversions() {
+        for (expression, expected) in [
+            ("3.5 ft to cm", "3.5 ft = 106.68 cm"),
+            ("1 mile in km", "1 mi = 1.609344 km"),
+            ("10 lb to kg", "10 lb = 4.5359237 kg"),
+            ("2 cups to mL", "2 cup = 473.176473 mL"),
+            ("1 acre to sq ft", "1 acre = 43560 ft²"),
+            ("60 mph to km/h", "60 mph = 96.56064 km/h"),
+            ("1 kWh to kcal", "1 kWh = 860.4206501 kcal"),
+            ("1,000,000 seconds to days", "1000000 s = 11.57407407 d"),
+            ("1 year to days", "1 yr = 365.2425 d"),
+            ("5 light years to km", "5 ly = 47303652362904 km"),
+            ("1 eV to J", "1 eV = 1.602176634e-19 J"),
+            ("12 in to cm", "12 in = 30.48 cm"),
+            ("5 in in cm", "5 in = 12.7 cm"),
+            ("1 Gb to MB", "1 Gbit = 125 MB"),
+            ("1 gb to mb", "1 Gbit = 1000 Mbit"),
+        ] {
+            assert_eq!(convert(expression).unwrap(), expected, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn test_temperature_offsets() {
+        assert_eq!(convert("98.6 °F to °C").unwrap(), "98.6 °F = 37 °C");
+        assert_eq!(convert("-40 C to F").unwrap(), "-40 °C = -40 °F");
+        assert_eq!(convert("0 celsius to kelvin").unwrap(), "0 °C = 273.15 K");
+        assert_eq!(convert("100 fahrenheit").unwrap(), "100 °F = 310.9277778 K");
+        assert_eq!(convert("491.67 R to C").unwrap(), "491.67 °R = 0 °C");
+        assert_eq!(convert("-300 C to K").unwrap_err(), "-300 °C is below absolute zero");
+        assert_eq!(convert("-500 F to C").unwrap_err(), "-500 °F is below absolute zero");
+    }
+
+    #[test]
+    fn test_data_sizes() {
+        assert_eq!(convert("1 GiB to MB").unwrap(), "1 GiB = 1073.741824 MB");
+        assert_eq!(convert("1 GB to MiB").unwrap(), "1 GB = 953.6743164 MiB");
+        assert_eq!(convert("100 Mbit to MB").unwrap(), "100 Mbit = 12.5 MB");
+        assert_eq!(convert("2 TB").unwrap(), "2 TB = 2000000000000 B");
+    }
+
+    #[test]
+    fn test_unit_names() {
+        let table = UnitTable::builtin();
+        assert_eq!(table.unit("Feet").unwrap().symbol, "ft");
+        assert_eq!(table.unit("Cal").unwrap().symbol, "kcal");
+        assert_eq!(table.unit("cal").unwrap().symbol, "cal");
+        // "CAL" could be either
+        assert_eq!(table.unit("CAL").unwrap_err(), "Unknown unit 'CAL'");
+        assert_eq!(table.unit("furlong").unwrap_err(), "Unknown unit 'furlong'");
+    }
+
+    #[test]
+    fn test_dimension_mismatch() {
+        assert_eq!(convert("3 ft to kg").unwrap_err(), "Cannot convert ft (length) to kg (mass)");
+        assert_eq!(convert("5 GB to hours").unwrap_err(), "Cannot convert GB (data size) to h (time)");
+        assert!(convert("three feet to cm").unwrap_err().starts_with("Invalid expression"));
+        assert!(convert("12 to cm").unwrap_err().starts_with("Invalid expression"));
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0.1 + 0.2), "0.3");
+        assert_eq!(format_number(-0.000000001), "-1e-9");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_number(123456789012.0), "123456789012");
+    }
+
+    #[tokio::test]
+    async fn test_tool_arguments() {
+        let tool = UnitsTool::new();
+        let run = |args: Value| tool.run(serde_json::from_value(args).unwrap());
+        assert_eq!(run(json!({"expression": "3.5 ft to cm"})).await.unwrap(), "3.5 ft = 106.68 cm");
+        assert_eq!(run(json!({"value": 26.2, "from": "miles", "to": "km"})).await.unwrap(), "26.2 mi = 42.1648128 km");
+        assert_eq!(run(json!({"value": 3, "from": "ft"})).await.unwrap(), "3 ft = 0.9144 m");
+
+        let err = run(json!({"value": 3, "from": "ft", "to": "L"})).await.unwrap_err();
+        assert!(matches!(&err, AgentError::ToolError { tool_name, message } if tool_name == "units" && message == "Cannot convert ft (length) to L (volume)"), "{}", err);
+        assert!(run(json!({"value": "3", "from": "ft"})).await.unwrap_err().to_string().contains("'value' must be a number"));
+        assert!(run(json!({})).await.unwrap_err().to_string().contains("Provide 'expression'"));
+    }
+
+    #[test]
+    fn test_data_file_adds_units() {
+        let dir = std::env::temp_dir().join(format!("cvc_units_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("units.yaml");
+        std::fs::write(&path, r#"
+units:
+  - {names: [furlong, furlongs], equals: "220 yd"}
+  - {names: [fortnight-furlong], equals: "0.001 furlong"}
+  - {names: [smoot, smoots], equals: "1.7018 m"}
+"#).unwrap();
+        let table = UnitTable::from_yaml_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(table.convert_expression("1 furlong to m").unwrap().to_string(), "1 furlong = 201.168 m");
+        assert_eq!(table.convert_expression("1000 fortnight-furlong to ft").unwrap().to_string(), "1000 fortnight-furlong = 660 ft");
+        assert_eq!(table.convert_expression("364.4 smoots to ft").unwrap().to_string(), "364.4 smoot = 2034.566667 ft");
+
+        for (bad, message) in [
+            ("units:\n  - {names: [warmth], equals: \"2 °C\"}\n", "warmth: cannot be defined from °C, an offset scale"),
+            ("units:\n  - {names: [loop], equals: \"2 loop\"}\n", "loop: unit 'loop' is defined in terms of itself"),
+            ("units:\n  - {names: [blob], equals: \"2 blobs\"}\n", "blob: Unknown unit 'blobs'"),
+            ("units:\n  - {names: [blob], equals: \"many\"}\n", "blob: 'many' must be a number and a unit"),
+        ] {
+            std::fs::write(&path, bad).unwrap();
+            let err = UnitTable::from_yaml_file(path.to_str().unwrap()).unwrap_err().to_string();
+            assert!(err.contains(message), "{}", err);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
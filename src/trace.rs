@@ -0,0 +1,77 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::policy::PolicyDecision;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum TraceEvent {
+    PolicyDecision {
+        timestamp: String,
+        #[serde(flatten)]
+        decision: PolicyDecision,
+    },
+    ToolCall {
+        timestamp: String,
+        #[serde(flatten)]
+        call: ToolCallRecord,
+    },
+}
+
+/// One tool call the model asked for and how it ended.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub tool_name: String,
+    pub arguments: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// Set when the call was denied, its arguments didn't parse, or the tool failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Everything notable that happened during one agent run, in order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunTrace {
+    pub events: Vec<TraceEvent>,
+    /// Time spent queued on the client-side rate limiter, not doing work
+    #[serde(default)]
+    pub rate_limit_wait: Duration,
+    /// Requests sent to the model
+    #[serde(default)]
+    pub round_trips: usize,
+}
+
+impl RunTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_decision(&mut self, at: DateTime<Utc>, decision: PolicyDecision) {
+        self.events.push(TraceEvent::PolicyDecision {
+            timestamp: at.to_rfc3339(),
+            decision,
+        });
+    }
+
+    pub fn record_tool_call(&mut self, at: DateTime<Utc>, call: ToolCallRecord) {
+        self.events.push(TraceEvent::ToolCall {
+            timestamp: at.to_rfc3339(),
+            call,
+        });
+    }
+
+    pub fn decisions(&self) -> impl Iterator<Item = &PolicyDecision> {
+        self.events.iter().filter_map(|event| match event {
+            TraceEvent::PolicyDecision { decision, .. } => Some(decision),
+            _ => None,
+        })
+    }
+
+    pub fn tool_calls(&self) -> impl Iterator<Item = &ToolCallRecord> {
+        self.events.iter().filter_map(|event| match event {
+            TraceEvent::ToolCall { call, .. } => Some(call),
+            _ => None,
+        })
+    }
+}
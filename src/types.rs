@@ -67,7 +67,7 @@ impl Message {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.content.as_ref().map_or(true, |c| c.trim().is_empty()) && 
+        self.content.as_ref().is_none_or(|c| c.trim().is_empty()) && 
         self.tool_calls.is_none()
     }
 }
@@ -98,3 +98,17 @@ pub struct ToolSpec {
     pub description: String,
     pub parameters: serde_json::Value,
 }
+
+/// Request sampling settings. Temperature defaults to 0 so runs are as
+/// repeatable as the provider allows.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sampling {
+    #[serde(default)]
+    pub temperature: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
@@ -0,0 +1,179 @@
+use countvoncount::benchmark::matrix::MatrixConfig;
+use countvoncount::benchmark::{parse_results, ArmMatrix, BenchmarkOptions, CaseSelection, ResumeOptions};
+use countvoncount::{resume_benchmarks, run_benchmarks, RunOptions};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Minimal OpenAI-compatible endpoint that always answers "The answer is 4".
+async fn mock_openai() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else { return };
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // read headers, then the body they announce
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    if n == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text.lines()
+                            .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap_or(0)))
+                            .unwrap_or(0);
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+                let body = r#"{"choices":[{"message":{"role":"assistant","content":"The answer is 4"}}]}"#;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(), body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    (base_url, requests)
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cvc-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+const MOCK_KEY: &str = "test-key";
+
+/// Options running `cases_yaml` on one mock model, with and without tools,
+/// in a scratch directory; results go to `results.jsonl` there.
+async fn mock_benchmark(name: &str, cases_yaml: &str) -> (BenchmarkOptions, Arc<AtomicUsize>) {
+    let (base_url, requests) = mock_openai().await;
+    let dir = scratch_dir(name);
+
+    let cases = dir.join("cases.yaml");
+    std::fs::write(&cases, cases_yaml).unwrap();
+    let matrix: MatrixConfig = serde_yaml::from_str(&format!(
+        "providers:\n  mock: {{base_url: \"{}\"}}\nmodels: [\"mock:m\"]\n", base_url
+    )).unwrap();
+
+    let options = BenchmarkOptions {
+        cases: CaseSelection { paths: vec![cases], ..Default::default() },
+        matrix: Some(ArmMatrix::from_config(matrix).unwrap()),
+        output: Some(dir.join("results.jsonl")),
+        run: RunOptions { api_key: Some(MOCK_KEY.to_string()), ..Default::default() },
+        ..Default::default()
+    };
+    (options, requests)
+}
+
+fn resume_options() -> ResumeOptions {
+    ResumeOptions { api_key: Some(MOCK_KEY.to_string()), ..Default::default() }
+}
+
+const TWO_CASES: &str = "tests:\n  - {id: two, prompt: What is 2 + 2?, expected: \"4\"}\n  - {id: ten, prompt: What is 5 + 5?, expected: \"10\"}\n";
+
+#[tokio::test]
+async fn test_interrupted_run_resumes_missing_arms() {
+    let (options, requests) = mock_benchmark("resume", TWO_CASES).await;
+    let output = options.output.clone().unwrap();
+    let dir = output.parent().unwrap().to_path_buf();
+    run_benchmarks(BenchmarkOptions { concurrency: 2, ..options }).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+    assert!(dir.join("results.manifest.json").exists());
+
+    let results = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    let order: Vec<(&str, &str)> = results.iter().map(|r| (r.id.as_str(), r.arm.as_str())).collect();
+    assert_eq!(order, vec![("two", "mock:m/tools"), ("two", "mock:m/no-tools"), ("ten", "mock:m/tools"), ("ten", "mock:m/no-tools")]);
+    assert_eq!(results[0].correct, Some(true));
+    assert_eq!(results[2].correct, Some(false));
+    assert_eq!((results[0].round_trips, results[0].tool_calls.len(), results[0].max_loops_exceeded), (1, 0, false));
+
+    // keep one answer plus half of another, as if killed mid-write
+    let content = std::fs::read_to_string(&output).unwrap();
+    let mut lines = content.lines();
+    let first = lines.next().unwrap();
+    let torn = &lines.next().unwrap()[..20];
+    std::fs::write(&output, format!("{}\n{}", first, torn)).unwrap();
+
+    let summary = resume_benchmarks(output.clone(), resume_options()).await.unwrap();
+    assert!(summary.contains("Resumed: 1 of 4 answers already done"), "{}", summary);
+    assert_eq!(requests.load(Ordering::SeqCst), 7);
+
+    let resumed = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(resumed.len(), 4);
+    assert_eq!(resumed[0].timestamp, results[0].timestamp);
+    assert_eq!(resumed[3].arm, "mock:m/no-tools");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_resume_reruns_only_failed_requests_and_keeps_unplanned_answers() {
+    let (options, requests) = mock_benchmark("resume-edited", TWO_CASES).await;
+    let output = options.output.clone().unwrap();
+    let dir = output.parent().unwrap().to_path_buf();
+    let cases = options.cases.paths[0].clone();
+    run_benchmarks(options).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+    // one arm hit the loop limit (a real result), one request failed
+    let mut results = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    results[0].answer = "Error: Maximum loops exceeded".to_string();
+    results[0].max_loops_exceeded = true;
+    results[1].answer = "Error: connection refused".to_string();
+    let lines: Vec<String> = results.iter().map(|r| serde_json::to_string(r).unwrap()).collect();
+    std::fs::write(&output, lines.join("\n") + "\n").unwrap();
+    // and the "ten" case was dropped from the case file
+    std::fs::write(&cases, "tests:\n  - {id: two, prompt: What is 2 + 2?, expected: \"4\"}\n").unwrap();
+
+    let summary = resume_benchmarks(output.clone(), resume_options()).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 5);
+    assert!(summary.contains("Kept 2 earlier answer(s) for cases not in this run"), "{}", summary);
+
+    let resumed = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    let order: Vec<(&str, &str)> = resumed.iter().map(|r| (r.id.as_str(), r.arm.as_str())).collect();
+    assert_eq!(order, vec![("two", "mock:m/tools"), ("two", "mock:m/no-tools"), ("ten", "mock:m/tools"), ("ten", "mock:m/no-tools")]);
+    assert!(resumed[0].max_loops_exceeded);
+    assert_eq!(resumed[1].answer, "The answer is 4");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_repeat_runs_each_case_per_arm() {
+    let (options, requests) = mock_benchmark("repeat", "tests:\n  - {id: two, prompt: What is 2 + 2?, expected: \"4\"}\n").await;
+    let output = options.output.clone().unwrap();
+    let dir = output.parent().unwrap().to_path_buf();
+    let summary = run_benchmarks(BenchmarkOptions { concurrency: 3, repeat: 3, ..options }).await.unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 6);
+    assert!(summary.contains("Benchmark: What is 2 + 2? (run 3/3)"), "{}", summary);
+    assert!(summary.contains("both 3, only first 0, only second 0, neither 0"), "{}", summary);
+    assert!(summary.contains("[not significant: accuracy"), "{}", summary);
+
+    let results = parse_results(&std::fs::read_to_string(&output).unwrap()).unwrap();
+    let order: Vec<(usize, &str)> = results.iter().map(|r| (r.repeat, r.arm.as_str())).collect();
+    assert_eq!(order, vec![
+        (0, "mock:m/tools"), (0, "mock:m/no-tools"),
+        (1, "mock:m/tools"), (1, "mock:m/no-tools"),
+        (2, "mock:m/tools"), (2, "mock:m/no-tools"),
+    ]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
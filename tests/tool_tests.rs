@@ -1,4 +1,4 @@
-use countvoncount::tools::create_default_registry;
+use countvoncount::tools::{create_default_registry, RunContext};
 use serde_json::json;
 use std::collections::HashMap;
 
@@ -128,3 +128,145 @@ async fn test_empty_calculator_expression() {
     let error_msg = result.unwrap_err().to_string();
     assert!(error_msg.contains("cannot be empty"));
 }
+
+#[tokio::test]
+async fn test_date_math_difference() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("difference"));
+    args.insert("date".to_string(), json!("2025-03-17"));
+    args.insert("end_date".to_string(), json!("2025-11-27"));
+
+    let result = registry.execute_tool("date_math", args).await.unwrap();
+    assert_eq!(result, "255");
+}
+
+#[tokio::test]
+async fn test_date_math_rejects_loose_dates() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("day_of_week"));
+    args.insert("date".to_string(), json!("March 17"));
+
+    let error_msg = registry.execute_tool("date_math", args).await.unwrap_err().to_string();
+    assert!(error_msg.contains("expected YYYY-MM-DD"));
+}
+
+#[tokio::test]
+async fn test_holidays_date() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("date"));
+    args.insert("name".to_string(), json!("Thanksgiving"));
+    args.insert("year".to_string(), json!(2025));
+
+    let result = registry.execute_tool("holidays", args).await.unwrap();
+    assert_eq!(result, "2025-11-27 (Thursday)");
+}
+
+#[tokio::test]
+async fn test_holidays_next_from_date() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("next"));
+    args.insert("name".to_string(), json!("Easter"));
+    args.insert("from".to_string(), json!("2025-08-21"));
+
+    let result = registry.execute_tool("holidays", args).await.unwrap();
+    assert_eq!(result, "2026-04-05 (Sunday), 227 days from 2025-08-21");
+}
+
+#[tokio::test]
+async fn test_astronomy_season() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("season"));
+    args.insert("event".to_string(), json!("winter_solstice"));
+    args.insert("year".to_string(), json!(2025));
+
+    let result = registry.execute_tool("astronomy", args).await.unwrap();
+    assert_eq!(result, "December solstice 2025: 2025-12-21 15:03 UTC");
+}
+
+#[tokio::test]
+async fn test_timezone_convert() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("convert"));
+    args.insert("time".to_string(), json!("2025-08-22 09:00"));
+    args.insert("from".to_string(), json!("America/Chicago"));
+    args.insert("to".to_string(), json!("Asia/Tokyo"));
+
+    let result = registry.execute_tool("timezone", args).await.unwrap();
+    assert!(result.ends_with("is 2025-08-22 23:00 JST (UTC+09:00) in Asia/Tokyo"));
+}
+
+#[tokio::test]
+async fn test_units_conversion() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("expression".to_string(), json!("3.5 ft to cm"));
+    let result = registry.execute_tool("units", args).await.unwrap();
+    assert_eq!(result, "3.5 ft = 106.68 cm");
+
+    let mut args = HashMap::new();
+    args.insert("value".to_string(), json!(5));
+    args.insert("from".to_string(), json!("kg"));
+    args.insert("to".to_string(), json!("mph"));
+    let err = registry.execute_tool("units", args).await.unwrap_err();
+    assert!(err.to_string().contains("Cannot convert kg (mass) to mph (speed)"));
+}
+
+#[tokio::test]
+async fn test_calculator_variables_within_run() {
+    let registry = create_default_registry();
+    let run = RunContext::default();
+
+    let mut args = HashMap::new();
+    args.insert("expression".to_string(), json!("steps = 10000 * 2.5"));
+    registry.execute_tool_in("calculator", args, &run).await.unwrap();
+
+    let mut args = HashMap::new();
+    args.insert("expression".to_string(), json!("steps / 5280"));
+    args.insert("precision".to_string(), json!(2));
+    let result = registry.execute_tool_in("calculator", args.clone(), &run).await.unwrap();
+    assert_eq!(result, "4.73 (rounded from 625/132)");
+
+    // outside the run the variable is unknown
+    let err = registry.execute_tool("calculator", args).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown name 'steps'"));
+}
+
+#[tokio::test]
+async fn test_statistics_describe() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("describe"));
+    args.insert("values".to_string(), json!([12, 15, 11, 18, 15, 21]));
+    args.insert("measures".to_string(), json!(["mean", "median", "mode"]));
+
+    let result = registry.execute_tool("statistics", args).await.unwrap();
+    assert_eq!(result, "mean: 15.33333333\nmedian: 15\nmode: 15");
+}
+
+#[tokio::test]
+async fn test_finance_loan_payment() {
+    let registry = create_default_registry();
+
+    let mut args = HashMap::new();
+    args.insert("operation".to_string(), json!("loan_payment"));
+    args.insert("principal".to_string(), json!("$285,000"));
+    args.insert("rate".to_string(), json!(6.5));
+    args.insert("years".to_string(), json!(30));
+
+    let result = registry.execute_tool("finance", args).await.unwrap();
+    assert!(result.starts_with("Payment: 1801.39 per period (360 payments"), "{}", result);
+}